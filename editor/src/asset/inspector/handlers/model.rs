@@ -42,8 +42,14 @@ impl ImportOptionsHandler for ModelImportOptionsHandler {
 
     fn handle_property_changed(&mut self, property_changed: &PropertyChanged) {
         if let FieldKind::Object(ref args) = property_changed.value {
-            if let ModelImportOptions::MATERIAL_SEARCH_OPTIONS = property_changed.name.as_ref() {
-                self.options.material_search_options = args.cast_clone().unwrap()
+            match property_changed.name.as_ref() {
+                ModelImportOptions::MATERIAL_SEARCH_OPTIONS => {
+                    self.options.material_search_options = args.cast_clone().unwrap()
+                }
+                ModelImportOptions::ANIMATION_COMPRESSION_TOLERANCE => {
+                    self.options.animation_compression_tolerance = args.cast_clone().unwrap()
+                }
+                _ => (),
             }
         }
     }
@@ -1,6 +1,17 @@
 use crate::algebra::{Vector3, Vector4};
 use crate::visitor::{Visit, VisitResult, Visitor};
 
+/// 32-bit RGBA color.
+///
+/// # Alpha
+///
+/// Color uses *straight* (non-premultiplied) alpha, which means that color components are
+/// independent from alpha. Renderers blend with straight alpha as well (`src * a + dst * (1 - a)`),
+/// use [`Color::premultiply`] when you need to pass a color to a premultiplied alpha pipeline.
+///
+/// # HSV
+///
+/// Use [`Hsv`] and its `From` implementations to convert a color to HSV and back.
 #[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
 #[repr(C)]
 pub struct Color {
@@ -281,6 +292,38 @@ impl Color {
         }
     }
 
+    /// Multiplies color components by alpha. Premultiplied colors are blended correctly across
+    /// nested translucent layers, without dark fringes on edges.
+    #[must_use]
+    #[inline]
+    pub fn premultiply(self) -> Self {
+        let a = self.a as u32;
+        Self {
+            r: ((self.r as u32 * a + 127) / 255) as u8,
+            g: ((self.g as u32 * a + 127) / 255) as u8,
+            b: ((self.b as u32 * a + 127) / 255) as u8,
+            a: self.a,
+        }
+    }
+
+    /// Divides color components by alpha, this is an inverse of [`Color::premultiply`]. Fully
+    /// transparent colors are left untouched, since there is no way to restore color components.
+    #[must_use]
+    #[inline]
+    pub fn unpremultiply(self) -> Self {
+        if self.a == 0 {
+            self
+        } else {
+            let a = self.a as u32;
+            Self {
+                r: ((self.r as u32 * 255 + a / 2) / a).min(255) as u8,
+                g: ((self.g as u32 * 255 + a / 2) / a).min(255) as u8,
+                b: ((self.b as u32 * 255 + a / 2) / a).min(255) as u8,
+                a: self.a,
+            }
+        }
+    }
+
     #[inline]
     pub fn with_new_alpha(self, a: u8) -> Self {
         Self {
@@ -304,3 +347,44 @@ impl Visit for Color {
         visitor.leave_region()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::color::Color;
+
+    #[test]
+    fn premultiply_opaque_and_transparent() {
+        let color = Color::opaque(200, 100, 50);
+        assert_eq!(color.premultiply(), color);
+        assert_eq!(color.unpremultiply(), color);
+
+        let transparent = Color::from_rgba(200, 100, 50, 0);
+        assert_eq!(transparent.premultiply(), Color::from_rgba(0, 0, 0, 0));
+        // Color components cannot be restored.
+        assert_eq!(transparent.unpremultiply(), transparent);
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        assert_eq!(
+            Color::from_rgba(200, 100, 50, 128).premultiply(),
+            Color::from_rgba(100, 50, 25, 128)
+        );
+
+        for a in 1..=255u8 {
+            for c in 0..=255u8 {
+                // Precision of premultiplied color depends on alpha, so the error of the round
+                // trip is at most a half of the step.
+                let restored = Color::from_rgba(c, c, c, a).premultiply().unpremultiply();
+                assert_eq!(restored.a, a);
+                assert!((restored.r as i32 - c as i32).abs() * 2 * a as i32 <= 255 + a as i32);
+
+                // Premultiplied colors survive the round trip exactly.
+                if c <= a {
+                    let premultiplied = Color::from_rgba(c, c, c, a);
+                    assert_eq!(premultiplied.unpremultiply().premultiply(), premultiplied);
+                }
+            }
+        }
+    }
+}
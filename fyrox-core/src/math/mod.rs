@@ -64,6 +64,7 @@ where
         }
     }
 
+    /// Returns `true` if the point is inside the rectangle, borders are included.
     pub fn contains(&self, pt: Vector2<T>) -> bool {
         pt.x >= self.position.x
             && pt.x <= self.position.x + self.size.x
@@ -146,6 +147,52 @@ where
         }
     }
 
+    /// Returns `true` if the other rectangle is fully inside the rectangle.
+    pub fn contains_rect(&self, other: Rect<T>) -> bool {
+        self.contains(other.position) && self.contains(other.right_bottom_corner())
+    }
+
+    /// Returns common part of two rectangles, or `None` if the rectangles do not intersect.
+    pub fn intersection(&self, other: Rect<T>) -> Option<Rect<T>> {
+        fn min<T: PartialOrd>(a: T, b: T) -> T {
+            if a < b {
+                a
+            } else {
+                b
+            }
+        }
+
+        fn max<T: PartialOrd>(a: T, b: T) -> T {
+            if a > b {
+                a
+            } else {
+                b
+            }
+        }
+
+        let self_right_bottom = self.right_bottom_corner();
+        let other_right_bottom = other.right_bottom_corner();
+
+        let left = max(self.position.x, other.position.x);
+        let top = max(self.position.y, other.position.y);
+        let right = min(self_right_bottom.x, other_right_bottom.x);
+        let bottom = min(self_right_bottom.y, other_right_bottom.y);
+
+        if left <= right && top <= bottom {
+            Some(Rect::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
+    /// Returns smallest rectangle that contains both rectangles.
+    #[must_use = "this method creates new instance of rect"]
+    pub fn union(&self, other: Rect<T>) -> Rect<T> {
+        let mut union = *self;
+        union.extend_to_contain(other);
+        union
+    }
+
     #[must_use = "this method creates new instance of rect"]
     pub fn translate(&self, translation: Vector2<T>) -> Self {
         Self {
@@ -183,21 +230,30 @@ where
         distance_squared < (radius * radius)
     }
 
+    /// Extends the rectangle so it will contain the other rectangle. Left-top corner of the result
+    /// is the minimum of left-top corners and right-bottom corner is the maximum of right-bottom
+    /// corners of both rectangles.
     pub fn extend_to_contain(&mut self, other: Rect<T>) {
+        // Right-bottom corner must be taken before the position is moved, otherwise right and
+        // bottom edges of the rectangle would be lost.
+        let mut right_bottom = self.right_bottom_corner();
+        let other_right_bottom = other.right_bottom_corner();
+        if other_right_bottom.x > right_bottom.x {
+            right_bottom.x = other_right_bottom.x;
+        }
+        if other_right_bottom.y > right_bottom.y {
+            right_bottom.y = other_right_bottom.y;
+        }
         if other.position.x < self.position.x {
             self.position.x = other.position.x;
         }
         if other.position.y < self.position.y {
             self.position.y = other.position.y;
         }
-        let self_right_bottom_corner = self.right_bottom_corner();
-        let other_right_bottom_corner = other.right_bottom_corner();
-        if other_right_bottom_corner.x > self_right_bottom_corner.x {
-            self.size.x += other_right_bottom_corner.x - self_right_bottom_corner.x;
-        }
-        if other_right_bottom_corner.y > self_right_bottom_corner.y {
-            self.size.y += other_right_bottom_corner.y - self_right_bottom_corner.y;
-        }
+        self.size = Vector2::new(
+            right_bottom.x - self.position.x,
+            right_bottom.y - self.position.y,
+        );
     }
 
     #[inline(always)]
@@ -1026,6 +1082,9 @@ pub trait Vector2Ext {
 
     fn per_component_min(&self, other: &Self) -> Self;
     fn per_component_max(&self, other: &Self) -> Self;
+
+    /// Clamps each component of the vector between respective components of `min` and `max`.
+    fn per_component_clamp(&self, min: &Self, max: &Self) -> Self;
 }
 
 impl Vector2Ext for Vector2<f32> {
@@ -1044,6 +1103,11 @@ impl Vector2Ext for Vector2<f32> {
     fn per_component_max(&self, other: &Self) -> Self {
         Self::new(self.x.max(other.x), self.y.max(other.y))
     }
+
+    #[inline]
+    fn per_component_clamp(&self, min: &Self, max: &Self) -> Self {
+        Self::new(clampf(self.x, min.x, max.x), clampf(self.y, min.y, max.y))
+    }
 }
 
 /// Returns rotation quaternion that represents rotation basis with Z axis aligned on `vec`.
@@ -1065,6 +1129,7 @@ mod test {
     use crate::algebra::Vector2;
     use crate::math::Rect;
     use crate::math::SmoothAngle;
+    use crate::math::Vector2Ext;
 
     #[test]
     fn ray_rect_intersection() {
@@ -1099,4 +1164,37 @@ mod test {
             println!("{}", angle.update(1.0).angle().to_degrees());
         }
     }
+
+    #[test]
+    fn vector2_ext() {
+        let a = Vector2::new(1.0, 5.0);
+        let b = Vector2::new(3.0, 2.0);
+        assert_eq!(a.per_component_min(&b), Vector2::new(1.0, 2.0));
+        assert_eq!(a.per_component_max(&b), Vector2::new(3.0, 5.0));
+        assert_eq!(
+            a.per_component_clamp(&Vector2::new(2.0, 0.0), &Vector2::new(4.0, 4.0)),
+            Vector2::new(2.0, 4.0)
+        );
+        // Per-component multiplication is provided by nalgebra.
+        assert_eq!(a.component_mul(&b), Vector2::new(3.0, 10.0));
+
+        let mut c = Vector2::new(0.0, 0.0);
+        c.follow(&Vector2::new(2.0, 4.0), 0.5);
+        assert_eq!(c, Vector2::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn rect_intersection_and_union() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let b = Rect::new(5.0, 5.0, 10.0, 10.0);
+        assert_eq!(a.intersection(b), Some(Rect::new(5.0, 5.0, 5.0, 5.0)));
+        assert_eq!(a.intersection(Rect::new(20.0, 0.0, 1.0, 1.0)), None);
+        assert_eq!(a.union(b), Rect::new(0.0, 0.0, 15.0, 15.0));
+        assert_eq!(b.union(a), Rect::new(0.0, 0.0, 15.0, 15.0));
+        let mut c = b;
+        c.extend_to_contain(a);
+        assert_eq!(c, Rect::new(0.0, 0.0, 15.0, 15.0));
+        assert!(a.contains_rect(Rect::new(1.0, 1.0, 2.0, 2.0)));
+        assert!(!a.contains_rect(b));
+    }
 }
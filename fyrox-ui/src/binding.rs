@@ -0,0 +1,153 @@
+//! Bindings synchronize a value of a widget with an external data model. See
+//! [`UserInterface::bind`](crate::UserInterface::bind) for more info.
+
+use crate::{
+    check_box::CheckBoxMessage,
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        pool::Handle,
+    },
+    message::{MessageData, MessageDirection, UiMessage},
+    numeric::{NumericType, NumericUpDownMessage},
+    scroll_bar::ScrollBarMessage,
+    text_box::TextBoxMessage,
+    vec::{vec2::Vec2EditorMessage, vec3::Vec3EditorMessage, vec4::Vec4EditorMessage},
+    UiNode,
+};
+use std::sync::mpsc::Sender;
+
+/// A message that carries a value of a widget. Every message that implements the trait can be
+/// used to bind a widget to a data model.
+pub trait ValueMessage: MessageData {
+    type Value: Clone + PartialEq + 'static;
+
+    /// Creates new message that carries given value.
+    fn make(
+        destination: Handle<UiNode>,
+        direction: MessageDirection,
+        value: Self::Value,
+    ) -> UiMessage;
+
+    /// Returns value of the message, `None` if the message does not carry a value.
+    fn value(&self) -> Option<&Self::Value>;
+}
+
+macro_rules! impl_value_message {
+    ($msg:ty, $variant:path, $value:ty, $make:path $(, $generic:ident)?) => {
+        impl$(<$generic: NumericType>)? ValueMessage for $msg {
+            type Value = $value;
+
+            fn make(
+                destination: Handle<UiNode>,
+                direction: MessageDirection,
+                value: Self::Value,
+            ) -> UiMessage {
+                $make(destination, direction, value)
+            }
+
+            #[allow(unreachable_patterns)]
+            fn value(&self) -> Option<&Self::Value> {
+                match self {
+                    $variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_value_message!(
+    ScrollBarMessage,
+    ScrollBarMessage::Value,
+    f32,
+    ScrollBarMessage::value
+);
+impl_value_message!(
+    CheckBoxMessage,
+    CheckBoxMessage::Check,
+    Option<bool>,
+    CheckBoxMessage::checked
+);
+impl_value_message!(
+    TextBoxMessage,
+    TextBoxMessage::Text,
+    String,
+    TextBoxMessage::text
+);
+impl_value_message!(
+    NumericUpDownMessage<T>,
+    NumericUpDownMessage::Value,
+    T,
+    NumericUpDownMessage::value,
+    T
+);
+impl_value_message!(
+    Vec2EditorMessage<T>,
+    Vec2EditorMessage::Value,
+    Vector2<T>,
+    Vec2EditorMessage::value,
+    T
+);
+impl_value_message!(
+    Vec3EditorMessage<T>,
+    Vec3EditorMessage::Value,
+    Vector3<T>,
+    Vec3EditorMessage::value,
+    T
+);
+impl_value_message!(
+    Vec4EditorMessage<T>,
+    Vec4EditorMessage::Value,
+    Vector4<T>,
+    Vec4EditorMessage::value,
+    T
+);
+
+pub(in crate) trait AnyBinding {
+    fn widget(&self) -> Handle<UiNode>;
+
+    /// Reads a value from the data model and sends it to the widget if it has changed.
+    fn pull(&mut self, sender: &Sender<UiMessage>);
+
+    /// Writes a value from the widget's response message to the data model if the value
+    /// differs from the last synchronized one.
+    fn push(&mut self, message: &UiMessage);
+}
+
+pub(in crate) struct Binding<M: ValueMessage> {
+    pub widget: Handle<UiNode>,
+    pub getter: Box<dyn FnMut() -> M::Value>,
+    pub setter: Box<dyn FnMut(M::Value)>,
+    pub last: Option<M::Value>,
+}
+
+impl<M: ValueMessage> AnyBinding for Binding<M> {
+    fn widget(&self) -> Handle<UiNode> {
+        self.widget
+    }
+
+    fn pull(&mut self, sender: &Sender<UiMessage>) {
+        let value = (self.getter)();
+        if self.last.as_ref() != Some(&value) {
+            self.last = Some(value.clone());
+            let _ = sender.send(M::make(self.widget, MessageDirection::ToWidget, value));
+        }
+    }
+
+    fn push(&mut self, message: &UiMessage) {
+        if message.destination() != self.widget
+            || message.direction() != MessageDirection::FromWidget
+        {
+            return;
+        }
+
+        if let Some(value) = message.data::<M>().and_then(|msg| msg.value()) {
+            // Widgets echo every value they were given, such responses must not be written
+            // back to the model.
+            if self.last.as_ref() != Some(value) {
+                self.last = Some(value.clone());
+                (self.setter)(value.clone());
+            }
+        }
+    }
+}
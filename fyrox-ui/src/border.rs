@@ -28,10 +28,9 @@ impl Control for Border {
     fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
-        let margin_x = self.stroke_thickness.left + self.stroke_thickness.right;
-        let margin_y = self.stroke_thickness.top + self.stroke_thickness.bottom;
+        let margin = self.stroke_thickness.axes_margin();
 
-        let size_for_child = Vector2::new(available_size.x - margin_x, available_size.y - margin_y);
+        let size_for_child = available_size - margin;
         let mut desired_size = Vector2::default();
 
         for child_handle in self.widget.children() {
@@ -46,10 +45,7 @@ impl Control for Border {
             }
         }
 
-        desired_size.x += margin_x;
-        desired_size.y += margin_y;
-
-        desired_size
+        desired_size + margin
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
@@ -58,8 +54,8 @@ impl Control for Border {
         let rect_for_child = Rect::new(
             self.stroke_thickness.left,
             self.stroke_thickness.top,
-            final_size.x - (self.stroke_thickness.right + self.stroke_thickness.left),
-            final_size.y - (self.stroke_thickness.bottom + self.stroke_thickness.top),
+            final_size.x - self.stroke_thickness.horizontal(),
+            final_size.y - self.stroke_thickness.vertical(),
         );
 
         for child_handle in self.widget.children() {
@@ -33,6 +33,7 @@ pub struct Button {
     widget: Widget,
     decorator: Handle<UiNode>,
     content: Handle<UiNode>,
+    press_position: Option<Vector2<f32>>,
 }
 
 crate::define_widget_deref!(Button);
@@ -43,6 +44,7 @@ impl Button {
             widget,
             decorator: body,
             content,
+            press_position: None,
         }
     }
 
@@ -78,18 +80,30 @@ impl Control for Button {
                 || self.has_descendant(message.destination(), ui)
             {
                 match msg {
-                    WidgetMessage::MouseUp { .. } => {
-                        ui.send_message(ButtonMessage::click(
-                            self.handle(),
-                            MessageDirection::FromWidget,
-                        ));
+                    &WidgetMessage::MouseUp { pos, .. } => {
+                        // Click is fired only if cursor stayed within drag threshold, otherwise
+                        // the sequence is a drag and it must not activate the button.
+                        if let Some(press_position) = self.press_position.take() {
+                            if !ui.is_drag(press_position, pos) {
+                                ui.send_message(ButtonMessage::click(
+                                    self.handle(),
+                                    MessageDirection::FromWidget,
+                                ));
+                            }
+                        }
                         ui.release_mouse_capture();
                         message.set_handled(true);
                     }
-                    WidgetMessage::MouseDown { .. } => {
+                    &WidgetMessage::MouseDown { pos, .. } => {
+                        self.press_position = Some(pos);
                         ui.capture_mouse(message.destination());
                         message.set_handled(true);
                     }
+                    WidgetMessage::TouchCancelled { .. } => {
+                        // Cancelled touch must not leave the button pressed, so the next release
+                        // won't click it.
+                        self.press_position = None;
+                    }
                     _ => (),
                 }
             }
@@ -159,7 +173,11 @@ impl ButtonBuilder {
         self
     }
 
-    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+    pub fn build(mut self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        if self.widget_builder.focusable.is_none() {
+            self.widget_builder.focusable = Some(true);
+        }
+
         let content = if let Some(content) = self.content {
             match content {
                 ButtonContent::Text(txt) => TextBuilder::new(WidgetBuilder::new())
@@ -214,6 +232,7 @@ impl ButtonBuilder {
             widget: self.widget_builder.with_child(back).build(),
             decorator: back,
             content,
+            press_position: None,
         };
         ctx.add_node(UiNode::new(button))
     }
@@ -35,19 +35,21 @@ impl Control for Canvas {
             ui.measure_node(*child_handle, size_for_child);
         }
 
-        Vector2::default()
+        self.widget.padding().axes_margin()
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
+        let offset = self.widget.padding().offset();
+
         for &child_handle in self.widget.children() {
             let child = ui.nodes.borrow(child_handle);
             ui.arrange_node(
                 child_handle,
                 &Rect::new(
-                    child.desired_local_position().x,
-                    child.desired_local_position().y,
+                    offset.x + child.desired_local_position().x,
+                    offset.y + child.desired_local_position().y,
                     child.desired_size().x,
                     child.desired_size().y,
                 ),
@@ -401,6 +401,18 @@ impl Control for CurveEditor {
                     }
                     _ => {}
                 }
+
+                // Keys, selection and view are changed directly by input.
+                if self.operation_context.is_some()
+                    || !matches!(
+                        msg,
+                        WidgetMessage::MouseMove { .. }
+                            | WidgetMessage::MouseEnter
+                            | WidgetMessage::MouseLeave
+                    )
+                {
+                    self.invalidate_visual();
+                }
             } else if let Some(msg) = message.data::<CurveEditorMessage>() {
                 if message.destination() == self.handle
                     && message.direction() == MessageDirection::ToWidget
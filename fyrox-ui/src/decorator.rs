@@ -195,7 +195,9 @@ impl Control for Decorator {
                 || self.has_descendant(message.destination(), ui)
             {
                 match msg {
-                    WidgetMessage::MouseLeave => {
+                    // Mouse enter and leave are fired for each node under the cursor, so react
+                    // only on own ones to prevent flickering when cursor moves over children.
+                    WidgetMessage::MouseLeave if message.destination() == self.handle() => {
                         if self.is_selected {
                             ui.send_message(WidgetMessage::background(
                                 self.handle(),
@@ -210,7 +212,7 @@ impl Control for Decorator {
                             ));
                         }
                     }
-                    WidgetMessage::MouseEnter => {
+                    WidgetMessage::MouseEnter if message.destination() == self.handle() => {
                         ui.send_message(WidgetMessage::background(
                             self.handle(),
                             MessageDirection::ToWidget,
@@ -224,7 +226,7 @@ impl Control for Decorator {
                             self.pressed_brush.clone(),
                         ));
                     }
-                    WidgetMessage::MouseUp { .. } => {
+                    WidgetMessage::MouseUp { .. } | WidgetMessage::TouchCancelled { .. } => {
                         if self.is_selected {
                             ui.send_message(WidgetMessage::background(
                                 self.handle(),
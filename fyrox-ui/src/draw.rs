@@ -6,7 +6,7 @@ use crate::{
         math::{self, Rect, TriangleDefinition},
     },
     formatted_text::FormattedText,
-    ttf::SharedFont,
+    ttf::{Font, SharedFont},
     Thickness,
 };
 use std::{any::Any, ops::Range, sync::Arc};
@@ -134,6 +134,12 @@ pub trait Draw {
 
     fn last_vertex_index(&self) -> u32;
 
+    /// Returns `true` if edges of filled rectangles should be antialiased, see
+    /// [`Self::push_rect_filled_antialiased`].
+    fn is_antialiasing_enabled(&self) -> bool {
+        false
+    }
+
     fn push_triangle_multicolor(&mut self, vertices: [(Vector2<f32>, Color); 3]) {
         let index = self.last_vertex_index();
         for &(pos, color) in &vertices {
@@ -225,6 +231,12 @@ pub trait Draw {
     }
 
     fn push_rect_filled(&mut self, rect: &Rect<f32>, tex_coords: Option<&[Vector2<f32>; 4]>) {
+        // Textured rectangles (glyphs, images) have their own edges.
+        if tex_coords.is_none() && self.is_antialiasing_enabled() {
+            self.push_rect_filled_antialiased(rect, 1.0);
+            return;
+        }
+
         let index = self.last_vertex_index();
         self.push_vertex(
             Vector2::new(rect.x(), rect.y()),
@@ -247,6 +259,59 @@ pub trait Draw {
         self.push_triangle(index, index + 2, index + 3);
     }
 
+    /// Pushes a filled rectangle with a translucent fringe of given width around its edges. The
+    /// fringe is centered on the edges, so the rectangle keeps its visual size. Alpha of the fringe
+    /// goes from opaque to transparent, which smooths edges that are not aligned with pixels.
+    fn push_rect_filled_antialiased(&mut self, rect: &Rect<f32>, feather: f32) {
+        let half = (feather * 0.5)
+            .min(rect.w() * 0.5)
+            .min(rect.h() * 0.5)
+            .max(0.0);
+        let inner = Rect::new(
+            rect.x() + half,
+            rect.y() + half,
+            rect.w() - 2.0 * half,
+            rect.h() - 2.0 * half,
+        );
+        let outer = Rect::new(
+            rect.x() - half,
+            rect.y() - half,
+            rect.w() + 2.0 * half,
+            rect.h() + 2.0 * half,
+        );
+
+        // Fringe keeps color of the inner part, otherwise edges blend towards black.
+        let index = self.last_vertex_index();
+        for (bounds, color) in [
+            (inner, Color::WHITE),
+            (outer, Color::from_rgba(255, 255, 255, 0)),
+        ] {
+            for (pos, tex_coord) in [
+                (bounds.left_top_corner(), Vector2::new(0.0, 0.0)),
+                (bounds.right_top_corner(), Vector2::new(1.0, 0.0)),
+                (bounds.right_bottom_corner(), Vector2::new(1.0, 1.0)),
+                (bounds.left_bottom_corner(), Vector2::new(0.0, 1.0)),
+            ] {
+                self.push_vertex_raw(Vertex {
+                    pos,
+                    tex_coord,
+                    color,
+                });
+            }
+        }
+
+        // Inner part.
+        self.push_triangle(index, index + 1, index + 2);
+        self.push_triangle(index, index + 2, index + 3);
+
+        // Fringe, a quad per edge.
+        for edge in 0..4 {
+            let next = (edge + 1) % 4;
+            self.push_triangle(index + edge, index + 4 + edge, index + 4 + next);
+            self.push_triangle(index + edge, index + 4 + next, index + next);
+        }
+    }
+
     fn push_rect_multicolor(&mut self, rect: &Rect<f32>, colors: [Color; 4]) {
         let index = self.last_vertex_index();
         self.push_vertex_raw(Vertex {
@@ -310,12 +375,33 @@ pub trait Draw {
     }
 }
 
+/// Statistics of a frame drawn by a [`DrawingContext`].
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct DrawingStatistics {
+    /// Amount of commands emitted.
+    pub commands: usize,
+    /// Amount of vertices emitted.
+    pub vertices: usize,
+    /// Amount of triangles emitted.
+    pub triangles: usize,
+    /// `true` if at least one of the buffers had to grow to fit the frame. Buffers keep their
+    /// capacity between frames, so normally this should happen only for first few frames or
+    /// when amount of content on screen increases.
+    pub buffers_grown: bool,
+}
+
 pub struct DrawingContext {
     vertex_buffer: Vec<Vertex>,
     triangle_buffer: Vec<TriangleDefinition>,
     command_buffer: Vec<Command>,
+    prev_vertex_buffer: Vec<Vertex>,
+    prev_triangle_buffer: Vec<TriangleDefinition>,
+    prev_command_buffer: Vec<Command>,
     opacity_stack: Vec<f32>,
     triangles_to_commit: usize,
+    capacities: [usize; 3],
+    statistics: DrawingStatistics,
+    antialiasing: bool,
 }
 
 fn get_line_thickness_vector(a: Vector2<f32>, b: Vector2<f32>, thickness: f32) -> Vector2<f32> {
@@ -353,6 +439,11 @@ impl Draw for DrawingContext {
     fn last_vertex_index(&self) -> u32 {
         self.vertex_buffer.len() as u32
     }
+
+    #[inline(always)]
+    fn is_antialiasing_enabled(&self) -> bool {
+        self.antialiasing
+    }
 }
 
 impl DrawingContext {
@@ -361,13 +452,70 @@ impl DrawingContext {
             vertex_buffer: Vec::new(),
             triangle_buffer: Vec::new(),
             command_buffer: Vec::new(),
+            prev_vertex_buffer: Vec::new(),
+            prev_triangle_buffer: Vec::new(),
+            prev_command_buffer: Vec::new(),
             triangles_to_commit: 0,
             opacity_stack: vec![1.0],
+            capacities: Default::default(),
+            statistics: Default::default(),
+            antialiasing: false,
         }
     }
 
+    /// Enables or disables antialiasing of edges of filled non-textured rectangles, see
+    /// [`Draw::push_rect_filled_antialiased`]. Disabled by default, because pixel-perfect UIs
+    /// (pixel art for example) need sharp edges.
+    pub fn set_antialiasing(&mut self, antialiasing: bool) {
+        self.antialiasing = antialiasing;
+    }
+
+    fn buffer_capacities(&self) -> [usize; 3] {
+        // Buffers of current and previous frames are swapped each frame.
+        [
+            self.vertex_buffer
+                .capacity()
+                .max(self.prev_vertex_buffer.capacity()),
+            self.triangle_buffer
+                .capacity()
+                .max(self.prev_triangle_buffer.capacity()),
+            self.command_buffer
+                .capacity()
+                .max(self.prev_command_buffer.capacity()),
+        ]
+    }
+
+    /// Clears the context for a new frame. Buffers keep their memory, so drawing of the next frame
+    /// won't allocate unless it has more content than any of the previous frames. Statistics of
+    /// the cleared frame are available via [`Self::statistics`].
     #[inline]
     pub fn clear(&mut self) {
+        self.update_statistics();
+        self.reset_buffers();
+    }
+
+    /// Same as [`Self::clear`], but keeps buffers of the cleared frame, so its commands could be
+    /// reused by [`Self::reuse_command`] while drawing the new frame.
+    pub(in crate) fn clear_keeping_previous(&mut self) {
+        self.update_statistics();
+        std::mem::swap(&mut self.vertex_buffer, &mut self.prev_vertex_buffer);
+        std::mem::swap(&mut self.triangle_buffer, &mut self.prev_triangle_buffer);
+        std::mem::swap(&mut self.command_buffer, &mut self.prev_command_buffer);
+        self.reset_buffers();
+    }
+
+    fn update_statistics(&mut self) {
+        let capacities = self.buffer_capacities();
+        self.statistics = DrawingStatistics {
+            commands: self.command_buffer.len(),
+            vertices: self.vertex_buffer.len(),
+            triangles: self.triangle_buffer.len(),
+            buffers_grown: capacities != self.capacities,
+        };
+        self.capacities = capacities;
+    }
+
+    fn reset_buffers(&mut self) {
         self.vertex_buffer.clear();
         self.triangle_buffer.clear();
         self.command_buffer.clear();
@@ -376,6 +524,67 @@ impl DrawingContext {
         self.triangles_to_commit = 0;
     }
 
+    /// Returns statistics of the last frame, that was cleared by [`Self::clear`].
+    #[inline]
+    pub fn statistics(&self) -> DrawingStatistics {
+        self.statistics
+    }
+
+    /// Releases memory of the buffers that is not used by the current frame. Could be useful on
+    /// memory-constrained targets after a frame with lots of content, in other cases it will
+    /// just cause reallocations on next frames.
+    pub fn shrink_to_fit(&mut self) {
+        self.vertex_buffer.shrink_to_fit();
+        self.triangle_buffer.shrink_to_fit();
+        self.command_buffer.shrink_to_fit();
+        self.prev_vertex_buffer.shrink_to_fit();
+        self.prev_triangle_buffer.shrink_to_fit();
+        self.prev_command_buffer.shrink_to_fit();
+        self.capacities = self.buffer_capacities();
+    }
+
+    /// Returns commands of previous frame, if it was cleared by [`Self::clear_keeping_previous`].
+    #[inline]
+    pub(in crate) fn previous_commands(&self) -> &[Command] {
+        &self.prev_command_buffer
+    }
+
+    /// Copies a command of previous frame with its geometry to the current frame. Opacity of the
+    /// command is taken from the opacity stack, because it is defined by ancestors of a node.
+    pub(in crate) fn reuse_command(&mut self, index: usize) {
+        let command = &self.prev_command_buffer[index];
+        let triangles = &self.prev_triangle_buffer[command.triangles.clone()];
+
+        // Geometry of a command is pushed in one go, so its vertices are laid out contiguously.
+        let (mut first, mut last) = (u32::MAX, 0);
+        for triangle in triangles {
+            for &index in triangle.as_ref() {
+                first = first.min(index);
+                last = last.max(index);
+            }
+        }
+        if first > last {
+            return;
+        }
+
+        let offset = self.vertex_buffer.len() as u32;
+        self.vertex_buffer
+            .extend_from_slice(&self.prev_vertex_buffer[first as usize..=last as usize]);
+
+        let first_triangle = self.triangle_buffer.len();
+        self.triangle_buffer.extend(
+            triangles
+                .iter()
+                .map(|triangle| TriangleDefinition(triangle.0.map(|index| index - first + offset))),
+        );
+
+        self.command_buffer.push(Command {
+            triangles: first_triangle..self.triangle_buffer.len(),
+            opacity: *self.opacity_stack.last().unwrap(),
+            ..command.clone()
+        });
+    }
+
     #[inline]
     pub fn get_vertices(&self) -> &[Vertex] {
         self.vertex_buffer.as_slice()
@@ -391,8 +600,11 @@ impl DrawingContext {
         &self.command_buffer
     }
 
+    /// Pushes opacity for subsequent commands. Opacities are composed multiplicatively, so
+    /// opacity of a command is a product of every opacity in the stack.
     pub fn push_opacity(&mut self, opacity: f32) {
-        self.opacity_stack.push(opacity);
+        let composed = opacity * *self.opacity_stack.last().unwrap();
+        self.opacity_stack.push(composed);
     }
 
     pub fn pop_opacity(&mut self) {
@@ -466,14 +678,12 @@ impl DrawingContext {
         }
     }
 
-    pub fn draw_text(
+    fn push_text_glyphs(
         &mut self,
-        clip_bounds: Rect<f32>,
         position: Vector2<f32>,
         formatted_text: &FormattedText,
+        font: &Font,
     ) {
-        let font = formatted_text.get_font();
-
         for element in formatted_text.get_glyphs() {
             let bounds = element.get_bounds();
 
@@ -484,9 +694,65 @@ impl DrawingContext {
                 bounds.h(),
             );
 
-            self.push_rect_filled(&final_bounds, Some(element.get_tex_coords()));
+            // Take actual texture coordinates, because glyph could be moved when atlas has grown
+            // after the text was laid out.
+            let tex_coords = element
+                .glyph_index()
+                .and_then(|index| font.glyphs().get(index))
+                .map_or(element.get_tex_coords(), |glyph| &glyph.tex_coords);
+
+            self.push_rect_filled(&final_bounds, Some(tex_coords));
+        }
+    }
+
+    /// Draws formatted text at given position. Shadow and outline of the text (if any) are drawn
+    /// as separate commands before the text itself.
+    pub fn draw_text(
+        &mut self,
+        clip_bounds: Rect<f32>,
+        position: Vector2<f32>,
+        formatted_text: &FormattedText,
+    ) {
+        let font = formatted_text.get_font();
+        let font_ref = font.0.lock().unwrap();
+
+        if let Some(shadow) = formatted_text.shadow() {
+            self.push_text_glyphs(position + shadow.offset, formatted_text, &font_ref);
+            self.commit(
+                clip_bounds,
+                Brush::Solid(shadow.color),
+                CommandTexture::Font(font.clone()),
+                None,
+            );
+        }
+
+        if let Some(outline) = formatted_text.outline() {
+            // Outline is made of glyphs shifted in eight directions.
+            for (x, y) in [
+                (-1.0, -1.0),
+                (0.0, -1.0),
+                (1.0, -1.0),
+                (-1.0, 0.0),
+                (1.0, 0.0),
+                (-1.0, 1.0),
+                (0.0, 1.0),
+                (1.0, 1.0),
+            ] {
+                let offset = Vector2::new(x, y) * outline.thickness;
+                self.push_text_glyphs(position + offset, formatted_text, &font_ref);
+            }
+            self.commit(
+                clip_bounds,
+                Brush::Solid(outline.color),
+                CommandTexture::Font(font.clone()),
+                None,
+            );
         }
 
+        self.push_text_glyphs(position, formatted_text, &font_ref);
+
+        drop(font_ref);
+
         self.commit(
             clip_bounds,
             formatted_text.brush(),
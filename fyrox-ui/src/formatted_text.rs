@@ -10,6 +10,7 @@ use std::ops::Range;
 pub struct TextGlyph {
     bounds: Rect<f32>,
     tex_coords: [Vector2<f32>; 4],
+    glyph_index: Option<usize>,
 }
 
 impl TextGlyph {
@@ -20,6 +21,12 @@ impl TextGlyph {
     pub fn get_tex_coords(&self) -> &[Vector2<f32>; 4] {
         &self.tex_coords
     }
+
+    /// Index of the glyph in the font, `None` for invalid symbols. Texture coordinates of a glyph
+    /// could change when font atlas grows, so the index could be used to fetch actual ones.
+    pub fn glyph_index(&self) -> Option<usize> {
+        self.glyph_index
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -68,7 +75,7 @@ pub enum WrapMode {
     /// Letter-based wrapping.
     Letter,
 
-    /// Word-based wrapping.
+    /// Word-based wrapping. Words longer than a line are broken on letters.
     Word,
 }
 
@@ -85,6 +92,47 @@ impl Character {
             glyph_index: font.glyph_index(char_code).unwrap_or_default() as u32,
         }
     }
+
+    /// Same as [`Self::from_char_with_font`], but rasterizes the glyph of the character if the
+    /// font does not have it yet, see [`Font::rasterize_glyph`].
+    pub fn from_char_rasterized(char_code: u32, font: &mut Font) -> Self {
+        Self {
+            char_code,
+            glyph_index: font.rasterize_glyph(char_code).unwrap_or_default() as u32,
+        }
+    }
+}
+
+/// Outline drawn around every glyph of a text, makes text readable on busy backgrounds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextOutline {
+    pub color: Color,
+    /// Thickness of the outline in pixels.
+    pub thickness: f32,
+}
+
+/// Shadow drawn under every glyph of a text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextShadow {
+    pub color: Color,
+    /// Offset of the shadow relative to the text in pixels.
+    pub offset: Vector2<f32>,
+}
+
+fn character_advance(
+    font: &Font,
+    character: Character,
+    letter_spacing: f32,
+    tab_width: Option<f32>,
+) -> f32 {
+    let advance = match tab_width {
+        Some(tab_width) if character.char_code == u32::from(b'\t') => tab_width,
+        _ => match font.glyphs().get(character.glyph_index as usize) {
+            Some(glyph) => glyph.advance,
+            None => font.height(),
+        },
+    };
+    advance + letter_spacing
 }
 
 #[derive(Clone, Debug)]
@@ -103,6 +151,11 @@ pub struct FormattedText {
     constraint: Vector2<f32>,
     wrap: WrapMode,
     mask_char: Option<Character>,
+    outline: Option<TextOutline>,
+    shadow: Option<TextShadow>,
+    line_spacing: f32,
+    letter_spacing: f32,
+    tab_width: Option<f32>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -159,6 +212,55 @@ impl FormattedText {
         self.brush.clone()
     }
 
+    pub fn set_outline(&mut self, outline: Option<TextOutline>) -> &mut Self {
+        self.outline = outline;
+        self
+    }
+
+    pub fn outline(&self) -> Option<TextOutline> {
+        self.outline
+    }
+
+    pub fn set_shadow(&mut self, shadow: Option<TextShadow>) -> &mut Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub fn shadow(&self) -> Option<TextShadow> {
+        self.shadow
+    }
+
+    /// Sets multiplier of vertical distance between lines. It does not affect wrapping.
+    pub fn set_line_spacing(&mut self, line_spacing: f32) -> &mut Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn line_spacing(&self) -> f32 {
+        self.line_spacing
+    }
+
+    /// Sets extra horizontal distance (in pixels) added to advance of every character.
+    pub fn set_letter_spacing(&mut self, letter_spacing: f32) -> &mut Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    pub fn letter_spacing(&self) -> f32 {
+        self.letter_spacing
+    }
+
+    /// Sets advance (in pixels) of a tab character, `None` means that advance of the tab glyph
+    /// of the font is used.
+    pub fn set_tab_width(&mut self, tab_width: Option<f32>) -> &mut Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn tab_width(&self) -> Option<f32> {
+        self.tab_width
+    }
+
     pub fn set_constraint(&mut self, constraint: Vector2<f32>) -> &mut Self {
         self.constraint = constraint;
         self
@@ -179,19 +281,84 @@ impl FormattedText {
         let mut width = 0.0;
         let font = self.font.0.lock().unwrap();
         for index in range {
-            width += font.glyph_advance(self.text[index].glyph_index);
+            width +=
+                character_advance(&font, self.text[index], self.letter_spacing, self.tab_width);
         }
         width
     }
 
+    fn character_advance(&self, font: &Font, index: usize) -> f32 {
+        let character = self.mask_char.unwrap_or(self.text[index]);
+        character_advance(font, character, self.letter_spacing, self.tab_width)
+    }
+
+    /// Returns index of a character which is the closest to the given point in local coordinates
+    /// of the text. The index is a caret position, so it is in `0..=len` range: points to the
+    /// right of a middle of a character map to the next character. Uses the layout produced by
+    /// the last [`Self::build`] call.
+    pub fn glyph_at(&self, local_point: Vector2<f32>) -> usize {
+        let line = match self
+            .lines
+            .iter()
+            .find(|line| local_point.y < line.y_offset + line.height)
+            .or_else(|| self.lines.last())
+        {
+            Some(line) => line,
+            None => return 0,
+        };
+
+        let font = self.font.0.lock().unwrap();
+        let mut x = line.x_offset;
+        for index in line.begin..line.end {
+            let advance = self.character_advance(&font, index);
+            if local_point.x < x + advance * 0.5 {
+                return index;
+            }
+            x += advance;
+        }
+        line.end
+    }
+
+    /// Returns bounds of a character at the given caret position in local coordinates of the
+    /// text. Width of the rectangle is the advance of the character (zero at the end of a line),
+    /// height is the height of the font. Uses the layout produced by the last [`Self::build`]
+    /// call.
+    pub fn caret_rect(&self, index: usize) -> Rect<f32> {
+        let font = self.font.0.lock().unwrap();
+
+        let line = match self
+            .lines
+            .iter()
+            .find(|line| index <= line.end)
+            .or_else(|| self.lines.last())
+        {
+            Some(line) => line,
+            None => return Rect::new(0.0, 0.0, 0.0, font.height()),
+        };
+
+        let index = index.max(line.begin).min(line.end);
+        let mut x = line.x_offset;
+        for i in line.begin..index {
+            x += self.character_advance(&font, i);
+        }
+        let width = if index < line.end {
+            self.character_advance(&font, index)
+        } else {
+            0.0
+        };
+
+        Rect::new(x, line.y_offset, width, font.height())
+    }
+
     pub fn set_text<P: AsRef<str>>(&mut self, text: P) -> &mut Self {
         // Convert text to UTF32.
         self.text.clear();
 
-        let font = self.font.0.lock().unwrap();
+        let mut font = self.font.0.lock().unwrap();
 
         for code in text.as_ref().chars().map(|c| c as u32) {
-            self.text.push(Character::from_char_with_font(code, &font));
+            self.text
+                .push(Character::from_char_rasterized(code, &mut font));
         }
 
         drop(font);
@@ -209,10 +376,12 @@ impl FormattedText {
     }
 
     pub fn insert_char(&mut self, code: char, index: usize) -> &mut Self {
-        let font = self.font.0.lock().unwrap();
+        let mut font = self.font.0.lock().unwrap();
 
-        self.text
-            .insert(index, Character::from_char_with_font(code as u32, &font));
+        self.text.insert(
+            index,
+            Character::from_char_rasterized(code as u32, &mut font),
+        );
 
         drop(font);
 
@@ -220,12 +389,12 @@ impl FormattedText {
     }
 
     pub fn insert_str(&mut self, str: &str, position: usize) -> &mut Self {
-        let font = self.font.0.lock().unwrap();
+        let mut font = self.font.0.lock().unwrap();
 
         for (i, code) in str.chars().enumerate() {
             self.text.insert(
                 position + i,
-                Character::from_char_with_font(code as u32, &font),
+                Character::from_char_rasterized(code as u32, &mut font),
             );
         }
 
@@ -255,16 +424,18 @@ impl FormattedText {
             &self.text
         };
 
+        let letter_spacing = self.letter_spacing;
+        let tab_width = self.tab_width;
+        // Line spacing affects only vertical advance, so it does not change wrapping.
+        let line_advance = font.ascender() * self.line_spacing;
+
         // Split on lines.
         let mut total_height = 0.0;
         let mut current_line = TextLine::new();
         let mut word: Option<Word> = None;
         self.lines.clear();
         for (i, character) in text.iter().enumerate() {
-            let advance = match font.glyphs().get(character.glyph_index as usize) {
-                Some(glyph) => glyph.advance,
-                None => font.height(),
-            };
+            let advance = character_advance(&font, *character, letter_spacing, tab_width);
             let is_new_line =
                 character.char_code == u32::from(b'\n') || character.char_code == u32::from(b'\r');
             let new_width = current_line.width + advance;
@@ -296,7 +467,7 @@ impl FormattedText {
                 current_line.begin = if is_new_line { i + 1 } else { i };
                 current_line.end = current_line.begin;
                 current_line.width = advance;
-                total_height += font.ascender();
+                total_height += line_advance;
             } else {
                 match self.wrap {
                     WrapMode::NoWrap => {
@@ -309,7 +480,7 @@ impl FormattedText {
                             current_line.begin = if is_new_line { i + 1 } else { i };
                             current_line.end = current_line.begin + 1;
                             current_line.width = advance;
-                            total_height += font.ascender();
+                            total_height += line_advance;
                         } else {
                             current_line.width = new_width;
                             current_line.end += 1;
@@ -319,14 +490,26 @@ impl FormattedText {
                         if word_ended {
                             let word = word.take().unwrap();
                             if word.width > self.constraint.x {
-                                // The word is longer than available constraints.
-                                // Push the word as a whole.
-                                current_line.width += word.width;
-                                current_line.end += word.length;
-                                self.lines.push(current_line);
-                                current_line.begin = current_line.end;
-                                current_line.width = 0.0;
-                                total_height += font.ascender();
+                                // The word is longer than available constraints, it
+                                // starts from new line and breaks on letters.
+                                let word_begin = current_line.end;
+                                for (k, &letter) in
+                                    text.iter().enumerate().skip(word_begin).take(word.length)
+                                {
+                                    let advance =
+                                        character_advance(&font, letter, letter_spacing, tab_width);
+                                    if current_line.end > current_line.begin
+                                        && (k == word_begin
+                                            || current_line.width + advance > self.constraint.x)
+                                    {
+                                        self.lines.push(current_line);
+                                        current_line.begin = current_line.end;
+                                        current_line.width = 0.0;
+                                        total_height += line_advance;
+                                    }
+                                    current_line.width += advance;
+                                    current_line.end += 1;
+                                }
                             } else if current_line.width + word.width > self.constraint.x {
                                 // The word will exceed horizontal constraint, we have to
                                 // commit current line and move the word in the next line.
@@ -334,7 +517,7 @@ impl FormattedText {
                                 current_line.begin = i - word.length;
                                 current_line.end = i;
                                 current_line.width = word.width;
-                                total_height += font.ascender();
+                                total_height += line_advance;
                             } else {
                                 // The word does not exceed horizontal constraint, append it
                                 // to the line.
@@ -355,17 +538,27 @@ impl FormattedText {
         // Commit rest of text.
         if current_line.begin != current_line.end {
             for character in text.iter().skip(current_line.end) {
-                let advance = match font.glyphs().get(character.glyph_index as usize) {
-                    Some(glyph) => glyph.advance,
-                    None => font.height(),
-                };
-                current_line.width += advance;
+                current_line.width +=
+                    character_advance(&font, *character, letter_spacing, tab_width);
             }
             current_line.end = self.text.len();
             self.lines.push(current_line);
-            total_height += font.ascender();
+            total_height += line_advance;
         }
 
+        // Outline and shadow are drawn outside of glyphs, they would be clipped by bounds of a
+        // widget without extra space. Outline extends glyphs in every direction, shadow extends
+        // them only in the direction of its offset (which could be negative). Content is shifted
+        // by the left and top padding, so nothing is drawn outside of the text bounds.
+        let outline = self
+            .outline
+            .map_or(0.0, |outline| outline.thickness.max(0.0));
+        let shadow = self
+            .shadow
+            .map_or(Vector2::default(), |shadow| shadow.offset);
+        let padding_left_top = Vector2::new(outline.max(-shadow.x), outline.max(-shadow.y));
+        let padding_right_bottom = Vector2::new(outline.max(shadow.x), outline.max(shadow.y));
+
         // Align lines according to desired alignment.
         for line in self.lines.iter_mut() {
             match self.horizontal_alignment {
@@ -386,6 +579,7 @@ impl FormattedText {
                 }
                 HorizontalAlignment::Stretch => line.x_offset = 0.0,
             }
+            line.x_offset += padding_left_top.x;
         }
 
         // Generate glyphs for each text line.
@@ -408,7 +602,7 @@ impl FormattedText {
                 }
             }
             VerticalAlignment::Stretch => 0.0,
-        };
+        } + padding_left_top.y;
 
         let cursor_x_start = if self.constraint.x.is_infinite() {
             0.0
@@ -421,6 +615,7 @@ impl FormattedText {
             cursor.x = line.x_offset;
 
             for &character in text.iter().take(line.end).skip(line.begin) {
+                let advance = character_advance(&font, character, letter_spacing, tab_width);
                 match font.glyphs().get(character.glyph_index as usize) {
                     Some(glyph) => {
                         // Insert glyph
@@ -435,10 +630,9 @@ impl FormattedText {
                         let text_glyph = TextGlyph {
                             bounds: rect,
                             tex_coords: glyph.tex_coords,
+                            glyph_index: Some(character.glyph_index as usize),
                         };
                         self.glyphs.push(text_glyph);
-
-                        cursor.x += glyph.advance;
                     }
                     None => {
                         // Insert invalid symbol
@@ -451,14 +645,15 @@ impl FormattedText {
                         self.glyphs.push(TextGlyph {
                             bounds: rect,
                             tex_coords: [Vector2::default(); 4],
+                            glyph_index: None,
                         });
-                        cursor.x += rect.w();
                     }
                 }
+                cursor.x += advance;
             }
             line.height = font.ascender();
             line.y_offset = cursor.y;
-            cursor.y += font.ascender();
+            cursor.y += line_advance;
         }
 
         // Minus here is because descender has negative value.
@@ -466,6 +661,9 @@ impl FormattedText {
         for line in self.lines.iter() {
             full_size.x = line.width.max(full_size.x);
         }
+
+        full_size += padding_left_top + padding_right_bottom;
+
         full_size
     }
 }
@@ -479,6 +677,11 @@ pub struct FormattedTextBuilder {
     horizontal_alignment: HorizontalAlignment,
     wrap: WrapMode,
     mask_char: Option<char>,
+    outline: Option<TextOutline>,
+    shadow: Option<TextShadow>,
+    line_spacing: f32,
+    letter_spacing: f32,
+    tab_width: Option<f32>,
 }
 
 impl Default for FormattedTextBuilder {
@@ -499,6 +702,11 @@ impl FormattedTextBuilder {
             constraint: Vector2::new(128.0, 128.0),
             wrap: WrapMode::NoWrap,
             mask_char: None,
+            outline: None,
+            shadow: None,
+            line_spacing: 1.0,
+            letter_spacing: 0.0,
+            tab_width: None,
         }
     }
 
@@ -542,13 +750,38 @@ impl FormattedTextBuilder {
         self
     }
 
+    pub fn with_outline(mut self, outline: Option<TextOutline>) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    pub fn with_shadow(mut self, shadow: Option<TextShadow>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    pub fn with_tab_width(mut self, tab_width: Option<f32>) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
     pub fn build(self) -> FormattedText {
-        let font = self.font.0.lock().unwrap();
+        let mut font = self.font.0.lock().unwrap();
         FormattedText {
             text: self
                 .text
                 .chars()
-                .map(|c| Character::from_char_with_font(c as u32, &font))
+                .map(|c| Character::from_char_rasterized(c as u32, &mut font))
                 .collect(),
             lines: Vec::new(),
             glyphs: Vec::new(),
@@ -559,7 +792,12 @@ impl FormattedTextBuilder {
             wrap: self.wrap,
             mask_char: self
                 .mask_char
-                .map(|code| Character::from_char_with_font(u32::from(code), &font)),
+                .map(|code| Character::from_char_rasterized(u32::from(code), &mut font)),
+            outline: self.outline,
+            shadow: self.shadow,
+            line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            tab_width: self.tab_width,
             font: {
                 drop(font);
                 self.font
@@ -567,3 +805,120 @@ impl FormattedTextBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, color::Color},
+        formatted_text::{FormattedTextBuilder, TextOutline, TextShadow, WrapMode},
+    };
+
+    #[test]
+    fn glyph_hit_testing() {
+        let mut text = FormattedTextBuilder::new()
+            .with_text("abc".to_owned())
+            .with_constraint(Vector2::new(1000.0, 1000.0))
+            .build();
+        text.build();
+
+        let first = text.caret_rect(0);
+        let second = text.caret_rect(1);
+        assert_eq!(second.x(), first.x() + first.w());
+        assert_eq!(text.caret_rect(3).w(), 0.0);
+
+        let y = first.y() + first.h() * 0.5;
+        assert_eq!(text.glyph_at(Vector2::new(first.x() - 10.0, y)), 0);
+        assert_eq!(text.glyph_at(Vector2::new(second.x() + 0.1, y)), 1);
+        assert_eq!(text.glyph_at(Vector2::new(10000.0, y)), 3);
+    }
+
+    #[test]
+    fn caret_at_end_of_line() {
+        let mut text = FormattedTextBuilder::new()
+            .with_text("ab\ncd".to_owned())
+            .with_constraint(Vector2::new(1000.0, 1000.0))
+            .build();
+        text.build();
+
+        // The caret stays on the first line after its last character, not at the beginning of
+        // the next line.
+        let last = text.caret_rect(1);
+        let end = text.caret_rect(2);
+        assert_eq!(end.y(), last.y());
+        assert_eq!(end.x(), last.x() + last.w());
+        assert_eq!(end.w(), 0.0);
+
+        assert!(text.caret_rect(3).y() > end.y());
+    }
+
+    #[test]
+    fn spacing() {
+        let mut text = FormattedTextBuilder::new()
+            .with_text("ab\ncd".to_owned())
+            .with_constraint(Vector2::new(1000.0, 1000.0))
+            .build();
+        let size = text.build();
+        let width = text.get_lines()[0].width;
+        let line_offset = text.get_lines()[1].y_offset;
+
+        text.set_letter_spacing(2.0).set_line_spacing(2.0);
+        let spaced_size = text.build();
+        assert!((text.get_lines()[0].width - (width + 4.0)).abs() < 0.001);
+        assert_eq!(text.get_lines()[1].y_offset, 2.0 * line_offset);
+        assert!(spaced_size.y > size.y);
+    }
+
+    #[test]
+    fn long_word_wrap() {
+        let mut text = FormattedTextBuilder::new()
+            .with_text("a abcdefghij".to_owned())
+            .with_wrap(WrapMode::Word)
+            .with_constraint(Vector2::new(1000.0, 1000.0))
+            .build();
+        let single_line_size = text.build();
+        let letter_width = text.get_lines()[0].width / 12.0;
+
+        // About four letters per line.
+        text.set_constraint(Vector2::new(letter_width * 4.5, 1000.0));
+        let size = text.build();
+        let lines = text.get_lines();
+        assert!(lines.len() >= 4);
+        // The long word starts from new line.
+        assert_eq!((lines[0].begin, lines[0].end), (0, 2));
+        assert_eq!(lines[1].begin, 2);
+        assert_eq!(lines.last().unwrap().end, 12);
+        for line in lines {
+            assert!(line.width <= letter_width * 4.5 + 0.001);
+        }
+        assert!(size.y > single_line_size.y * 2.0);
+    }
+
+    #[test]
+    fn outline_and_shadow_size() {
+        let mut text = FormattedTextBuilder::new()
+            .with_text("abc".to_owned())
+            .with_constraint(Vector2::new(1000.0, 1000.0))
+            .build();
+        let size = text.build();
+
+        text.set_outline(Some(TextOutline {
+            color: Color::BLACK,
+            thickness: 2.0,
+        }));
+        assert_eq!(text.build(), size + Vector2::new(4.0, 4.0));
+        assert_eq!(text.get_lines()[0].x_offset, 2.0);
+        assert_eq!(text.get_lines()[0].y_offset, 2.0);
+
+        text.set_shadow(Some(TextShadow {
+            color: Color::BLACK,
+            offset: Vector2::new(3.0, -1.0),
+        }));
+        assert_eq!(text.build(), size + Vector2::new(5.0, 4.0));
+
+        // Negative offset of the shadow extends the text to the top.
+        text.set_outline(None);
+        assert_eq!(text.build(), size + Vector2::new(3.0, 1.0));
+        assert_eq!(text.get_lines()[0].x_offset, 0.0);
+        assert_eq!(text.get_lines()[0].y_offset, 1.0);
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range},
 };
 
 #[derive(Clone, Copy, PartialEq)]
@@ -24,6 +24,7 @@ pub struct GridDimension {
     desired_size: f32,
     actual_size: f32,
     location: f32,
+    stretch_weight: f32,
 }
 
 impl GridDimension {
@@ -33,6 +34,7 @@ impl GridDimension {
             desired_size,
             actual_size: 0.0,
             location: 0.0,
+            stretch_weight: 1.0,
         }
     }
 
@@ -44,6 +46,20 @@ impl GridDimension {
         Self::generic(SizeMode::Stretch, 0.0)
     }
 
+    /// Creates stretch dimension that takes a share of free space proportional to its weight,
+    /// for example a column with weight `2.0` will be twice as wide as a column with weight `1.0`
+    /// (which is the weight of [`Self::stretch`]). Negative weights are treated as zero.
+    pub fn stretch_weighted(weight: f32) -> Self {
+        Self {
+            stretch_weight: weight.max(0.0),
+            ..Self::stretch()
+        }
+    }
+
+    pub fn stretch_weight(&self) -> f32 {
+        self.stretch_weight
+    }
+
     pub fn auto() -> Self {
         Self::generic(SizeMode::Auto, 0.0)
     }
@@ -139,17 +155,15 @@ fn calc_total_size_of_non_stretch_dims(
     preset_size
 }
 
-fn count_stretch_dims(dims: &[GridDimension]) -> usize {
-    let mut stretch_sized_dims = 0;
-    for dim in dims.iter() {
-        if dim.size_mode == SizeMode::Stretch {
-            stretch_sized_dims += 1;
-        }
-    }
-    stretch_sized_dims
+fn total_stretch_weight(dims: &[GridDimension]) -> f32 {
+    dims.iter()
+        .filter(|dim| dim.size_mode == SizeMode::Stretch)
+        .map(|dim| dim.stretch_weight)
+        .sum()
 }
 
-fn calc_avg_size_for_stretch_dim(
+// Returns size of free space per unit of stretch weight.
+fn calc_unit_size_for_stretch_dim(
     dims: &[GridDimension],
     children: &[Handle<UiNode>],
     available_size: f32,
@@ -160,16 +174,16 @@ fn calc_avg_size_for_stretch_dim(
 
     let rest_width = available_size - preset_size;
 
-    let stretch_sized_dims = count_stretch_dims(dims);
-    if stretch_sized_dims > 0 {
-        rest_width / stretch_sized_dims as f32
+    let total_weight = total_stretch_weight(dims);
+    if total_weight > 0.0 {
+        rest_width / total_weight
     } else {
         0.0
     }
 }
 
 fn fetch_width(child: &UiNode, i: usize) -> Option<f32> {
-    if child.column() == i && child.visibility() {
+    if child.column() == i && child.column_span() == 1 && child.visibility() {
         Some(child.desired_size().x)
     } else {
         None
@@ -177,13 +191,54 @@ fn fetch_width(child: &UiNode, i: usize) -> Option<f32> {
 }
 
 fn fetch_height(child: &UiNode, i: usize) -> Option<f32> {
-    if child.row() == i && child.visibility() {
+    if child.row() == i && child.row_span() == 1 && child.visibility() {
         Some(child.desired_size().y)
     } else {
         None
     }
 }
 
+// Returns range of dimensions covered by a child, span that goes past the last dimension is
+// clamped.
+fn span_range(start: usize, span: usize, count: usize) -> Range<usize> {
+    start.min(count)..start.saturating_add(span).min(count)
+}
+
+fn span_constraint(dims: &[GridDimension], available_size: f32) -> f32 {
+    if dims.iter().all(|dim| dim.size_mode == SizeMode::Strict) {
+        dims.iter().map(|dim| dim.desired_size).sum()
+    } else {
+        available_size
+    }
+}
+
+// Grows last auto-sized dimension of the span, so the span will fit given size.
+fn fit_span(dims: &mut [GridDimension], size: f32) {
+    let span_size = dims.iter().map(|dim| dim.actual_size).sum::<f32>();
+    if size > span_size {
+        if let Some(dim) = dims
+            .iter_mut()
+            .rev()
+            .find(|dim| dim.size_mode == SizeMode::Auto)
+        {
+            dim.actual_size += size - span_size;
+        }
+    }
+}
+
+// Returns location and size of a span of arranged dimensions.
+fn span_bounds(dims: &[GridDimension], range: Range<usize>) -> Option<(f32, f32)> {
+    if range.is_empty() {
+        return None;
+    }
+    let first = &dims[range.start];
+    let last = &dims[range.end - 1];
+    Some((
+        first.location,
+        last.location + last.actual_size - first.location,
+    ))
+}
+
 fn arrange_dims(dims: &mut [GridDimension], final_size: f32) {
     let mut preset_width = 0.0;
     for dim in dims.iter() {
@@ -192,9 +247,9 @@ fn arrange_dims(dims: &mut [GridDimension], final_size: f32) {
         }
     }
 
-    let stretch_count = count_stretch_dims(dims);
-    let avg_size = if stretch_count > 0 {
-        (final_size - preset_width) / stretch_count as f32
+    let total_weight = total_stretch_weight(dims);
+    let unit_size = if total_weight > 0.0 {
+        (final_size - preset_width) / total_weight
     } else {
         0.0
     };
@@ -204,7 +259,7 @@ fn arrange_dims(dims: &mut [GridDimension], final_size: f32) {
         dim.location = location;
         location += match dim.size_mode {
             SizeMode::Strict | SizeMode::Auto => dim.actual_size,
-            SizeMode::Stretch => avg_size,
+            SizeMode::Stretch => unit_size * dim.stretch_weight,
         };
     }
 }
@@ -226,9 +281,15 @@ impl Control for Grid {
         let mut groups = self.groups.borrow_mut();
         let mut cells = self.cells.borrow_mut();
 
+        let padding = self.widget.padding();
+        let available_size = Vector2::new(
+            (available_size.x - padding.horizontal()).max(0.0),
+            (available_size.y - padding.vertical()).max(0.0),
+        );
+
         // In case of no rows or columns, grid acts like default panel.
         if columns.is_empty() || rows.is_empty() {
-            return self.widget.measure_override(ui, available_size);
+            return self.widget.measure_override(ui, available_size) + padding.axes_margin();
         }
 
         for row in rows.iter_mut() {
@@ -252,7 +313,11 @@ impl Control for Grid {
                         .iter()
                         .filter_map(|&c| {
                             let child_ref = ui.node(c);
-                            if child_ref.row() == row_index && child_ref.column() == column_index {
+                            if child_ref.row() == row_index
+                                && child_ref.column() == column_index
+                                && child_ref.row_span() == 1
+                                && child_ref.column_span() == 1
+                            {
                                 Some(c)
                             } else {
                                 None
@@ -271,21 +336,21 @@ impl Control for Grid {
             for &cell_index in group.iter() {
                 let cell = &cells[cell_index];
 
-                let stretch_sized_width = calc_avg_size_for_stretch_dim(
+                let stretch_sized_width = calc_unit_size_for_stretch_dim(
                     &columns,
                     self.children(),
                     available_size.x,
                     ui,
                     fetch_width,
-                );
+                ) * columns[cell.column_index].stretch_weight;
 
-                let stretch_sized_height = calc_avg_size_for_stretch_dim(
+                let stretch_sized_height = calc_unit_size_for_stretch_dim(
                     &rows,
                     self.children(),
                     available_size.y,
                     ui,
                     fetch_height,
-                );
+                ) * rows[cell.row_index].stretch_weight;
 
                 let child_constraint = Vector2::new(
                     cell.width_constraint.unwrap_or(stretch_sized_width),
@@ -311,6 +376,33 @@ impl Control for Grid {
             }
         }
 
+        // Children that span several cells are measured after regular cells, with the size of the
+        // area they cover. If such child does not fit, last auto-sized row or column of the area
+        // grows to fit the child.
+        for &child in self.children() {
+            let child_ref = ui.node(child);
+            if child_ref.row_span() == 1 && child_ref.column_span() == 1 {
+                continue;
+            }
+
+            let column_range =
+                span_range(child_ref.column(), child_ref.column_span(), columns.len());
+            let row_range = span_range(child_ref.row(), child_ref.row_span(), rows.len());
+            if column_range.is_empty() || row_range.is_empty() {
+                continue;
+            }
+
+            let constraint = Vector2::new(
+                span_constraint(&columns[column_range.clone()], available_size.x),
+                span_constraint(&rows[row_range.clone()], available_size.y),
+            );
+            ui.measure_node(child, constraint);
+
+            let desired_size = ui.node(child).desired_size();
+            fit_span(&mut columns[column_range], desired_size.x);
+            fit_span(&mut rows[row_range], desired_size.y);
+        }
+
         let mut desired_size = Vector2::default();
         // Step 4. Calculate desired size of grid.
         for column in columns.iter() {
@@ -319,7 +411,7 @@ impl Control for Grid {
         for row in rows.iter() {
             desired_size.y += row.actual_size;
         }
-        desired_size
+        desired_size + padding.axes_margin()
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
@@ -328,31 +420,31 @@ impl Control for Grid {
         let mut columns = self.columns.borrow_mut();
         let mut rows = self.rows.borrow_mut();
 
+        let padding = self.widget.padding();
+        let inner_rect = padding.deflate_rect(&Rect::new(0.0, 0.0, final_size.x, final_size.y));
+
         if columns.is_empty() || rows.is_empty() {
-            let rect = Rect::new(0.0, 0.0, final_size.x, final_size.y);
             for child_handle in self.widget.children() {
-                ui.arrange_node(*child_handle, &rect);
+                ui.arrange_node(*child_handle, &inner_rect);
             }
             return final_size;
         }
 
-        arrange_dims(&mut columns, final_size.x);
-        arrange_dims(&mut rows, final_size.y);
+        arrange_dims(&mut columns, inner_rect.w());
+        arrange_dims(&mut rows, inner_rect.h());
 
         for child_handle in self.widget.children() {
             let child = ui.nodes.borrow(*child_handle);
-            if let Some(column) = columns.get(child.column()) {
-                if let Some(row) = rows.get(child.row()) {
-                    ui.arrange_node(
-                        *child_handle,
-                        &Rect::new(
-                            column.location,
-                            row.location,
-                            column.actual_size,
-                            row.actual_size,
-                        ),
-                    );
-                }
+            let column_range = span_range(child.column(), child.column_span(), columns.len());
+            let row_range = span_range(child.row(), child.row_span(), rows.len());
+            if let (Some((x, width)), Some((y, height))) = (
+                span_bounds(&columns, column_range),
+                span_bounds(&rows, row_range),
+            ) {
+                ui.arrange_node(
+                    *child_handle,
+                    &Rect::new(inner_rect.x() + x, inner_rect.y() + y, width, height),
+                );
             }
         }
 
@@ -373,14 +465,18 @@ impl Control for Grid {
             drawing_context.push_line(right_bottom, left_bottom, self.border_thickness);
             drawing_context.push_line(left_bottom, left_top, self.border_thickness);
 
+            // Locations of rows and columns are relative to the padded area.
+            let offset = self.widget.padding().offset();
             for column in self.columns.borrow().iter() {
-                let a = Vector2::new(bounds.x() + column.location, bounds.y());
-                let b = Vector2::new(bounds.x() + column.location, bounds.y() + bounds.h());
+                let x = bounds.x() + offset.x + column.location;
+                let a = Vector2::new(x, bounds.y());
+                let b = Vector2::new(x, bounds.y() + bounds.h());
                 drawing_context.push_line(a, b, self.border_thickness);
             }
             for row in self.rows.borrow().iter() {
-                let a = Vector2::new(bounds.x(), bounds.y() + row.location);
-                let b = Vector2::new(bounds.x() + bounds.w(), bounds.y() + row.location);
+                let y = bounds.y() + offset.y + row.location;
+                let a = Vector2::new(bounds.x(), y);
+                let b = Vector2::new(bounds.x() + bounds.w(), y);
                 drawing_context.push_line(a, b, self.border_thickness);
             }
 
@@ -518,3 +614,107 @@ impl Grid {
         self.border_thickness
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        core::algebra::Vector2,
+        grid::{Column, GridBuilder, Row},
+        widget::WidgetBuilder,
+        HorizontalAlignment, UserInterface,
+    };
+
+    #[test]
+    fn weighted_stretch() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let cells = (0..3)
+            .map(|column| {
+                BorderBuilder::new(WidgetBuilder::new().on_column(column))
+                    .build(&mut ui.build_ctx())
+            })
+            .collect::<Vec<_>>();
+        GridBuilder::new(
+            WidgetBuilder::new()
+                .with_width(100.0)
+                .with_height(10.0)
+                .with_children(cells.iter().cloned()),
+        )
+        .add_columns(vec![
+            Column::strict(10.0),
+            Column::stretch_weighted(2.0),
+            Column::stretch(),
+        ])
+        .add_row(Row::stretch())
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        // 90 pixels of free space are split as 2:1.
+        assert_eq!(ui.node(cells[1]).actual_local_position().x, 10.0);
+        assert_eq!(ui.node(cells[1]).actual_size().x, 60.0);
+        assert_eq!(ui.node(cells[2]).actual_local_position().x, 70.0);
+        assert_eq!(ui.node(cells[2]).actual_size().x, 30.0);
+    }
+
+    #[test]
+    fn strict_span() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        // Span goes past the last column, it must be clamped.
+        let spanned = BorderBuilder::new(
+            WidgetBuilder::new()
+                .on_column(1)
+                .with_column_span(5)
+                .with_row_span(2),
+        )
+        .build(&mut ui.build_ctx());
+        GridBuilder::new(WidgetBuilder::new().with_child(spanned))
+            .add_columns(vec![
+                Column::strict(10.0),
+                Column::strict(20.0),
+                Column::strict(30.0),
+            ])
+            .add_rows(vec![Row::strict(5.0), Row::strict(5.0)])
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let spanned_ref = ui.node(spanned);
+        assert_eq!(spanned_ref.actual_local_position(), Vector2::new(10.0, 0.0));
+        assert_eq!(spanned_ref.actual_size(), Vector2::new(50.0, 10.0));
+    }
+
+    #[test]
+    fn auto_span() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let first = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(10.0)
+                .with_horizontal_alignment(HorizontalAlignment::Left),
+        )
+        .build(&mut ui.build_ctx());
+        let spanned = BorderBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .with_column_span(2)
+                .with_width(50.0)
+                .with_horizontal_alignment(HorizontalAlignment::Left),
+        )
+        .build(&mut ui.build_ctx());
+        let grid = GridBuilder::new(WidgetBuilder::new().with_child(first).with_child(spanned))
+            .add_columns(vec![Column::auto(), Column::auto()])
+            .add_rows(vec![Row::strict(5.0), Row::strict(5.0)])
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        // Spanned child does not widen first column, the last column of the span grows instead.
+        assert_eq!(ui.node(grid).desired_size(), Vector2::new(50.0, 10.0));
+        assert_eq!(ui.node(first).actual_size().x, 10.0);
+        assert_eq!(
+            ui.node(spanned).actual_local_position(),
+            Vector2::new(0.0, 5.0)
+        );
+        assert_eq!(ui.node(spanned).actual_size().x, 50.0);
+    }
+}
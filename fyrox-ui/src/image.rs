@@ -1,6 +1,6 @@
 use crate::{
     brush::Brush,
-    core::{algebra::Vector2, color::Color, pool::Handle},
+    core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
     define_constructor,
     draw::{CommandTexture, Draw, DrawingContext, SharedTexture},
     message::{MessageDirection, UiMessage},
@@ -16,11 +16,105 @@ use std::{
 pub enum ImageMessage {
     Texture(Option<SharedTexture>),
     Flip(bool),
+    Stretch(Stretch),
+    TextureSize(Vector2<f32>),
 }
 
 impl ImageMessage {
     define_constructor!(ImageMessage:Texture => fn texture(Option<SharedTexture>), layout: false);
     define_constructor!(ImageMessage:Flip => fn flip(bool), layout: false);
+    define_constructor!(ImageMessage:Stretch => fn stretch(Stretch), layout: false);
+    define_constructor!(ImageMessage:TextureSize => fn texture_size(Vector2<f32>), layout: false);
+}
+
+/// Defines how an image is fitted into bounds of the widget. Every mode except [`Stretch::Fill`]
+/// needs to know size of the texture, see [`ImageBuilder::with_texture_size`]. Without it,
+/// the image is always stretched to fill the bounds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stretch {
+    /// Image fills the bounds, aspect ratio is not preserved.
+    Fill,
+    /// Image is scaled to fit into the bounds with preserved aspect ratio, it may leave empty
+    /// space on the sides.
+    Uniform,
+    /// Image is scaled to cover the bounds with preserved aspect ratio, parts of the image that
+    /// do not fit are cropped.
+    UniformToFill,
+    /// Image keeps its size and is centered in the bounds, parts of the image that do not fit
+    /// are cropped.
+    Center,
+}
+
+impl Default for Stretch {
+    fn default() -> Self {
+        Self::Fill
+    }
+}
+
+/// Calculates screen rectangle and texture coordinates of an image. `None` is returned if the
+/// image is not visible at all.
+fn stretch_image(
+    bounds: Rect<f32>,
+    texture_size: Vector2<f32>,
+    stretch: Stretch,
+    flip: bool,
+) -> Option<(Rect<f32>, [Vector2<f32>; 4])> {
+    let scale = if texture_size.x > 0.0 && texture_size.y > 0.0 {
+        let scale_x = bounds.w() / texture_size.x;
+        let scale_y = bounds.h() / texture_size.y;
+        match stretch {
+            Stretch::Fill => None,
+            Stretch::Uniform => Some(scale_x.min(scale_y)),
+            Stretch::UniformToFill => Some(scale_x.max(scale_y)),
+            Stretch::Center => Some(1.0),
+        }
+    } else {
+        None
+    };
+
+    // Rectangle of the whole image, it could be larger than the bounds.
+    let image = match scale {
+        Some(scale) => {
+            let size = texture_size.scale(scale);
+            Rect::new(
+                bounds.x() + (bounds.w() - size.x) * 0.5,
+                bounds.y() + (bounds.h() - size.y) * 0.5,
+                size.x,
+                size.y,
+            )
+        }
+        None => bounds,
+    };
+
+    if image.w() <= 0.0 || image.h() <= 0.0 {
+        return None;
+    }
+    let rect = if image == bounds {
+        bounds
+    } else {
+        image.intersection(bounds)?
+    };
+
+    let u0 = (rect.x() - image.x()) / image.w();
+    let u1 = (rect.x() + rect.w() - image.x()) / image.w();
+    let (mut v0, mut v1) = (
+        (rect.y() - image.y()) / image.h(),
+        (rect.y() + rect.h() - image.y()) / image.h(),
+    );
+    if flip {
+        v0 = -v0;
+        v1 = -v1;
+    }
+
+    Some((
+        rect,
+        [
+            Vector2::new(u0, v0),
+            Vector2::new(u1, v0),
+            Vector2::new(u1, v1),
+            Vector2::new(u0, v1),
+        ],
+    ))
 }
 
 #[derive(Clone)]
@@ -28,6 +122,8 @@ pub struct Image {
     widget: Widget,
     texture: Option<SharedTexture>,
     flip: bool,
+    stretch: Stretch,
+    texture_size: Vector2<f32>,
 }
 
 crate::define_widget_deref!(Image);
@@ -38,6 +134,8 @@ impl Image {
             widget,
             texture: None,
             flip: false,
+            stretch: Default::default(),
+            texture_size: Default::default(),
         }
     }
 
@@ -48,6 +146,24 @@ impl Image {
     pub fn texture(&self) -> Option<SharedTexture> {
         self.texture.clone()
     }
+
+    pub fn set_stretch(&mut self, stretch: Stretch) {
+        self.stretch = stretch;
+    }
+
+    pub fn stretch(&self) -> Stretch {
+        self.stretch
+    }
+
+    /// Sets size of the texture in pixels, it is used by stretch modes to preserve aspect ratio
+    /// of the image. Zero size means that the size is unknown.
+    pub fn set_texture_size(&mut self, texture_size: Vector2<f32>) {
+        self.texture_size = texture_size;
+    }
+
+    pub fn texture_size(&self) -> Vector2<f32> {
+        self.texture_size
+    }
 }
 
 impl Control for Image {
@@ -61,17 +177,12 @@ impl Control for Image {
 
     fn draw(&self, drawing_context: &mut DrawingContext) {
         let bounds = self.widget.screen_bounds();
-        let tex_coords = if self.flip {
-            Some([
-                Vector2::new(0.0, 0.0),
-                Vector2::new(1.0, 0.0),
-                Vector2::new(1.0, -1.0),
-                Vector2::new(0.0, -1.0),
-            ])
-        } else {
-            None
-        };
-        drawing_context.push_rect_filled(&bounds, tex_coords.as_ref());
+        let (rect, tex_coords) =
+            match stretch_image(bounds, self.texture_size, self.stretch, self.flip) {
+                Some(image) => image,
+                None => return,
+            };
+        drawing_context.push_rect_filled(&rect, Some(&tex_coords));
         let texture = self
             .texture
             .as_ref()
@@ -91,6 +202,18 @@ impl Control for Image {
                     &ImageMessage::Flip(flip) => {
                         self.flip = flip;
                     }
+                    &ImageMessage::Stretch(stretch) => {
+                        if self.stretch != stretch {
+                            self.stretch = stretch;
+                            self.invalidate_visual();
+                        }
+                    }
+                    &ImageMessage::TextureSize(texture_size) => {
+                        if self.texture_size != texture_size {
+                            self.texture_size = texture_size;
+                            self.invalidate_visual();
+                        }
+                    }
                 }
             }
         }
@@ -101,6 +224,8 @@ pub struct ImageBuilder {
     widget_builder: WidgetBuilder,
     texture: Option<SharedTexture>,
     flip: bool,
+    stretch: Stretch,
+    texture_size: Vector2<f32>,
 }
 
 impl ImageBuilder {
@@ -109,6 +234,8 @@ impl ImageBuilder {
             widget_builder,
             texture: None,
             flip: false,
+            stretch: Default::default(),
+            texture_size: Default::default(),
         }
     }
 
@@ -127,6 +254,20 @@ impl ImageBuilder {
         self
     }
 
+    /// Sets how the image should be fitted into bounds of the widget, default is
+    /// [`Stretch::Fill`].
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Sets size of the texture in pixels, it is required for every stretch mode except
+    /// [`Stretch::Fill`].
+    pub fn with_texture_size(mut self, texture_size: Vector2<f32>) -> Self {
+        self.texture_size = texture_size;
+        self
+    }
+
     pub fn build_node(mut self) -> UiNode {
         if self.widget_builder.background.is_none() {
             self.widget_builder.background = Some(Brush::Solid(Color::WHITE))
@@ -136,6 +277,8 @@ impl ImageBuilder {
             widget: self.widget_builder.build(),
             texture: self.texture,
             flip: self.flip,
+            stretch: self.stretch,
+            texture_size: self.texture_size,
         };
         UiNode::new(image)
     }
@@ -144,3 +287,49 @@ impl ImageBuilder {
         ctx.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, math::Rect},
+        image::{stretch_image, Stretch},
+    };
+
+    #[test]
+    fn stretch_modes() {
+        let bounds = Rect::new(0.0, 0.0, 200.0, 100.0);
+        let texture_size = Vector2::new(100.0, 100.0);
+        let full = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ];
+
+        let (rect, tex_coords) = stretch_image(bounds, texture_size, Stretch::Fill, false).unwrap();
+        assert_eq!((rect, tex_coords), (bounds, full));
+
+        // Size of the texture is unknown.
+        let (rect, _) = stretch_image(bounds, Vector2::default(), Stretch::Uniform, false).unwrap();
+        assert_eq!(rect, bounds);
+
+        let (rect, tex_coords) =
+            stretch_image(bounds, texture_size, Stretch::Uniform, false).unwrap();
+        assert_eq!(
+            (rect, tex_coords),
+            (Rect::new(50.0, 0.0, 100.0, 100.0), full)
+        );
+
+        let (rect, tex_coords) =
+            stretch_image(bounds, texture_size, Stretch::UniformToFill, false).unwrap();
+        assert_eq!(rect, bounds);
+        assert_eq!(tex_coords[0], Vector2::new(0.0, 0.25));
+        assert_eq!(tex_coords[2], Vector2::new(1.0, 0.75));
+
+        let (rect, tex_coords) =
+            stretch_image(bounds, Vector2::new(50.0, 200.0), Stretch::Center, true).unwrap();
+        assert_eq!(rect, Rect::new(75.0, 0.0, 50.0, 100.0));
+        assert_eq!(tex_coords[0], Vector2::new(0.0, -0.25));
+        assert_eq!(tex_coords[2], Vector2::new(1.0, -0.75));
+    }
+}
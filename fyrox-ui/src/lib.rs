@@ -14,6 +14,7 @@ extern crate lazy_static;
 
 pub use fyrox_core as core;
 
+pub mod binding;
 pub mod border;
 pub mod brush;
 pub mod button;
@@ -32,23 +33,27 @@ pub mod grid;
 pub mod image;
 pub mod inspector;
 pub mod list_view;
+pub mod localization;
 pub mod menu;
 pub mod message;
 pub mod messagebox;
 pub mod numeric;
 pub mod popup;
 pub mod progress_bar;
+pub mod radio_button;
 pub mod range;
 pub mod rect;
 pub mod scroll_bar;
 pub mod scroll_panel;
 pub mod scroll_viewer;
+pub mod slider;
 pub mod stack_panel;
 pub mod tab_control;
 pub mod text;
 pub mod text_box;
 pub mod tree;
 pub mod ttf;
+pub mod tween;
 pub mod utils;
 pub mod vec;
 pub mod vector_image;
@@ -57,22 +62,26 @@ pub mod window;
 pub mod wrap_panel;
 
 use crate::{
+    binding::{AnyBinding, Binding, ValueMessage},
     brush::Brush,
     canvas::Canvas,
     core::{
         algebra::Vector2,
         color::Color,
-        math::{clampf, Rect},
-        pool::{Handle, Pool},
+        math::{clampf, Rect, Vector2Ext},
+        pool::{Handle, Pool, Ticket},
         scope_profile,
     },
     draw::{CommandTexture, Draw, DrawingContext},
+    localization::LocalizationTable,
     message::{
-        ButtonState, CursorIcon, KeyboardModifiers, MessageDirection, MouseButton, OsEvent,
-        UiMessage,
+        ButtonState, CursorIcon, ImeEvent, KeyCode, KeyboardModifiers, MessageDirection,
+        MouseButton, NavigationDirection, OsEvent, TouchPhase, UiMessage,
     },
     popup::{Placement, PopupMessage},
+    text::Text,
     ttf::{Font, SharedFont},
+    tween::{Easing, Tween, TweenProperty},
     widget::{Widget, WidgetBuilder, WidgetMessage},
 };
 use copypasta::ClipboardContext;
@@ -80,9 +89,10 @@ use fxhash::{FxHashMap, FxHashSet};
 use std::{
     any::{Any, TypeId},
     cell::Cell,
+    cmp::Ordering,
     collections::VecDeque,
     fmt::Debug,
-    ops::{Deref, DerefMut, Index, IndexMut},
+    ops::{Add, Deref, DerefMut, Index, IndexMut, Sub},
     sync::{
         mpsc::{self, Receiver, Sender, TryRecvError},
         Arc, Mutex,
@@ -151,6 +161,15 @@ pub enum Orientation {
 }
 
 impl Thickness {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
     pub fn zero() -> Self {
         Self {
             left: 0.0,
@@ -205,13 +224,92 @@ impl Thickness {
         }
     }
 
+    /// Creates thickness with the same value on the left and right sides.
+    pub fn left_right(v: f32) -> Self {
+        Self {
+            left: v,
+            top: 0.0,
+            right: v,
+            bottom: 0.0,
+        }
+    }
+
+    /// Creates thickness with the same value on the top and bottom sides.
+    pub fn top_bottom(v: f32) -> Self {
+        Self {
+            left: 0.0,
+            top: v,
+            right: 0.0,
+            bottom: v,
+        }
+    }
+
     pub fn offset(&self) -> Vector2<f32> {
         Vector2::new(self.left, self.top)
     }
 
+    /// Returns sum of left and right sides.
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// Returns sum of top and bottom sides.
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
     /// Returns margin for each axis.
     pub fn axes_margin(&self) -> Vector2<f32> {
-        Vector2::new(self.left + self.right, self.top + self.bottom)
+        Vector2::new(self.horizontal(), self.vertical())
+    }
+
+    /// Shrinks given rectangle by the thickness on each side. Size of the result is clamped to
+    /// zero.
+    #[must_use = "this method creates new instance of rect"]
+    pub fn deflate_rect(&self, rect: &Rect<f32>) -> Rect<f32> {
+        Rect::new(
+            rect.x() + self.left,
+            rect.y() + self.top,
+            (rect.w() - self.horizontal()).max(0.0),
+            (rect.h() - self.vertical()).max(0.0),
+        )
+    }
+
+    /// Expands given rectangle by the thickness on each side.
+    #[must_use = "this method creates new instance of rect"]
+    pub fn inflate_rect(&self, rect: &Rect<f32>) -> Rect<f32> {
+        Rect::new(
+            rect.x() - self.left,
+            rect.y() - self.top,
+            rect.w() + self.horizontal(),
+            rect.h() + self.vertical(),
+        )
+    }
+}
+
+impl Add for Thickness {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            left: self.left + rhs.left,
+            top: self.top + rhs.top,
+            right: self.right + rhs.right,
+            bottom: self.bottom + rhs.bottom,
+        }
+    }
+}
+
+impl Sub for Thickness {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            left: self.left - rhs.left,
+            top: self.top - rhs.top,
+            right: self.right - rhs.right,
+            bottom: self.bottom - rhs.bottom,
+        }
     }
 }
 
@@ -252,6 +350,32 @@ impl NodeHandleMapping {
     }
 }
 
+/// Detached hierarchy of widgets that is not part of any user interface yet. Templates are
+/// produced by [`UserInterface::build_detached`] and can be instantiated any number of times
+/// under any parent using [`UserInterface::instantiate`].
+///
+/// # Limitations
+///
+/// Only the nodes of the hierarchy are stored in a template; widgets that are created by
+/// builders outside of the hierarchy (tooltips, context menus, popups) are not captured.
+pub struct UiTemplate {
+    nodes: FxHashMap<Handle<UiNode>, UiNode>,
+    root: Handle<UiNode>,
+}
+
+impl UiTemplate {
+    /// Returns handle of the root node of the template. The handle is valid only inside the
+    /// template, use handle returned by [`UserInterface::instantiate`] to access instances.
+    pub fn root(&self) -> Handle<UiNode> {
+        self.root
+    }
+
+    /// Returns total amount of nodes in the template.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
 pub trait BaseControl: 'static {
     fn as_any(&self) -> &dyn Any;
 
@@ -289,18 +413,48 @@ pub trait Control: BaseControl + Deref<Target = Widget> + DerefMut {
 
     fn on_remove(&self, _sender: &Sender<UiMessage>) {}
 
+    /// Calculates desired size of the widget. This is the first pass of the layout system and it
+    /// is the main extension point for custom layout panels (radial menus, hexagonal grids, etc.) -
+    /// there is no need to modify the library to add a new container, just implement this method
+    /// together with [`Control::arrange_override`].
+    ///
+    /// # Implementation
+    ///
+    /// Implementation must call [`UserInterface::measure_node`] for every child that should take
+    /// part in the layout and then return the size the widget wants to have, the size must not
+    /// include margin of the widget - it is added by the layout system. Default implementation
+    /// measures every child with full `available_size` and returns the largest desired size.
     fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
         self.deref().measure_override(ui, available_size)
     }
 
+    /// Places children of the widget inside of given `final_size`. This is the second pass of
+    /// the layout system, it is called after [`Control::measure_override`] so desired sizes of
+    /// children are known at this point.
+    ///
+    /// # Implementation
+    ///
+    /// Implementation must call [`UserInterface::arrange_node`] for every child with a rectangle
+    /// in local coordinates of the widget and return actual size of the widget. Default
+    /// implementation stretches every child to the full `final_size`.
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
         self.deref().arrange_override(ui, final_size)
     }
 
+    /// Emits drawing commands of the widget. Custom widgets (a radar sweep, a minimap, etc.) can
+    /// put any geometry here using methods of [`draw::Draw`] trait and then [`DrawingContext::commit`]
+    /// it with a brush and a texture.
+    ///
+    /// # Implementation
+    ///
+    /// All coordinates are in screen space, use [`Widget::screen_bounds`] to get bounds of the
+    /// widget and always pass [`Widget::clip_bounds`] when committing geometry, so the widget will
+    /// be correctly clipped by its parents. Opacity and drawing order are handled by the library:
+    /// the method is called before children of the widget are drawn, so children will be on top.
     fn draw(&self, _drawing_context: &mut DrawingContext) {}
 
     fn update(&mut self, _dt: f32, _sender: &Sender<UiMessage>) {}
@@ -314,6 +468,23 @@ pub trait Control: BaseControl + Deref<Target = Widget> + DerefMut {
     /// to check if event came from/for this node or to capture input on node.
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage);
 
+    /// Allows a widget to intercept a message before it reaches its destination. Tunneling is the
+    /// first phase of message routing, the message goes from the root of the tree down to its
+    /// destination, so parents have a chance to "see" the message before their children. Typical
+    /// use case is a drag-scroll panel that wants first crack at mouse moves before its children.
+    ///
+    /// Mark the message as handled (`message.set_handled(true)`) to stop tunneling, the message
+    /// will then be passed to bubbling phase ([`Control::handle_routed_message`]) as usual, but
+    /// with `handled` flag set.
+    ///
+    /// # Notes
+    ///
+    /// Same restrictions as for [`Control::handle_routed_message`] applies here - do *not* try to
+    /// borrow the node in UI, it is moved out of the pool at this moment.
+    fn handle_tunneled_message(&mut self, _ui: &mut UserInterface, _message: &mut UiMessage) {
+        // This method is optional.
+    }
+
     /// Used to react to a message (by producing another message) that was posted outside of current
     /// hierarchy. In other words this method is used when you need to "peek" a message before it'll
     /// be passed into bubbling router. Most common use case is to catch messages from popups: popup
@@ -372,6 +543,109 @@ impl Default for DragContext {
     }
 }
 
+/// A set of thresholds that is used to recognize high-level gestures (long press and double
+/// click) from a raw stream of press/move/release events.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GestureSettings {
+    /// Time (in seconds) that a button must be held to fire [`WidgetMessage::LongPress`].
+    pub long_press_time: f32,
+    /// Maximum distance (in pixels) that cursor can travel from press point while a button is
+    /// held, otherwise long press will be cancelled.
+    pub long_press_distance: f32,
+    /// Maximum time (in seconds) between two clicks to fire [`WidgetMessage::DoubleClick`].
+    pub double_click_time: f32,
+    /// Maximum distance (in pixels) between two clicks to fire [`WidgetMessage::DoubleClick`].
+    pub double_click_distance: f32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            long_press_time: 0.6,
+            long_press_distance: 5.0,
+            double_click_time: 0.4,
+            double_click_distance: 5.0,
+        }
+    }
+}
+
+impl GestureSettings {
+    /// Returns thresholds suitable for touch screens. Fingers are much less precise than mouse,
+    /// so distance thresholds are larger.
+    pub fn touch() -> Self {
+        Self {
+            long_press_time: 0.5,
+            long_press_distance: 16.0,
+            double_click_time: 0.4,
+            double_click_distance: 24.0,
+        }
+    }
+}
+
+/// Defines how mouse capture affects other nodes, see [`UserInterface::capture_mouse_with_mode`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CaptureMode {
+    /// Captured node is considered the only node under the cursor, hover state of other nodes
+    /// is frozen until the capture is released.
+    Exclusive,
+    /// Mouse input is still routed to captured node, but hover state (enter/leave messages,
+    /// drag over messages, hover path) follows the topmost node under the cursor. Useful for
+    /// drag and drop, when a drop target under the cursor must be highlighted.
+    HoverThrough,
+}
+
+impl Default for CaptureMode {
+    fn default() -> Self {
+        Self::Exclusive
+    }
+}
+
+/// Defines what happens when focus navigation cannot find a focusable node in requested direction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NavigationEdgeBehavior {
+    /// Focus stays on current node.
+    Stop,
+    /// Focus moves to the farthest focusable node on the opposite side, like in most console
+    /// menus.
+    Wrap,
+}
+
+/// Settings of directional focus navigation, see [`UserInterface::navigate_focus`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NavigationSettings {
+    /// Behavior at the edges of the UI.
+    pub edge_behavior: NavigationEdgeBehavior,
+    /// Weight of a distance perpendicular to navigation direction. The larger the value, the
+    /// more navigation prefers nodes that are aligned with currently focused node.
+    pub cross_axis_weight: f32,
+    /// If `true`, Tab key moves focus to the next focusable node and Shift+Tab - to the previous
+    /// one, see [`UserInterface::focus_next`]. Tab key is not passed to focused node then.
+    pub tab_navigation: bool,
+}
+
+impl Default for NavigationSettings {
+    fn default() -> Self {
+        Self {
+            edge_behavior: NavigationEdgeBehavior::Stop,
+            cross_axis_weight: 2.0,
+            tab_navigation: true,
+        }
+    }
+}
+
+#[derive(Default)]
+struct GestureContext {
+    press_node: Handle<UiNode>,
+    press_button: Option<MouseButton>,
+    press_pos: Vector2<f32>,
+    press_time: f32,
+    long_press_fired: bool,
+    last_click_node: Handle<UiNode>,
+    last_click_button: Option<MouseButton>,
+    last_click_pos: Vector2<f32>,
+    time_since_last_click: f32,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct MouseState {
     left: ButtonState,
@@ -436,6 +710,18 @@ impl<'a> BuildContext<'a> {
     pub fn copy(&mut self, node: Handle<UiNode>) -> Handle<UiNode> {
         self.ui.copy_node(node)
     }
+
+    pub fn default_font(&self) -> SharedFont {
+        self.ui.default_font()
+    }
+
+    pub fn font_by_name(&self, name: &str) -> Option<SharedFont> {
+        self.ui.font_by_name(name)
+    }
+
+    pub fn localization(&self) -> &LocalizationTable {
+        self.ui.localization()
+    }
 }
 
 impl<'a> Index<Handle<UiNode>> for BuildContext<'a> {
@@ -467,6 +753,58 @@ pub struct RestrictionEntry {
     pub stop: bool,
 }
 
+/// Statistics of incremental redraw of the user interface. Draw commands of a node are reused
+/// between frames while the node does not receive messages and its bounds stay the same, so
+/// mostly static interfaces (like a HUD with a single changing counter) re-emit only a few
+/// commands per frame. See [`UserInterface::redraw_statistics`].
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct RedrawStatistics {
+    /// Amount of nodes that were drawn from scratch.
+    pub redrawn_nodes: usize,
+    /// Amount of nodes which draw commands were reused from previous frame.
+    pub reused_nodes: usize,
+    /// Screen-space rectangle that contains old and new geometry of every redrawn node. `None`
+    /// if nothing was changed.
+    pub dirty_region: Option<Rect<f32>>,
+}
+
+impl RedrawStatistics {
+    /// Returns area of the dirty region in square pixels.
+    pub fn dirty_area(&self) -> f32 {
+        self.dirty_region
+            .map_or(0.0, |region| region.w() * region.h())
+    }
+
+    fn add_dirty_bounds(&mut self, bounds: Rect<f32>) {
+        match self.dirty_region.as_mut() {
+            Some(dirty_region) => *dirty_region = dirty_region.union(bounds),
+            None => self.dirty_region = Some(bounds),
+        }
+    }
+}
+
+/// Statistics of layout pass of the user interface. Measurement and arrangement results are
+/// cached per node, only nodes with invalidated layout (and their ancestors) are processed, so
+/// layout of a static interface costs almost nothing. See [`UserInterface::layout_statistics`].
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct LayoutStatistics {
+    /// Amount of nodes that were measured.
+    pub measured_nodes: usize,
+    /// Amount of nodes that were arranged.
+    pub arranged_nodes: usize,
+}
+
+/// Sub-graph is a set of UI nodes extracted from user interface with their tickets, it keeps
+/// handles of the nodes reserved so they could be put back later. It is the same as sub-graph
+/// of a scene graph and intended to be used by UI editors with a command stack.
+pub struct SubGraph {
+    /// A root node and its ticket.
+    pub root: (Ticket<UiNode>, UiNode),
+
+    /// A set of descendant nodes with their tickets.
+    pub descendants: Vec<(Ticket<UiNode>, UiNode)>,
+}
+
 struct TooltipEntry {
     tooltip: Handle<UiNode>,
     /// Time remaining until this entry should disappear (in seconds).
@@ -510,7 +848,9 @@ pub struct UserInterface {
     root_canvas: Handle<UiNode>,
     picked_node: Handle<UiNode>,
     prev_picked_node: Handle<UiNode>,
+    hover_path: Vec<Handle<UiNode>>,
     captured_node: Handle<UiNode>,
+    capture_mode: CaptureMode,
     keyboard_focus_node: Handle<UiNode>,
     cursor_position: Vector2<f32>,
     receiver: Receiver<UiMessage>,
@@ -519,15 +859,41 @@ pub struct UserInterface {
     picking_stack: Vec<RestrictionEntry>,
     bubble_queue: VecDeque<Handle<UiNode>>,
     drag_context: DragContext,
+    gesture_context: GestureContext,
+    gesture_settings: GestureSettings,
+    navigation_settings: NavigationSettings,
     mouse_state: MouseState,
     keyboard_modifiers: KeyboardModifiers,
     cursor_icon: CursorIcon,
     active_tooltip: Option<TooltipEntry>,
+    tooltip_delay: f32,
+    /// Tooltip of a hovered node that waits for the delay to pass.
+    pending_tooltip: Handle<UiNode>,
+    pending_tooltip_time: f32,
+    /// Cursor position at which the tooltip delay was restarted last time.
+    tooltip_anchor: Vector2<f32>,
     preview_set: FxHashSet<Handle<UiNode>>,
     clipboard: Option<ClipboardContext>,
     layout_events_receiver: Receiver<LayoutEvent>,
     layout_events_sender: Sender<LayoutEvent>,
     need_update_global_transform: bool,
+    pixel_snap: bool,
+    default_font: SharedFont,
+    fonts: FxHashMap<String, SharedFont>,
+    localization: LocalizationTable,
+    drag_threshold: f32,
+    bindings: Vec<Box<dyn AnyBinding>>,
+    layout_complete_callbacks: Vec<Box<dyn FnOnce(&mut UserInterface)>>,
+    tweens: Vec<Tween>,
+    draw_frame: u64,
+    drawn_fonts: Vec<(SharedFont, usize)>,
+    /// Active touches and nodes that received them.
+    touches: FxHashMap<u64, Handle<UiNode>>,
+    /// Id of a touch that emulates mouse.
+    primary_touch: Option<u64>,
+    redraw_statistics: RedrawStatistics,
+    layout_statistics: Cell<LayoutStatistics>,
+    next_node_id: u64,
 }
 
 lazy_static! {
@@ -542,6 +908,9 @@ fn draw_node(
     nodes: &Pool<UiNode>,
     node_handle: Handle<UiNode>,
     drawing_context: &mut DrawingContext,
+    frame: u64,
+    full_redraw: bool,
+    statistics: &mut RedrawStatistics,
 ) {
     scope_profile!();
 
@@ -558,7 +927,7 @@ fn draw_node(
     let mut parent = node.parent();
     while parent.is_some() {
         let parent_node = nodes.borrow(parent);
-        if !parent_node.screen_bounds().intersects(bounds) {
+        if parent_node.clip_children() && !parent_node.screen_bounds().intersects(bounds) {
             return;
         }
         parent = parent_node.parent();
@@ -566,17 +935,49 @@ fn draw_node(
 
     let start_index = drawing_context.get_commands().len();
 
-    let pushed = if !is_node_enabled(nodes, node_handle) {
-        drawing_context.push_opacity(0.4);
-        true
-    } else if let Some(opacity) = node.opacity() {
+    // Opacities are composed, so the disabled look is applied only once, on the node where
+    // disabled sub-tree starts. Its descendants are disabled too, but they keep own opacity.
+    let is_disabled_root = !node.is_globally_enabled()
+        && nodes
+            .try_borrow(node.parent())
+            .map_or(true, |parent| parent.is_globally_enabled());
+    let opacity = if is_disabled_root {
+        Some(0.4 * node.opacity().unwrap_or(1.0))
+    } else {
+        node.opacity()
+    };
+    let pushed = if let Some(opacity) = opacity {
         drawing_context.push_opacity(opacity);
         true
     } else {
         false
     };
 
-    node.draw(drawing_context);
+    // Commands of previous frame could be reused only if the node looks exactly the same.
+    let clip_bounds = node.clip_bounds();
+    let (last_frame, last_bounds, last_clip_bounds) = node.last_draw.get();
+    if !full_redraw
+        && node.is_visual_valid()
+        && last_frame + 1 == frame
+        && last_bounds == bounds
+        && last_clip_bounds == clip_bounds
+    {
+        for &index in node.prev_command_indices.borrow().iter() {
+            drawing_context.reuse_command(index);
+        }
+        statistics.reused_nodes += 1;
+    } else {
+        for &index in node.prev_command_indices.borrow().iter() {
+            statistics.add_dirty_bounds(drawing_context.previous_commands()[index].bounds);
+        }
+        node.draw(drawing_context);
+        for command in &drawing_context.get_commands()[start_index..] {
+            statistics.add_dirty_bounds(command.bounds);
+        }
+        node.validate_visual();
+        statistics.redrawn_nodes += 1;
+    }
+    node.last_draw.set((frame, bounds, clip_bounds));
 
     let end_index = drawing_context.get_commands().len();
     for i in start_index..end_index {
@@ -587,7 +988,14 @@ fn draw_node(
     for &child_node in node.children().iter() {
         // Do not continue render of top-most nodes - they'll be rendered in separate pass.
         if !nodes[child_node].is_draw_on_top() {
-            draw_node(nodes, child_node, drawing_context);
+            draw_node(
+                nodes,
+                child_node,
+                drawing_context,
+                frame,
+                full_redraw,
+                statistics,
+            );
         }
     }
 
@@ -621,31 +1029,114 @@ impl UserInterface {
             receiver,
             visual_debug: false,
             captured_node: Handle::NONE,
+            capture_mode: Default::default(),
             root_canvas: Handle::NONE,
             nodes: Pool::new(),
             cursor_position: Vector2::new(0.0, 0.0),
             drawing_context: DrawingContext::new(),
             picked_node: Handle::NONE,
             prev_picked_node: Handle::NONE,
+            hover_path: Default::default(),
             keyboard_focus_node: Handle::NONE,
             stack: Default::default(),
             picking_stack: Default::default(),
             bubble_queue: Default::default(),
             drag_context: Default::default(),
+            gesture_context: Default::default(),
+            gesture_settings: Default::default(),
+            navigation_settings: Default::default(),
             mouse_state: Default::default(),
             keyboard_modifiers: Default::default(),
             cursor_icon: Default::default(),
             active_tooltip: Default::default(),
+            tooltip_delay: 0.0,
+            pending_tooltip: Handle::NONE,
+            pending_tooltip_time: 0.0,
+            tooltip_anchor: Vector2::default(),
             preview_set: Default::default(),
             clipboard: ClipboardContext::new().ok(),
             layout_events_receiver,
             layout_events_sender,
             need_update_global_transform: Default::default(),
+            pixel_snap: false,
+            default_font: DEFAULT_FONT.clone(),
+            fonts: Default::default(),
+            localization: Default::default(),
+            drag_threshold: 5.0,
+            bindings: Default::default(),
+            layout_complete_callbacks: Default::default(),
+            tweens: Default::default(),
+            draw_frame: 0,
+            drawn_fonts: Default::default(),
+            touches: Default::default(),
+            primary_touch: None,
+            redraw_statistics: Default::default(),
+            layout_statistics: Default::default(),
+            next_node_id: 1,
         };
         ui.root_canvas = ui.add_node(UiNode::new(Canvas::new(WidgetBuilder::new().build())));
         ui
     }
 
+    /// Sets new font that will be used by widget builders when no font was specified explicitly.
+    /// Already built widgets won't be affected.
+    pub fn set_default_font(&mut self, font: SharedFont) {
+        self.default_font = font;
+    }
+
+    /// Returns font that is used by widget builders when no font was specified explicitly. It
+    /// is [`DEFAULT_FONT`] unless changed by [`Self::set_default_font`].
+    pub fn default_font(&self) -> SharedFont {
+        self.default_font.clone()
+    }
+
+    /// Registers a font under given name, so it could be referenced symbolically (for example
+    /// from data-driven UI or themes). Returns previously registered font with the same name.
+    pub fn register_font<N: AsRef<str>>(
+        &mut self,
+        name: N,
+        font: SharedFont,
+    ) -> Option<SharedFont> {
+        self.fonts.insert(name.as_ref().to_owned(), font)
+    }
+
+    /// Removes a font with given name from registry.
+    pub fn unregister_font(&mut self, name: &str) -> Option<SharedFont> {
+        self.fonts.remove(name)
+    }
+
+    /// Searches for a font that was registered under given name.
+    pub fn font_by_name(&self, name: &str) -> Option<SharedFont> {
+        self.fonts.get(name).cloned()
+    }
+
+    /// Returns string table that is used to resolve localized text of widgets.
+    pub fn localization(&self) -> &LocalizationTable {
+        &self.localization
+    }
+
+    /// Returns string table that is used to resolve localized text of widgets. Call
+    /// [`Self::refresh_localization`] after changing strings to update existing widgets.
+    pub fn localization_mut(&mut self) -> &mut LocalizationTable {
+        &mut self.localization
+    }
+
+    /// Sets active language and updates text of every localized text widget.
+    pub fn set_language<L: AsRef<str>>(&mut self, language: L) {
+        self.localization.set_language(language);
+        self.refresh_localization();
+    }
+
+    /// Resolves text of every localized text widget using current string table.
+    pub fn refresh_localization(&mut self) {
+        for node in self.nodes.iter_mut() {
+            if let Some(text) = node.cast_mut::<Text>() {
+                text.localize(&self.localization);
+                text.invalidate_visual();
+            }
+        }
+    }
+
     pub fn keyboard_modifiers(&self) -> KeyboardModifiers {
         self.keyboard_modifiers
     }
@@ -656,19 +1147,81 @@ impl UserInterface {
 
     #[inline]
     pub fn capture_mouse(&mut self, node: Handle<UiNode>) -> bool {
+        self.capture_mouse_with_mode(node, CaptureMode::Exclusive)
+    }
+
+    /// Same as [`Self::capture_mouse`], but allows to choose how the capture affects hover state
+    /// of other nodes.
+    #[inline]
+    pub fn capture_mouse_with_mode(&mut self, node: Handle<UiNode>, mode: CaptureMode) -> bool {
         if self.captured_node.is_none() {
             self.captured_node = node;
+            self.capture_mode = mode;
             true
         } else {
             false
         }
     }
 
+    /// Returns mode of current mouse capture.
+    #[inline]
+    pub fn capture_mode(&self) -> CaptureMode {
+        self.capture_mode
+    }
+
     #[inline]
     pub fn release_mouse_capture(&mut self) {
         self.captured_node = Handle::NONE;
     }
 
+    /// Checks if given node currently holds mouse capture.
+    #[inline]
+    pub fn is_capturing(&self, node: Handle<UiNode>) -> bool {
+        node.is_some() && self.captured_node == node
+    }
+
+    /// Releases mouse capture only if it is held by given node. Returns `true` if the capture
+    /// was released. Unlike [`Self::release_mouse_capture`], it won't break capture of some
+    /// other node.
+    #[inline]
+    pub fn release_capture_if(&mut self, node: Handle<UiNode>) -> bool {
+        if self.is_capturing(node) {
+            self.captured_node = Handle::NONE;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns handle of a node that currently has keyboard focus.
+    #[inline]
+    pub fn keyboard_focus_node(&self) -> Handle<UiNode> {
+        self.keyboard_focus_node
+    }
+
+    /// Releases mouse capture if captured node was deleted, became invisible or disabled, and
+    /// drops keyboard focus of deleted node. Otherwise the UI could get stuck with a capture
+    /// that will never be released.
+    fn validate_capture_and_focus(&mut self) {
+        if self.captured_node.is_some() {
+            let valid = self
+                .nodes
+                .try_borrow(self.captured_node)
+                .map_or(false, |node| {
+                    node.is_globally_visible() && node.is_globally_enabled()
+                });
+            if !valid {
+                self.captured_node = Handle::NONE;
+            }
+        }
+
+        if self.keyboard_focus_node.is_some()
+            && !self.nodes.is_valid_handle(self.keyboard_focus_node)
+        {
+            self.keyboard_focus_node = Handle::NONE;
+        }
+    }
+
     #[inline]
     pub fn get_drawing_context(&self) -> &DrawingContext {
         &self.drawing_context
@@ -679,10 +1232,46 @@ impl UserInterface {
         &mut self.drawing_context
     }
 
+    /// Checks if the node and all its ancestors are enabled by walking up on the tree.
+    #[deprecated(note = "use `is_enabled` instead, it returns cached state of a node")]
     pub fn is_node_enabled(&self, handle: Handle<UiNode>) -> bool {
         is_node_enabled(&self.nodes, handle)
     }
 
+    /// Returns effective enabled state of a node - a node is enabled only if it and all its
+    /// ancestors are enabled. The state is calculated once per [`Self::update`] call, so changes
+    /// of [`Widget::enabled`] are visible here after the next update. Returns `false` for invalid
+    /// handles.
+    pub fn is_enabled(&self, handle: Handle<UiNode>) -> bool {
+        self.nodes
+            .try_borrow(handle)
+            .map_or(false, |node| node.is_globally_enabled())
+    }
+
+    fn update_global_enabled(&mut self, from: Handle<UiNode>) {
+        scope_profile!();
+
+        self.stack.clear();
+        self.stack.push(from);
+        while let Some(node_handle) = self.stack.pop() {
+            let (widget, parent) = self
+                .nodes
+                .try_borrow_dependant_mut(node_handle, |n| n.parent());
+
+            if let Some(widget) = widget {
+                self.stack.extend_from_slice(widget.children());
+
+                let enabled = if let Some(parent) = parent {
+                    widget.enabled() && parent.is_globally_enabled()
+                } else {
+                    widget.enabled()
+                };
+
+                widget.set_global_enabled(enabled);
+            }
+        }
+    }
+
     fn update_global_visibility(&mut self, from: Handle<UiNode>) {
         scope_profile!();
 
@@ -731,12 +1320,17 @@ impl UserInterface {
             if widget.is_globally_visible() {
                 self.stack.extend_from_slice(widget.children());
 
-                let screen_position = if let Some(parent) = parent {
+                let mut screen_position = if let Some(parent) = parent {
                     widget.actual_local_position() + parent.screen_position()
                 } else {
                     widget.actual_local_position()
                 };
 
+                if self.pixel_snap {
+                    screen_position.x = screen_position.x.round();
+                    screen_position.y = screen_position.y.round();
+                }
+
                 widget.screen_position = screen_position;
             }
         }
@@ -746,6 +1340,48 @@ impl UserInterface {
         self.screen_size
     }
 
+    /// Enables or disables pixel snapping. When enabled, screen positions of every node will be
+    /// rounded to whole pixels, this prevents text and borders from being rendered at half-pixels
+    /// which makes them look blurry. Disabled by default.
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        if self.pixel_snap != pixel_snap {
+            self.pixel_snap = pixel_snap;
+            self.need_update_global_transform = true;
+        }
+    }
+
+    pub fn pixel_snap(&self) -> bool {
+        self.pixel_snap
+    }
+
+    /// Sets amount of time (in seconds) the cursor must stay over a node before its tooltip is
+    /// shown. Zero by default, which means that tooltips are shown immediately. Moving the cursor
+    /// further than [drag threshold](Self::drag_threshold) hides visible tooltip (unless the cursor
+    /// is over the tooltip) and restarts the delay.
+    pub fn set_tooltip_delay(&mut self, delay: f32) {
+        self.tooltip_delay = delay.max(0.0);
+    }
+
+    pub fn tooltip_delay(&self) -> f32 {
+        self.tooltip_delay
+    }
+
+    /// Enables or disables antialiasing of edges of filled rectangles (backgrounds of borders,
+    /// selections, etc.). Textured geometry, such as text and images, is not affected. Disabled
+    /// by default, see [`DrawingContext::set_antialiasing`] for more info.
+    pub fn set_antialiasing(&mut self, antialiasing: bool) {
+        if self.drawing_context.is_antialiasing_enabled() != antialiasing {
+            self.drawing_context.set_antialiasing(antialiasing);
+            for node in self.nodes.iter() {
+                node.invalidate_visual();
+            }
+        }
+    }
+
+    pub fn antialiasing(&self) -> bool {
+        self.drawing_context.is_antialiasing_enabled()
+    }
+
     fn handle_layout_events(&mut self) {
         fn invalidate_recursive_up(
             nodes: &Pool<UiNode>,
@@ -787,6 +1423,11 @@ impl UserInterface {
 
         self.handle_layout_events();
 
+        self.update_global_enabled(self.root_canvas);
+
+        self.validate_capture_and_focus();
+
+        self.layout_statistics.set(Default::default());
         self.measure_node(self.root_canvas, screen_size);
         self.arrange_node(
             self.root_canvas,
@@ -798,6 +1439,13 @@ impl UserInterface {
             self.need_update_global_transform = false;
         }
 
+        // Clip bounds are used by both drawing and hit testing, so they're calculated right after
+        // layout to be in sync with screen bounds of nodes.
+        self.calculate_clip_bounds(
+            self.root_canvas,
+            Some(Rect::new(0.0, 0.0, screen_size.x, screen_size.y)),
+        );
+
         let sender = self.sender.clone();
         for node in self.nodes.iter_mut() {
             node.update(dt, &sender)
@@ -805,6 +1453,12 @@ impl UserInterface {
 
         self.update_tooltips(dt);
 
+        self.update_gestures(dt);
+
+        self.update_bindings();
+
+        self.update_tweens(dt);
+
         if !self.drag_context.is_dragging {
             // Try to fetch new cursor icon starting from current picked node. Traverse
             // tree up until cursor with different value is found.
@@ -819,39 +1473,474 @@ impl UserInterface {
                 handle = node.parent();
             }
         }
+
+        for callback in std::mem::take(&mut self.layout_complete_callbacks) {
+            callback(self);
+        }
     }
 
-    pub fn cursor(&self) -> CursorIcon {
-        self.cursor_icon
+    /// Schedules a callback that will be called once at the end of next [`Self::update`], when
+    /// layout pass is finished and geometry of every node (actual size, screen position, etc.)
+    /// is final. It is useful when you need to know the size of a widget right after it was
+    /// built, for example to position a popup relative to it. Callbacks scheduled from inside
+    /// of a callback will be called on the next update.
+    pub fn on_layout_complete(&mut self, callback: Box<dyn FnOnce(&mut UserInterface)>) {
+        self.layout_complete_callbacks.push(callback);
     }
 
-    pub fn draw(&mut self) -> &DrawingContext {
-        scope_profile!();
+    /// Sets maximum distance (in pixels) that cursor can travel while a mouse button is pressed
+    /// to still treat press-move-release sequence as a click. If the distance is exceeded, the
+    /// sequence is treated as a drag. Default value is 5 pixels.
+    pub fn set_drag_threshold(&mut self, threshold: f32) {
+        self.drag_threshold = threshold.max(0.0);
+    }
 
-        self.calculate_clip_bounds(
-            self.root_canvas,
-            Rect::new(0.0, 0.0, self.screen_size.x, self.screen_size.y),
-        );
-        self.drawing_context.clear();
+    pub fn drag_threshold(&self) -> f32 {
+        self.drag_threshold
+    }
 
-        for node in self.nodes.iter_mut() {
-            node.command_indices.borrow_mut().clear();
-        }
+    /// Checks whether cursor has moved from `press_position` to `position` far enough to treat
+    /// a press-move-release sequence as a drag, not as a click. Widgets should use this method
+    /// instead of own thresholds, so every widget will distinguish clicks and drags consistently.
+    pub fn is_drag(&self, press_position: Vector2<f32>, position: Vector2<f32>) -> bool {
+        (position - press_position).norm() > self.drag_threshold
+    }
 
-        // Draw everything except top-most nodes.
-        draw_node(&self.nodes, self.root_canvas, &mut self.drawing_context);
+    /// Binds a value of a widget to a data model. Each update the value is pulled from `getter`
+    /// and sent to the widget if it has changed, unless the widget is being edited (it or any
+    /// of its descendants has keyboard focus or captures the mouse). Values reported by the
+    /// widget are pushed to `setter`, except values that are equal to the last synchronized
+    /// one - this prevents feedback loops when a widget echoes a value it was given. `M` is a
+    /// message that carries the value, for example [`scroll_bar::ScrollBarMessage`].
+    ///
+    /// The binding is removed automatically when the widget is deleted.
+    pub fn bind<M, G, S>(&mut self, widget: Handle<UiNode>, getter: G, setter: S)
+    where
+        M: ValueMessage,
+        G: FnMut() -> M::Value + 'static,
+        S: FnMut(M::Value) + 'static,
+    {
+        self.bindings.push(Box::new(Binding::<M> {
+            widget,
+            getter: Box::new(getter),
+            setter: Box::new(setter),
+            last: None,
+        }));
+    }
 
-        // Render top-most nodes in separate pass.
-        // TODO: This may give weird results because of invalid nesting.
-        self.stack.clear();
-        self.stack.push(self.root());
-        while let Some(node_handle) = self.stack.pop() {
-            let node = &self.nodes[node_handle];
-            if node.is_draw_on_top() {
-                draw_node(&self.nodes, node_handle, &mut self.drawing_context);
-            }
-            for &child in node.children() {
-                self.stack.push(child);
+    /// Removes every binding of the given widget.
+    pub fn unbind(&mut self, widget: Handle<UiNode>) {
+        self.bindings.retain(|binding| binding.widget() != widget);
+    }
+
+    fn is_editing(&self, widget: Handle<UiNode>) -> bool {
+        [self.captured_node, self.keyboard_focus_node]
+            .iter()
+            .any(|&node| {
+                node.is_some() && (node == widget || self.nodes[widget].has_descendant(node, self))
+            })
+    }
+
+    fn update_bindings(&mut self) {
+        let mut bindings = std::mem::take(&mut self.bindings);
+        bindings.retain(|binding| self.nodes.is_valid_handle(binding.widget()));
+        for binding in bindings.iter_mut() {
+            if !self.is_editing(binding.widget()) {
+                binding.pull(&self.sender);
+            }
+        }
+        self.bindings = bindings;
+    }
+
+    /// Smoothly changes a property of a node from its current value to the target value over
+    /// `duration` seconds using given easing function. The property is changed by messages (for
+    /// example [`WidgetMessage::width`]), that are sent on each [`Self::update`]. If the property
+    /// of the node is already being animated, previous animation is replaced with the new one,
+    /// which starts from the current value. Returned tween could be used to set a callback that
+    /// will be called when the animation is finished:
+    ///
+    /// ```rust
+    /// # use fyrox_ui::{
+    /// #     core::pool::Handle,
+    /// #     tween::{Easing, TweenProperty},
+    /// #     widget::WidgetMessage, message::MessageDirection, UiNode, UserInterface,
+    /// # };
+    /// fn fade_out_and_remove(ui: &mut UserInterface, dialog: Handle<UiNode>) {
+    ///     ui.animate(dialog, TweenProperty::Opacity(0.0), 0.25, Easing::QuadOut)
+    ///         .set_on_complete(Box::new(move |ui| {
+    ///             ui.send_message(WidgetMessage::remove(dialog, MessageDirection::ToWidget));
+    ///         }));
+    /// }
+    /// ```
+    pub fn animate(
+        &mut self,
+        node: Handle<UiNode>,
+        property: TweenProperty,
+        duration: f32,
+        easing: Easing,
+    ) -> &mut Tween {
+        self.tweens
+            .retain(|tween| !tween.is_animating(node, &property));
+        let tween = Tween::new(node, &self.nodes[node], property, duration, easing);
+        self.tweens.push(tween);
+        self.tweens.last_mut().unwrap()
+    }
+
+    /// Stops every animation of the node, properties keep their current values.
+    pub fn stop_animations(&mut self, node: Handle<UiNode>) {
+        self.tweens.retain(|tween| tween.node != node);
+    }
+
+    fn update_tweens(&mut self, dt: f32) {
+        let mut tweens = std::mem::take(&mut self.tweens);
+        tweens.retain(|tween| self.nodes.is_valid_handle(tween.node));
+
+        let mut callbacks = Vec::new();
+        for tween in tweens.iter_mut() {
+            if let Some(message) = tween.advance(dt) {
+                self.send_message(message);
+            }
+            if tween.is_complete() {
+                callbacks.extend(tween.on_complete.take());
+            }
+        }
+        tweens.retain(|tween| !tween.is_complete());
+
+        // Animations started while updating tweens stay in the list.
+        tweens.append(&mut self.tweens);
+        self.tweens = tweens;
+
+        for callback in callbacks {
+            callback(self);
+        }
+    }
+
+    pub fn gesture_settings(&self) -> &GestureSettings {
+        &self.gesture_settings
+    }
+
+    pub fn set_gesture_settings(&mut self, settings: GestureSettings) {
+        self.gesture_settings = settings;
+    }
+
+    pub fn navigation_settings(&self) -> &NavigationSettings {
+        &self.navigation_settings
+    }
+
+    pub fn set_navigation_settings(&mut self, settings: NavigationSettings) {
+        self.navigation_settings = settings;
+    }
+
+    /// Moves keyboard focus to the nearest focusable node (see [`WidgetBuilder::with_focusable`])
+    /// in given direction. Nodes are compared by centers of their screen bounds, so the layout of
+    /// the UI defines navigation order, not the order of nodes in the tree. If no node has focus,
+    /// the top-left focusable node is focused. Only nodes that can be picked are considered, so
+    /// navigation does not escape an open modal window or popup. Returns `true` if focus has
+    /// changed.
+    pub fn navigate_focus(&mut self, direction: NavigationDirection) -> bool {
+        let candidates = self.focus_candidates();
+
+        let origin = if candidates.contains(&self.keyboard_focus_node) {
+            self.keyboard_focus_node
+        } else {
+            Handle::NONE
+        };
+
+        let target = if origin.is_some() {
+            let center = |handle: Handle<UiNode>| {
+                let bounds = self.nodes[handle].screen_bounds();
+                bounds.position + bounds.size.scale(0.5)
+            };
+            let origin_center = center(origin);
+            let weight = self.navigation_settings.cross_axis_weight;
+
+            // Splits offset to a candidate into distances along navigation direction and across it.
+            let split = |handle: Handle<UiNode>| {
+                let offset = center(handle) - origin_center;
+                match direction {
+                    NavigationDirection::Up => (-offset.y, offset.x.abs()),
+                    NavigationDirection::Down => (offset.y, offset.x.abs()),
+                    NavigationDirection::Left => (-offset.x, offset.y.abs()),
+                    NavigationDirection::Right => (offset.x, offset.y.abs()),
+                }
+            };
+
+            let best = |filter: &dyn Fn(f32) -> bool| {
+                candidates
+                    .iter()
+                    .filter(|handle| **handle != origin)
+                    .map(|handle| (*handle, split(*handle)))
+                    .filter(|(_, (along, _))| filter(*along))
+                    .min_by(|(_, (a_along, a_across)), (_, (b_along, b_across))| {
+                        (a_along + weight * a_across)
+                            .partial_cmp(&(b_along + weight * b_across))
+                            .unwrap_or(Ordering::Equal)
+                    })
+                    .map(|(handle, _)| handle)
+            };
+
+            best(&|along| along > f32::EPSILON).or_else(|| {
+                match self.navigation_settings.edge_behavior {
+                    NavigationEdgeBehavior::Stop => None,
+                    // Most negative distance along the direction is the farthest node on the
+                    // opposite side.
+                    NavigationEdgeBehavior::Wrap => best(&|along| along < -f32::EPSILON),
+                }
+            })
+        } else {
+            candidates.iter().copied().min_by(|a, b| {
+                let a = self.nodes[*a].screen_position;
+                let b = self.nodes[*b].screen_position;
+                a.y.partial_cmp(&b.y)
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal))
+            })
+        };
+
+        match target {
+            Some(target) if target != self.keyboard_focus_node => {
+                self.set_keyboard_focus(target);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects every visible, enabled and focusable node that can be picked with respect to
+    /// the picking stack.
+    fn focus_candidates(&self) -> Vec<Handle<UiNode>> {
+        let mut roots = Vec::new();
+        if self.picking_stack.is_empty() {
+            roots.push(self.root_canvas);
+        } else {
+            for entry in self.picking_stack.iter().rev() {
+                roots.push(entry.handle);
+                if entry.stop {
+                    break;
+                }
+            }
+        }
+
+        let mut candidates = Vec::new();
+        let mut stack = roots;
+        while let Some(handle) = stack.pop() {
+            if let Some(node) = self.nodes.try_borrow(handle) {
+                if !node.is_globally_visible() || !node.is_globally_enabled() {
+                    continue;
+                }
+                if node.is_focusable() && !candidates.contains(&handle) {
+                    candidates.push(handle);
+                }
+                // Reversed, so children are visited in their order.
+                stack.extend(node.children().iter().rev());
+            }
+        }
+        candidates
+    }
+
+    /// Moves keyboard focus to the next focusable node (see [`WidgetBuilder::with_focusable`])
+    /// in order of the tree traversal, after the last node the focus goes back to the first one.
+    /// If no node has focus, the first focusable node is focused. Like [`Self::navigate_focus`],
+    /// considers only nodes that can be picked. Returns `true` if focus has changed.
+    pub fn focus_next(&mut self) -> bool {
+        self.focus_sequential(true)
+    }
+
+    /// Same as [`Self::focus_next`], but moves focus in reverse order.
+    pub fn focus_previous(&mut self) -> bool {
+        self.focus_sequential(false)
+    }
+
+    fn focus_sequential(&mut self, forward: bool) -> bool {
+        let candidates = self.focus_candidates();
+        if candidates.is_empty() {
+            return false;
+        }
+
+        let count = candidates.len();
+        let next = match candidates
+            .iter()
+            .position(|&h| h == self.keyboard_focus_node)
+        {
+            Some(current) if forward => (current + 1) % count,
+            Some(current) => (current + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+
+        let next = candidates[next];
+        if next == self.keyboard_focus_node {
+            return false;
+        }
+        self.set_keyboard_focus(next);
+        true
+    }
+
+    /// Moves keyboard focus to given node and notifies both the old and the new focused nodes.
+    fn set_keyboard_focus(&mut self, handle: Handle<UiNode>) {
+        if self.keyboard_focus_node != handle {
+            if self.keyboard_focus_node.is_some() {
+                self.send_message(WidgetMessage::lost_focus(
+                    self.keyboard_focus_node,
+                    MessageDirection::FromWidget,
+                ));
+            }
+
+            self.keyboard_focus_node = handle;
+
+            if self.keyboard_focus_node.is_some() {
+                self.send_message(WidgetMessage::got_focus(
+                    self.keyboard_focus_node,
+                    MessageDirection::FromWidget,
+                ));
+            }
+        }
+    }
+
+    fn update_gestures(&mut self, dt: f32) {
+        let ctx = &mut self.gesture_context;
+
+        ctx.time_since_last_click += dt;
+
+        if ctx.press_button.is_some() && !ctx.long_press_fired {
+            ctx.press_time += dt;
+
+            if ctx.press_time >= self.gesture_settings.long_press_time {
+                ctx.long_press_fired = true;
+
+                if let Some(button) = ctx.press_button {
+                    if self.nodes.is_valid_handle(ctx.press_node) {
+                        self.sender
+                            .send(WidgetMessage::long_press(
+                                ctx.press_node,
+                                MessageDirection::FromWidget,
+                                ctx.press_pos,
+                                button,
+                            ))
+                            .unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_gesture_press(&mut self, button: MouseButton) {
+        let ctx = &mut self.gesture_context;
+        ctx.press_node = self.picked_node;
+        ctx.press_button = Some(button);
+        ctx.press_pos = self.cursor_position;
+        ctx.press_time = 0.0;
+        ctx.long_press_fired = false;
+    }
+
+    fn on_gesture_move(&mut self) {
+        let ctx = &mut self.gesture_context;
+        if ctx.press_button.is_some()
+            && (ctx.press_pos - self.cursor_position).norm()
+                > self.gesture_settings.long_press_distance
+        {
+            // Cursor has moved too far, this is not a long press anymore.
+            ctx.press_button = None;
+        }
+    }
+
+    fn on_gesture_release(&mut self, button: MouseButton) {
+        let settings = self.gesture_settings;
+        let ctx = &mut self.gesture_context;
+
+        // Press and release must happen over the same node to be a click.
+        let is_click = ctx.press_button == Some(button)
+            && !ctx.long_press_fired
+            && self.picked_node.is_some()
+            && self.picked_node == ctx.press_node;
+        ctx.press_button = None;
+
+        if !is_click {
+            return;
+        }
+
+        if ctx.last_click_button == Some(button)
+            && ctx.last_click_node == self.picked_node
+            && ctx.time_since_last_click <= settings.double_click_time
+            && (ctx.last_click_pos - self.cursor_position).norm() <= settings.double_click_distance
+        {
+            // Reset last click, so triple click won't produce two double clicks.
+            ctx.last_click_button = None;
+            ctx.last_click_node = Handle::NONE;
+
+            self.sender
+                .send(WidgetMessage::double_click(
+                    self.picked_node,
+                    MessageDirection::FromWidget,
+                    self.cursor_position,
+                    button,
+                ))
+                .unwrap();
+        } else {
+            ctx.last_click_button = Some(button);
+            ctx.last_click_node = self.picked_node;
+            ctx.last_click_pos = self.cursor_position;
+            ctx.time_since_last_click = 0.0;
+        }
+    }
+
+    pub fn cursor(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    pub fn draw(&mut self) -> &DrawingContext {
+        scope_profile!();
+
+        self.drawing_context.clear_keeping_previous();
+
+        for node in self.nodes.iter_mut() {
+            let mut prev_command_indices = node.prev_command_indices.borrow_mut();
+            prev_command_indices.clear();
+            std::mem::swap(
+                &mut *prev_command_indices,
+                &mut *node.command_indices.borrow_mut(),
+            );
+        }
+
+        // Texture coordinates of glyphs are normalized, so they change when atlas of a font
+        // grows and every command that uses the font must be emitted again.
+        let full_redraw = self
+            .drawn_fonts
+            .iter()
+            .any(|(font, atlas_size)| font.0.lock().unwrap().atlas_size() != *atlas_size);
+
+        self.draw_frame += 1;
+        let frame = self.draw_frame;
+        let mut statistics = RedrawStatistics::default();
+
+        // Draw everything except top-most nodes.
+        draw_node(
+            &self.nodes,
+            self.root_canvas,
+            &mut self.drawing_context,
+            frame,
+            full_redraw,
+            &mut statistics,
+        );
+
+        // Render top-most nodes in separate pass.
+        // TODO: This may give weird results because of invalid nesting.
+        self.stack.clear();
+        self.stack.push(self.root());
+        while let Some(node_handle) = self.stack.pop() {
+            let node = &self.nodes[node_handle];
+            if node.is_draw_on_top() {
+                draw_node(
+                    &self.nodes,
+                    node_handle,
+                    &mut self.drawing_context,
+                    frame,
+                    full_redraw,
+                    &mut statistics,
+                );
+            }
+            for &child in node.children() {
+                self.stack.push(child);
             }
         }
 
@@ -880,9 +1969,31 @@ impl UserInterface {
             }
         }
 
+        self.redraw_statistics = statistics;
+
+        self.drawn_fonts.clear();
+        for command in self.drawing_context.get_commands() {
+            if let CommandTexture::Font(font) = &command.texture {
+                if self.drawn_fonts.iter().all(|(drawn, _)| drawn != font) {
+                    let atlas_size = font.0.lock().unwrap().atlas_size();
+                    self.drawn_fonts.push((font.clone(), atlas_size));
+                }
+            }
+        }
+
         &self.drawing_context
     }
 
+    /// Returns statistics of incremental redraw of last frame drawn by [`Self::draw`].
+    pub fn redraw_statistics(&self) -> RedrawStatistics {
+        self.redraw_statistics
+    }
+
+    /// Returns statistics of layout pass of last [`Self::update`].
+    pub fn layout_statistics(&self) -> LayoutStatistics {
+        self.layout_statistics.get()
+    }
+
     pub fn clipboard(&self) -> Option<&ClipboardContext> {
         self.clipboard.as_ref()
     }
@@ -895,41 +2006,58 @@ impl UserInterface {
         scope_profile!();
 
         let node = self.node(handle);
+        debug_assert_eq!(
+            node.handle(),
+            handle,
+            "Widget handle mismatch! Widgets must be added via UserInterface::add_node."
+        );
 
         if node.is_arrange_valid() && node.prev_arrange.get() == *final_rect {
             return false;
         }
 
+        let mut statistics = self.layout_statistics.get();
+        statistics.arranged_nodes += 1;
+        self.layout_statistics.set(statistics);
+
         if node.visibility() {
             node.prev_arrange.set(*final_rect);
+            // Widgets could change their content on layout pass (text wrapping for example).
+            node.invalidate_visual();
 
             let margin = node.margin().axes_margin();
 
-            let mut size = Vector2::new(
+            let available_size = Vector2::new(
                 (final_rect.w() - margin.x).max(0.0),
                 (final_rect.h() - margin.y).max(0.0),
             );
 
-            let available_size = size;
+            let mut size = available_size;
 
-            if node.horizontal_alignment() != HorizontalAlignment::Stretch {
-                size.x = size.x.min(node.desired_size().x - margin.x);
+            // Constraints are applied in the same order as in measure pass: explicit size first,
+            // then min/max size. This guarantees that actual size will never exceed desired size
+            // for non-stretch alignments.
+            if !node.width().is_nan() {
+                size.x = clampf(node.width(), node.min_size().x, node.max_size().x);
             }
-            if node.vertical_alignment() != VerticalAlignment::Stretch {
-                size.y = size.y.min(node.desired_size().y - margin.y);
+            if !node.height().is_nan() {
+                size.y = clampf(node.height(), node.min_size().y, node.max_size().y);
             }
 
-            if node.width() > 0.0 {
-                size.x = node.width();
+            if node.horizontal_alignment() != HorizontalAlignment::Stretch {
+                size.x = size.x.min((node.desired_size().x - margin.x).max(0.0));
             }
-            if node.height() > 0.0 {
-                size.y = node.height();
+            if node.vertical_alignment() != VerticalAlignment::Stretch {
+                size.y = size.y.min((node.desired_size().y - margin.y).max(0.0));
             }
 
-            size = node.arrange_override(self, size);
+            size.x = size.x.min(available_size.x);
+            size.y = size.y.min(available_size.y);
 
-            size.x = size.x.min(final_rect.w());
-            size.y = size.y.min(final_rect.h());
+            let arranged_size = node.arrange_override(self, size);
+
+            size.x = arranged_size.x.min(size.x);
+            size.y = arranged_size.y.min(size.y);
 
             let mut origin = final_rect.position + node.margin().offset();
 
@@ -959,34 +2087,40 @@ impl UserInterface {
         scope_profile!();
 
         let node = self.node(handle);
+        debug_assert_eq!(
+            node.handle(),
+            handle,
+            "Widget handle mismatch! Widgets must be added via UserInterface::add_node."
+        );
 
         if node.is_measure_valid() && node.prev_measure.get() == available_size {
             return false;
         }
 
+        let mut statistics = self.layout_statistics.get();
+        statistics.measured_nodes += 1;
+        self.layout_statistics.set(statistics);
+
         if node.visibility() {
             node.prev_measure.set(available_size);
 
             let axes_margin = node.margin().axes_margin();
-            let mut inner_size = available_size - axes_margin;
-            inner_size.x = inner_size.x.max(0.0);
-            inner_size.y = inner_size.y.max(0.0);
+            let inner_size = (available_size - axes_margin).per_component_max(&Vector2::default());
 
             let mut size = Vector2::new(
-                if node.width() > 0.0 {
-                    node.width()
-                } else {
+                if node.width().is_nan() {
                     inner_size.x
-                },
-                if node.height() > 0.0 {
-                    node.height()
                 } else {
+                    node.width()
+                },
+                if node.height().is_nan() {
                     inner_size.y
+                } else {
+                    node.height()
                 },
             );
 
-            size.x = clampf(size.x, node.min_size().x, node.max_size().x);
-            size.y = clampf(size.y, node.min_size().y, node.max_size().y);
+            size = size.per_component_clamp(&node.min_size(), &node.max_size());
 
             let mut desired_size = node.measure_override(self, size);
 
@@ -997,13 +2131,11 @@ impl UserInterface {
                 desired_size.y = node.height();
             }
 
-            desired_size.x = clampf(desired_size.x, node.min_size().x, node.max_size().x);
-            desired_size.y = clampf(desired_size.y, node.min_size().y, node.max_size().y);
+            desired_size = desired_size.per_component_clamp(&node.min_size(), &node.max_size());
 
             desired_size += axes_margin;
 
-            desired_size.x = desired_size.x.min(available_size.x);
-            desired_size.y = desired_size.y.min(available_size.y);
+            desired_size = desired_size.per_component_min(&available_size);
 
             node.commit_measure(desired_size);
         } else {
@@ -1013,36 +2145,66 @@ impl UserInterface {
         true
     }
 
+    /// Measures a node and its descendants against given available size (could be infinite)
+    /// and returns desired size of the node, including its margin. The node is not arranged or
+    /// drawn, so it could be used to fit a window to its content before showing it. The node
+    /// does not need to be a part of the visual tree of the root canvas.
+    ///
+    /// Measurement results are cached by nodes, so the node is marked for re-measurement to
+    /// make sure the next layout pass won't use the results obtained with foreign constraints.
+    pub fn measure_subtree(
+        &self,
+        node_handle: Handle<UiNode>,
+        available_size: Vector2<f32>,
+    ) -> Vector2<f32> {
+        self.measure_node(node_handle, available_size);
+        let node = self.node(node_handle);
+        let desired_size = node.desired_size();
+        node.invalidate_measure();
+        desired_size
+    }
+
     fn is_node_clipped(&self, node_handle: Handle<UiNode>, pt: Vector2<f32>) -> bool {
         scope_profile!();
 
-        let mut clipped = true;
-
         let widget = self.nodes.borrow(node_handle);
 
-        if widget.is_globally_visible() {
-            clipped = !widget.screen_bounds().contains(pt);
+        if !widget.is_globally_visible() {
+            return true;
+        }
 
-            if !clipped {
-                for command_index in widget.command_indices.borrow().iter() {
-                    if let Some(command) = self.drawing_context.get_commands().get(*command_index) {
-                        if let Some(geometry) = command.clipping_geometry.as_ref() {
-                            if geometry.is_contains_point(pt) {
-                                clipped = false;
-                                break;
-                            }
-                        }
+        // Fast path: clip bounds of a widget is an intersection of its screen bounds with clip
+        // bounds of its parent, so a single test rejects points outside of any ancestor. Clip
+        // bounds of fully clipped widget is an empty rect at origin, so it is checked separately.
+        if widget.is_fully_clipped.get() || !widget.clip_bounds().contains(pt) {
+            return true;
+        }
+
+        // Slow path: non-rectangular clipping geometry of the widget or any of its ancestors.
+        // Most widgets do not have any, so this is usually just a walk up the tree.
+        let mut handle = node_handle;
+        while let Some(node) = self.nodes.try_borrow(handle) {
+            let mut outside = false;
+            for command_index in node.command_indices.borrow().iter() {
+                if let Some(geometry) = self
+                    .drawing_context
+                    .get_commands()
+                    .get(*command_index)
+                    .and_then(|command| command.clipping_geometry.as_ref())
+                {
+                    outside = !geometry.is_contains_point(pt);
+                    if !outside {
+                        break;
                     }
                 }
             }
-
-            // Point can be clipped by parent's clipping geometry.
-            if !widget.parent().is_none() && !clipped {
-                clipped |= self.is_node_clipped(widget.parent(), pt);
+            if outside {
+                return true;
             }
+            handle = node.parent();
         }
 
-        clipped
+        false
     }
 
     fn is_node_contains_point(&self, node_handle: Handle<UiNode>, pt: Vector2<f32>) -> bool {
@@ -1115,7 +2277,14 @@ impl UserInterface {
 
         if self.nodes.is_valid_handle(self.captured_node) {
             self.captured_node
-        } else if self.picking_stack.is_empty() {
+        } else {
+            self.pick(pt)
+        }
+    }
+
+    /// Same as [`Self::hit_test`], but ignores mouse capture.
+    fn pick(&self, pt: Vector2<f32>) -> Handle<UiNode> {
+        if self.picking_stack.is_empty() {
             // We're not restricted to any node, just start from root.
             let mut level = 0;
             self.pick_node(self.root_canvas, pt, &mut level)
@@ -1202,13 +2371,22 @@ impl UserInterface {
             .has_descendant(node_handle, self)
     }
 
-    /// Recursively calculates clipping bounds for every node.
-    fn calculate_clip_bounds(&self, node: Handle<UiNode>, parent_bounds: Rect<f32>) {
+    /// Recursively calculates clipping bounds for every node. `None` bounds means that the node
+    /// is fully clipped by its ancestors.
+    fn calculate_clip_bounds(&self, node: Handle<UiNode>, parent_bounds: Option<Rect<f32>>) {
         let node = &self.nodes[node];
-        node.clip_bounds
-            .set(node.screen_bounds().clip_by(parent_bounds));
+        let clip_bounds = parent_bounds
+            .and_then(|parent_bounds| node.screen_bounds().intersection(parent_bounds));
+        node.clip_bounds.set(clip_bounds.unwrap_or_default());
+        node.is_fully_clipped.set(clip_bounds.is_none());
+        // Children of a node that does not clip them are clipped by the ancestors only.
+        let children_bounds = if node.clip_children() {
+            clip_bounds
+        } else {
+            parent_bounds
+        };
         for &child in node.children() {
-            self.calculate_clip_bounds(child, node.clip_bounds.get());
+            self.calculate_clip_bounds(child, children_bounds);
         }
     }
 
@@ -1316,12 +2494,45 @@ impl UserInterface {
     // Node will be topmost *only* on same hierarchy level! So if you have a floating
     // window (for example) and a window embedded into some other control (yes this is
     // possible) then floating window won't be the topmost.
+    //
+    // Z-index has priority: the node is moved on top of its siblings with the same or lower
+    // z-index, siblings with higher z-index stay on top of it.
     fn make_topmost(&mut self, node: Handle<UiNode>) {
-        let parent = self.node(node).parent();
-        if parent.is_some() {
-            let parent = &mut self.nodes[parent];
+        let parent_handle = self.node(node).parent();
+        if parent_handle.is_some() {
+            let parent = &mut self.nodes[parent_handle];
             parent.remove_child(node);
             parent.add_child(node, false);
+            self.restore_z_index_order(parent_handle, node);
+        }
+    }
+
+    fn tunnel_message(&mut self, message: &mut UiMessage) {
+        scope_profile!();
+
+        // Dispatch event using tunnel strategy. Tunnel routing means that message will go
+        // from tree root down on tree to specified destination.
+        // Gather chain of nodes from root to destination.
+        self.bubble_queue.clear();
+        self.bubble_queue.push_front(message.destination());
+        let mut parent = self.nodes[message.destination()].parent();
+        while parent.is_some() && self.nodes.is_valid_handle(parent) {
+            self.bubble_queue.push_front(parent);
+            parent = self.nodes[parent].parent();
+        }
+
+        while let Some(handle) = self.bubble_queue.pop_front() {
+            let (ticket, mut node) = self.nodes.take_reserve(handle);
+            node.handle_tunneled_message(self, message);
+            if handle == message.destination() && message.direction() == MessageDirection::ToWidget
+            {
+                node.invalidate_visual();
+            }
+            self.nodes.put_back(ticket, node);
+
+            if message.handled() {
+                break;
+            }
         }
     }
 
@@ -1342,6 +2553,12 @@ impl UserInterface {
         while let Some(handle) = self.bubble_queue.pop_front() {
             let (ticket, mut node) = self.nodes.take_reserve(handle);
             node.handle_routed_message(self, message);
+            // Messages to a widget change its state and could change its look. Widgets that
+            // change their look in response to input must invalidate themselves.
+            if handle == message.destination() && message.direction() == MessageDirection::ToWidget
+            {
+                node.invalidate_visual();
+            }
             self.nodes.put_back(ticket, node);
         }
     }
@@ -1369,8 +2586,13 @@ impl UserInterface {
                     }
                 }
 
+                self.tunnel_message(&mut message);
                 self.bubble_message(&mut message);
 
+                for binding in self.bindings.iter_mut() {
+                    binding.push(&message);
+                }
+
                 if let Some(msg) = message.data::<WidgetMessage>() {
                     match msg {
                         WidgetMessage::ZIndex(_) => {
@@ -1378,23 +2600,7 @@ impl UserInterface {
                             // the same as z-index of children.
                             let parent = self.node(message.destination()).parent();
                             if parent.is_some() {
-                                self.stack.clear();
-                                for child in self.nodes.borrow(parent).children() {
-                                    self.stack.push(*child);
-                                }
-
-                                let nodes = &mut self.nodes;
-                                self.stack.sort_by(|a, b| {
-                                    let z_a = nodes.borrow(*a).z_index();
-                                    let z_b = nodes.borrow(*b).z_index();
-                                    z_a.cmp(&z_b)
-                                });
-
-                                let parent = self.nodes.borrow_mut(parent);
-                                parent.clear_children();
-                                for child in self.stack.iter() {
-                                    parent.add_child(*child, false);
-                                }
+                                self.sort_children_by_z_index(parent);
                             }
                         }
                         WidgetMessage::TopMost => {
@@ -1559,6 +2765,7 @@ impl UserInterface {
         }
 
         // Check for hovering over a widget with a tooltip, or hovering over a tooltip.
+        let mut hovered_tooltip = Handle::NONE;
         let mut handle = self.picked_node;
         while let Some(node) = self.nodes.try_borrow(handle) {
             // Get the parent to avoid the problem with having a immutable access here and a
@@ -1569,7 +2776,22 @@ impl UserInterface {
                 // They have a tooltip, we stop here and use that.
                 let tooltip = node.tooltip();
                 let tooltip_time = node.tooltip_time();
-                self.replace_or_update_tooltip(tooltip, tooltip_time);
+                hovered_tooltip = tooltip;
+
+                if self.pending_tooltip == tooltip {
+                    self.pending_tooltip_time += dt;
+                } else {
+                    self.pending_tooltip = tooltip;
+                    self.pending_tooltip_time = 0.0;
+                }
+
+                let is_active = self
+                    .active_tooltip
+                    .as_ref()
+                    .map_or(false, |entry| entry.tooltip == tooltip);
+                if is_active || self.pending_tooltip_time >= self.tooltip_delay {
+                    self.replace_or_update_tooltip(tooltip, tooltip_time);
+                }
                 break;
             } else if let Some(entry) = self.active_tooltip.as_mut() {
                 if entry.tooltip == handle {
@@ -1582,34 +2804,138 @@ impl UserInterface {
 
             handle = parent;
         }
-    }
 
-    pub fn captured_node(&self) -> Handle<UiNode> {
-        self.captured_node
+        if hovered_tooltip.is_none() {
+            self.pending_tooltip = Handle::NONE;
+        }
+
+        if let Some(entry) = self.active_tooltip.as_ref() {
+            self.keep_tooltip_on_screen(entry.tooltip);
+        }
     }
 
-    /// Translates raw window event into some specific UI message. This is one of the
-    /// most important methods of UI. You must call it each time you received a message
-    /// from a window.
-    pub fn process_os_event(&mut self, event: &OsEvent) -> bool {
-        let mut event_processed = false;
+    /// Hides active tooltip and restarts the delay if the cursor has moved noticeably, so tooltips
+    /// are shown only while the cursor rests over a node.
+    fn reset_tooltips_on_cursor_move(&mut self) {
+        if !self.is_drag(self.tooltip_anchor, self.cursor_position) {
+            return;
+        }
 
-        match event {
-            &OsEvent::MouseInput { button, state, .. } => {
-                match button {
-                    MouseButton::Left => self.mouse_state.left = state,
-                    MouseButton::Right => self.mouse_state.right = state,
-                    MouseButton::Middle => self.mouse_state.middle = state,
-                    _ => {}
-                }
+        self.tooltip_anchor = self.cursor_position;
+        self.pending_tooltip_time = 0.0;
 
-                match state {
-                    ButtonState::Pressed => {
-                        self.picked_node = self.hit_test(self.cursor_position);
+        if let Some(entry) = self.active_tooltip.as_ref() {
+            let tooltip = entry.tooltip;
 
-                        // Try to find draggable node in hierarchy starting from picked node.
-                        if self.picked_node.is_some() {
-                            self.stack.clear();
+            // Keep the tooltip while the cursor is over it.
+            let mut handle = self.picked_node;
+            while let Some(node) = self.nodes.try_borrow(handle) {
+                if handle == tooltip {
+                    return;
+                }
+                handle = node.parent();
+            }
+
+            self.send_message(WidgetMessage::visibility(
+                tooltip,
+                MessageDirection::ToWidget,
+                false,
+            ));
+            self.active_tooltip = None;
+        }
+    }
+
+    /// Moves visible tooltip so it won't go off-screen. Size of the tooltip is known only after
+    /// it was arranged, so it is done on every update.
+    fn keep_tooltip_on_screen(&self, tooltip: Handle<UiNode>) {
+        if let Some(node) = self.nodes.try_borrow(tooltip) {
+            if !node.visibility() {
+                return;
+            }
+
+            let position = node.desired_local_position();
+            let max = self.screen_size - node.actual_size();
+            let clamped = Vector2::new(
+                position.x.min(max.x).max(0.0),
+                position.y.min(max.y).max(0.0),
+            );
+            if clamped != position {
+                self.send_message(WidgetMessage::desired_position(
+                    tooltip,
+                    MessageDirection::ToWidget,
+                    clamped,
+                ));
+            }
+        }
+    }
+
+    /// Fires mouse leave for every node that was under the cursor, but isn't anymore, and mouse
+    /// enter for every node that is under the cursor now, but wasn't before. Chains of nodes from
+    /// picked node up to the root are compared, so moving cursor between a parent and its child
+    /// won't fire leave/enter on the parent (and other common ancestors).
+    fn update_hover_path(&mut self, hovered: Handle<UiNode>) {
+        let mut new_path = Vec::new();
+        let mut handle = hovered;
+        while let Some(node) = self.nodes.try_borrow(handle) {
+            new_path.push(handle);
+            handle = node.parent();
+        }
+
+        // Leave is fired from deepest node up to common ancestor.
+        for &prev in self.hover_path.iter() {
+            if !new_path.contains(&prev) && self.nodes.is_valid_handle(prev) {
+                self.send_message(WidgetMessage::mouse_leave(
+                    prev,
+                    MessageDirection::FromWidget,
+                ));
+            }
+        }
+
+        // Enter is fired from common ancestor down to picked node.
+        for &new in new_path.iter().rev() {
+            if !self.hover_path.contains(&new) {
+                self.send_message(WidgetMessage::mouse_enter(
+                    new,
+                    MessageDirection::FromWidget,
+                ));
+            }
+        }
+
+        self.hover_path = new_path;
+    }
+
+    /// Returns chain of nodes under the cursor, starting from the topmost picked node up to the
+    /// root of the tree.
+    pub fn hover_path(&self) -> &[Handle<UiNode>] {
+        &self.hover_path
+    }
+
+    pub fn captured_node(&self) -> Handle<UiNode> {
+        self.captured_node
+    }
+
+    /// Translates raw window event into some specific UI message. This is one of the
+    /// most important methods of UI. You must call it each time you received a message
+    /// from a window.
+    pub fn process_os_event(&mut self, event: &OsEvent) -> bool {
+        let mut event_processed = false;
+
+        match event {
+            &OsEvent::MouseInput { button, state, .. } => {
+                match button {
+                    MouseButton::Left => self.mouse_state.left = state,
+                    MouseButton::Right => self.mouse_state.right = state,
+                    MouseButton::Middle => self.mouse_state.middle = state,
+                    _ => {}
+                }
+
+                match state {
+                    ButtonState::Pressed => {
+                        self.picked_node = self.hit_test(self.cursor_position);
+
+                        // Try to find draggable node in hierarchy starting from picked node.
+                        if self.picked_node.is_some() {
+                            self.stack.clear();
                             self.stack.push(self.picked_node);
                             while let Some(handle) = self.stack.pop() {
                                 let node = &self.nodes[handle];
@@ -1624,23 +2950,9 @@ impl UserInterface {
                             self.drag_context.click_pos = self.cursor_position;
                         }
 
-                        if self.keyboard_focus_node != self.picked_node {
-                            if self.keyboard_focus_node.is_some() {
-                                self.send_message(WidgetMessage::lost_focus(
-                                    self.keyboard_focus_node,
-                                    MessageDirection::FromWidget,
-                                ));
-                            }
-
-                            self.keyboard_focus_node = self.picked_node;
+                        self.set_keyboard_focus(self.picked_node);
 
-                            if self.keyboard_focus_node.is_some() {
-                                self.send_message(WidgetMessage::got_focus(
-                                    self.keyboard_focus_node,
-                                    MessageDirection::FromWidget,
-                                ));
-                            }
-                        }
+                        self.on_gesture_press(button);
 
                         if self.picked_node.is_some() {
                             self.send_message(WidgetMessage::mouse_down(
@@ -1653,6 +2965,8 @@ impl UserInterface {
                         }
                     }
                     ButtonState::Released => {
+                        self.on_gesture_release(button);
+
                         if self.picked_node.is_some() {
                             if self.drag_context.is_dragging {
                                 self.drag_context.is_dragging = false;
@@ -1697,11 +3011,14 @@ impl UserInterface {
                 self.cursor_position = *position;
                 self.picked_node = self.hit_test(self.cursor_position);
 
+                self.on_gesture_move();
+                self.reset_tooltips_on_cursor_move();
+
                 if !self.drag_context.is_dragging
                     && self.mouse_state.left == ButtonState::Pressed
                     && self.picked_node.is_some()
                     && self.drag_context.drag_node.is_some()
-                    && (self.drag_context.click_pos - *position).norm() > 5.0
+                    && self.is_drag(self.drag_context.click_pos, *position)
                 {
                     self.drag_context.drag_preview =
                         self.copy_node_with_limit(self.drag_context.drag_node, Some(30));
@@ -1728,28 +3045,29 @@ impl UserInterface {
                     ));
                 }
 
-                // Fire mouse leave for previously picked node
-                if self.picked_node != self.prev_picked_node && self.prev_picked_node.is_some() {
-                    let prev_picked_node = self.nodes.borrow_mut(self.prev_picked_node);
-                    if prev_picked_node.is_mouse_directly_over {
-                        prev_picked_node.is_mouse_directly_over = false;
-                        self.send_message(WidgetMessage::mouse_leave(
-                            self.prev_picked_node,
-                            MessageDirection::FromWidget,
-                        ));
+                // Hover state follows the real topmost node if capture allows it.
+                let hovered = if self.capture_mode == CaptureMode::HoverThrough
+                    && self.nodes.is_valid_handle(self.captured_node)
+                {
+                    self.pick(self.cursor_position)
+                } else {
+                    self.picked_node
+                };
+
+                let prev_hovered = self.hover_path.first().cloned().unwrap_or_default();
+                if hovered != prev_hovered {
+                    if let Some(prev_hovered) = self.nodes.try_borrow_mut(prev_hovered) {
+                        prev_hovered.is_mouse_directly_over = false;
                     }
                 }
 
-                if self.picked_node.is_some() {
-                    let picked_node = self.nodes.borrow_mut(self.picked_node);
-                    if !picked_node.is_mouse_directly_over {
-                        picked_node.is_mouse_directly_over = true;
-                        self.send_message(WidgetMessage::mouse_enter(
-                            self.picked_node,
-                            MessageDirection::FromWidget,
-                        ));
-                    }
+                self.update_hover_path(hovered);
+
+                if let Some(hovered) = self.nodes.try_borrow_mut(hovered) {
+                    hovered.is_mouse_directly_over = true;
+                }
 
+                if self.picked_node.is_some() {
                     // Fire mouse move
                     self.send_message(WidgetMessage::mouse_move(
                         self.picked_node,
@@ -1758,31 +3076,44 @@ impl UserInterface {
                         self.mouse_state,
                     ));
 
-                    if self.drag_context.is_dragging {
-                        self.send_message(WidgetMessage::drag_over(
-                            self.picked_node,
-                            MessageDirection::FromWidget,
-                            self.drag_context.drag_node,
-                        ));
-                    }
-
                     event_processed = true;
                 }
+
+                if self.drag_context.is_dragging && hovered.is_some() {
+                    self.send_message(WidgetMessage::drag_over(
+                        hovered,
+                        MessageDirection::FromWidget,
+                        self.drag_context.drag_node,
+                    ));
+                }
             }
-            OsEvent::MouseWheel(_, y) => {
+            OsEvent::MouseWheel(delta) => {
                 if self.picked_node.is_some() {
                     self.send_message(WidgetMessage::mouse_wheel(
                         self.picked_node,
                         MessageDirection::FromWidget,
                         self.cursor_position,
-                        *y,
+                        delta.lines().y,
+                        *delta,
                     ));
 
                     event_processed = true;
                 }
             }
+            OsEvent::KeyboardInput {
+                button: KeyCode::Tab,
+                state,
+            } if self.navigation_settings.tab_navigation => {
+                if *state == ButtonState::Pressed {
+                    event_processed = if self.keyboard_modifiers.shift {
+                        self.focus_previous()
+                    } else {
+                        self.focus_next()
+                    };
+                }
+            }
             OsEvent::KeyboardInput { button, state } => {
-                if self.keyboard_focus_node.is_some() {
+                if self.keyboard_focus_node.is_some() && self.is_enabled(self.keyboard_focus_node) {
                     self.send_message(match state {
                         ButtonState::Pressed => WidgetMessage::key_down(
                             self.keyboard_focus_node,
@@ -1799,8 +3130,10 @@ impl UserInterface {
                     event_processed = true;
                 }
             }
+            // Tab character comes along with Tab key press, which is used for navigation.
+            OsEvent::Character('\t') if self.navigation_settings.tab_navigation => {}
             OsEvent::Character(unicode) => {
-                if self.keyboard_focus_node.is_some() {
+                if self.keyboard_focus_node.is_some() && self.is_enabled(self.keyboard_focus_node) {
                     self.send_message(WidgetMessage::text(
                         self.keyboard_focus_node,
                         MessageDirection::FromWidget,
@@ -1810,10 +3143,49 @@ impl UserInterface {
                     event_processed = true;
                 }
             }
+            OsEvent::Ime(ime_event) => {
+                if self.keyboard_focus_node.is_some() && self.is_enabled(self.keyboard_focus_node) {
+                    match ime_event {
+                        ImeEvent::Preedit(text) => {
+                            self.send_message(WidgetMessage::ime_composition(
+                                self.keyboard_focus_node,
+                                MessageDirection::FromWidget,
+                                text.clone(),
+                            ));
+                        }
+                        ImeEvent::Commit(text) => {
+                            self.send_message(WidgetMessage::ime_composition(
+                                self.keyboard_focus_node,
+                                MessageDirection::FromWidget,
+                                String::new(),
+                            ));
+                            for unicode in text.chars() {
+                                self.send_message(WidgetMessage::text(
+                                    self.keyboard_focus_node,
+                                    MessageDirection::FromWidget,
+                                    unicode,
+                                ));
+                            }
+                        }
+                    }
+
+                    event_processed = true;
+                }
+            }
             &OsEvent::KeyboardModifiers(modifiers) => {
                 // TODO: Is message needed for focused node?
                 self.keyboard_modifiers = modifiers;
             }
+            &OsEvent::Touch {
+                id,
+                phase,
+                position,
+            } => {
+                event_processed = self.process_touch(id, phase, position);
+            }
+            &OsEvent::Navigate(direction) => {
+                event_processed = self.navigate_focus(direction);
+            }
         }
 
         self.prev_picked_node = self.picked_node;
@@ -1835,14 +3207,112 @@ impl UserInterface {
         event_processed
     }
 
+    fn process_touch(&mut self, id: u64, phase: TouchPhase, position: Vector2<f32>) -> bool {
+        let mut processed = false;
+
+        if phase == TouchPhase::Started && self.primary_touch.is_none() {
+            self.primary_touch = Some(id);
+            processed |= self.process_os_event(&OsEvent::CursorMoved { position });
+            processed |= self.process_os_event(&OsEvent::MouseInput {
+                button: MouseButton::Left,
+                state: ButtonState::Pressed,
+            });
+        } else if self.primary_touch == Some(id) {
+            // First touch emulates mouse, so widgets that know nothing about touches still work.
+            processed |= self.process_os_event(&OsEvent::CursorMoved { position });
+            match phase {
+                TouchPhase::Ended => {
+                    self.primary_touch = None;
+                    processed |= self.process_os_event(&OsEvent::MouseInput {
+                        button: MouseButton::Left,
+                        state: ButtonState::Released,
+                    });
+                }
+                TouchPhase::Cancelled => {
+                    // Cancelled touch (for example a system gesture took over) must not be
+                    // reported as a release, otherwise a button under the finger would be
+                    // clicked. Pressed widget is notified by `TouchCancelled` below.
+                    self.primary_touch = None;
+                    self.mouse_state.left = ButtonState::Released;
+                    self.gesture_context.press_button = None;
+                    self.release_mouse_capture();
+                }
+                TouchPhase::Started | TouchPhase::Moved => (),
+            }
+        }
+
+        // Every touch is bound to the node it has started on, so multiple fingers won't interfere
+        // with each other.
+        let node = match phase {
+            TouchPhase::Started => {
+                let node = self.pick(position);
+                self.touches.insert(id, node);
+                node
+            }
+            TouchPhase::Moved => self.touches.get(&id).cloned().unwrap_or_default(),
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&id).unwrap_or_default()
+            }
+        };
+
+        if self.nodes.is_valid_handle(node) {
+            let direction = MessageDirection::FromWidget;
+            self.send_message(match phase {
+                TouchPhase::Started => WidgetMessage::touch_started(node, direction, id, position),
+                TouchPhase::Moved => WidgetMessage::touch_moved(node, direction, id, position),
+                TouchPhase::Ended => WidgetMessage::touch_ended(node, direction, id, position),
+                TouchPhase::Cancelled => WidgetMessage::touch_cancelled(node, direction, id),
+            });
+            processed = true;
+        }
+
+        processed
+    }
+
+    /// Injects synthetic text input, each character of the string is passed to currently
+    /// focused node exactly like real input from a keyboard. Could be useful for tests and
+    /// on-screen keyboards. Returns `true` if at least one character was processed.
+    pub fn inject_text(&mut self, text: &str) -> bool {
+        let mut processed = false;
+        for unicode in text.chars() {
+            processed |= self.process_os_event(&OsEvent::Character(unicode));
+        }
+        processed
+    }
+
+    /// Injects synthetic key press or release, the key will be passed to currently focused
+    /// node exactly like real input from a keyboard. Returns `true` if the key was processed.
+    pub fn inject_key(&mut self, key: KeyCode, pressed: bool) -> bool {
+        self.process_os_event(&OsEvent::KeyboardInput {
+            button: key,
+            state: if pressed {
+                ButtonState::Pressed
+            } else {
+                ButtonState::Released
+            },
+        })
+    }
+
     pub fn nodes(&self) -> &Pool<UiNode> {
         &self.nodes
     }
 
+    /// Returns handle of the root canvas. Every node without explicit parent is linked to it,
+    /// children of the canvas could be positioned explicitly in screen coordinates.
     pub fn root(&self) -> Handle<UiNode> {
         self.root_canvas
     }
 
+    /// Immediately links the node directly to the root canvas. The node keeps its desired
+    /// position, which is now relative to the screen. Use [`WidgetMessage::unlink`] to keep the
+    /// node at its current screen position instead.
+    pub fn link_to_root(&mut self, node_handle: Handle<UiNode>) {
+        self.link_nodes_internal(node_handle, self.root_canvas, false);
+    }
+
+    /// Adds a node to the user interface and returns its handle. This is the only way of adding
+    /// a widget, it assigns the handle of the widget (see [`Widget::handle`]), so widgets built
+    /// by hand (not via builders) must be added using this method too.
     pub fn add_node(&mut self, mut node: UiNode) -> Handle<UiNode> {
         let children = node.children().to_vec();
         node.clear_children();
@@ -1850,18 +3320,33 @@ impl UserInterface {
         if self.root_canvas.is_some() {
             self.link_nodes_internal(node_handle, self.root_canvas, false);
         }
-        for child in children {
-            self.link_nodes_internal(child, node_handle, false)
-        }
+        self.link_children(node_handle, &children);
         let node = self.nodes[node_handle].deref_mut();
         node.layout_events_sender = Some(self.layout_events_sender.clone());
+        node.invalidate_visual();
         if node.preview_messages {
             self.preview_set.insert(node_handle);
         }
         node.handle = node_handle;
+        node.id = self.next_node_id;
+        self.next_node_id += 1;
         node_handle
     }
 
+    /// Searches a node by its unique id (see [`Widget::id`]), returns [`Handle::NONE`] if there
+    /// is no such node.
+    pub fn find_by_id(&self, id: u64) -> Handle<UiNode> {
+        if id == 0 {
+            return Handle::NONE;
+        }
+
+        self.nodes
+            .pair_iter()
+            .find(|(_, node)| node.id() == id)
+            .map(|(handle, _)| handle)
+            .unwrap_or_default()
+    }
+
     pub fn push_picking_restriction(&mut self, restriction: RestrictionEntry) {
         if let Some(top) = self.top_picking_restriction() {
             assert_ne!(top.handle, restriction.handle);
@@ -1888,34 +3373,54 @@ impl UserInterface {
         self.picking_stack.last().cloned()
     }
 
-    /// Use WidgetMessage::remove(...) to remove node.
-    fn remove_node(&mut self, node: Handle<UiNode>) {
+    /// Drops every reference of the user interface to a node that is about to leave the pool.
+    fn forget_node_references(&mut self, handle: Handle<UiNode>) {
+        if self.prev_picked_node == handle {
+            self.prev_picked_node = Handle::NONE;
+        }
+        if self.picked_node == handle {
+            self.picked_node = Handle::NONE;
+        }
+        self.hover_path.retain(|h| *h != handle);
+        if self.captured_node == handle {
+            self.captured_node = Handle::NONE;
+        }
+        if self.keyboard_focus_node == handle {
+            self.keyboard_focus_node = Handle::NONE;
+        }
+        if self.pending_tooltip == handle {
+            self.pending_tooltip = Handle::NONE;
+        }
+        self.remove_picking_restriction(handle);
+        self.preview_set.remove(&handle);
+    }
+
+    /// Removes a node with all its descendants (and their tooltips) from the user interface
+    /// immediately. The node is unlinked from its parent, every reference of the user interface
+    /// to removed nodes (captured, picked, focused nodes, etc.) is dropped. Use
+    /// [`WidgetMessage::remove`] if removal should be done in order with other messages.
+    ///
+    /// Invalid handles and the root canvas are ignored. A tooltip shared by several widgets is
+    /// removed once, so make sure that it is not used by remaining widgets.
+    pub fn remove_node(&mut self, node: Handle<UiNode>) {
+        if !self.nodes.is_valid_handle(node) || node == self.root_canvas {
+            return;
+        }
+
         self.unlink_node_internal(node);
 
         let mut tooltips = Vec::new();
         let sender = self.sender.clone();
         let mut stack = vec![node];
         while let Some(handle) = stack.pop() {
-            if self.prev_picked_node == handle {
-                self.prev_picked_node = Handle::NONE;
-            }
-            if self.picked_node == handle {
-                self.picked_node = Handle::NONE;
-            }
-            if self.captured_node == handle {
-                self.captured_node = Handle::NONE;
-            }
-            if self.keyboard_focus_node == handle {
-                self.keyboard_focus_node = Handle::NONE;
-            }
-            self.remove_picking_restriction(handle);
+            self.forget_node_references(handle);
 
             let node_ref = self.nodes.borrow(handle);
             stack.extend_from_slice(node_ref.children());
 
             // We also must delete tooltips, since they're not in the tree of the widget they
             // won't be deleted automatically.
-            if node_ref.tooltip().is_some() {
+            if node_ref.tooltip().is_some() && !tooltips.contains(&node_ref.tooltip()) {
                 tooltips.push(node_ref.tooltip());
             }
 
@@ -1926,11 +3431,87 @@ impl UserInterface {
             self.nodes.free(handle);
         }
 
+        // Tooltips could also be a part of removed sub-tree, such ones are already freed.
         for tooltip in tooltips {
             self.remove_node(tooltip);
         }
+    }
+
+    /// Extracts a node from the user interface and reserves its handle, an attempt to borrow
+    /// the node by the handle will result in panic. The node is detached from its parent, but
+    /// its children stay in the pool. Use [`Self::take_reserve_sub_graph`] to extract a node
+    /// with all its descendants.
+    pub fn take_reserve(&mut self, handle: Handle<UiNode>) -> (Ticket<UiNode>, UiNode) {
+        self.unlink_node_internal(handle);
+        self.forget_node_references(handle);
+        self.nodes.take_reserve(handle)
+    }
+
+    /// Puts node back by given ticket. Attaches back to root canvas.
+    pub fn put_back(&mut self, ticket: Ticket<UiNode>, node: UiNode) -> Handle<UiNode> {
+        let handle = self.nodes.put_back(ticket, node);
+        self.restore_node_references(handle);
+        self.link_nodes_internal(handle, self.root_canvas, false);
+        handle
+    }
+
+    /// Makes node handle vacant again.
+    pub fn forget_ticket(&mut self, ticket: Ticket<UiNode>, node: UiNode) -> UiNode {
+        self.nodes.forget_ticket(ticket);
+        node.on_remove(&self.sender);
+        node
+    }
+
+    /// Extracts sub-graph starting from a given node. All handles to extracted nodes become
+    /// reserved, so the sub-graph could be put back later with the same handles. Root node of
+    /// the sub-graph will be detached from its parent. Tooltips of extracted nodes are not part
+    /// of the sub-graph and stay in the user interface.
+    pub fn take_reserve_sub_graph(&mut self, root: Handle<UiNode>) -> SubGraph {
+        // Take out descendants first.
+        let mut descendants = Vec::new();
+        let mut stack = self.nodes[root].children().to_vec();
+        while let Some(handle) = stack.pop() {
+            stack.extend_from_slice(self.nodes[handle].children());
+            self.forget_node_references(handle);
+            descendants.push(self.nodes.take_reserve(handle));
+        }
+
+        SubGraph {
+            // Root must be extracted with detachment from its parent (if any).
+            root: self.take_reserve(root),
+            descendants,
+        }
+    }
+
+    /// Puts previously extracted sub-graph back into user interface. Handles to nodes will
+    /// become valid again. Root of the sub-graph will be attached to root canvas, you probably
+    /// want to re-link it with its previous parent.
+    pub fn put_sub_graph_back(&mut self, sub_graph: SubGraph) -> Handle<UiNode> {
+        for (ticket, node) in sub_graph.descendants {
+            let handle = self.nodes.put_back(ticket, node);
+            self.restore_node_references(handle);
+        }
+
+        let (ticket, node) = sub_graph.root;
+        self.put_back(ticket, node)
+    }
+
+    /// Forgets the entire sub-graph making handles to nodes invalid.
+    pub fn forget_sub_graph(&mut self, sub_graph: SubGraph) {
+        for (ticket, node) in sub_graph.descendants {
+            self.forget_ticket(ticket, node);
+        }
+        let (ticket, root) = sub_graph.root;
+        self.forget_ticket(ticket, root);
+    }
 
-        self.preview_set.remove(&node);
+    fn restore_node_references(&mut self, handle: Handle<UiNode>) {
+        let node = &self.nodes[handle];
+        if node.preview_messages {
+            self.preview_set.insert(handle);
+        }
+        node.invalidate_layout();
+        node.invalidate_visual();
     }
 
     /// Links specified child with specified parent.
@@ -1945,6 +3526,108 @@ impl UserInterface {
         self.unlink_node_internal(child_handle);
         self.nodes[child_handle].set_parent(parent_handle);
         self.nodes[parent_handle].add_child(child_handle, in_front);
+        self.restore_z_index_order(parent_handle, child_handle);
+        self.sync_global_enabled(child_handle);
+    }
+
+    /// Makes effective enabled state of a linked sub-tree consistent with its new parent, so it
+    /// is correct before the next update.
+    fn sync_global_enabled(&mut self, node_handle: Handle<UiNode>) {
+        let node = &self.nodes[node_handle];
+        let enabled = node.enabled()
+            && self
+                .nodes
+                .try_borrow(node.parent())
+                .map_or(true, |parent| parent.is_globally_enabled());
+        // Descendants are already consistent with the node if its state did not change.
+        if enabled != node.is_globally_enabled() {
+            self.update_global_enabled(node_handle);
+        }
+    }
+
+    /// Sorts children of a node by their z-index, so children with larger z-index are drawn on
+    /// top and picked first. Sorting is stable, so order of children with the same z-index is
+    /// preserved.
+    fn sort_children_by_z_index(&mut self, parent_handle: Handle<UiNode>) {
+        let nodes = &self.nodes;
+        let children = nodes[parent_handle].children();
+        let is_sorted = children
+            .windows(2)
+            .all(|pair| nodes[pair[0]].z_index() <= nodes[pair[1]].z_index());
+        if is_sorted {
+            return;
+        }
+
+        self.stack.clear();
+        self.stack.extend_from_slice(children);
+        self.stack.sort_by_key(|child| nodes[*child].z_index());
+
+        let parent = self.nodes.borrow_mut(parent_handle);
+        parent.clear_children();
+        parent.add_children(&self.stack);
+    }
+
+    /// Restores z-index order of children of a node after a child was put at the beginning or
+    /// at the end of its children list. Children are sorted only if the child breaks the order,
+    /// so linking lots of children with the same z-index one by one stays cheap.
+    fn restore_z_index_order(
+        &mut self,
+        parent_handle: Handle<UiNode>,
+        child_handle: Handle<UiNode>,
+    ) {
+        let nodes = &self.nodes;
+        let children = nodes[parent_handle].children();
+        let z_index = nodes[child_handle].z_index();
+        let in_order = if children.last() == Some(&child_handle) {
+            children.len() < 2 || nodes[children[children.len() - 2]].z_index() <= z_index
+        } else if children.first() == Some(&child_handle) {
+            children
+                .get(1)
+                .map_or(true, |next| z_index <= nodes[*next].z_index())
+        } else {
+            false
+        };
+        if !in_order {
+            self.sort_children_by_z_index(parent_handle);
+        }
+    }
+
+    /// Links every child from the list to specified parent in one pass. Children are appended
+    /// to the end of children list of the parent in the same order. It is much faster than
+    /// linking children one by one when there are lots of them, because children of the same
+    /// previous parent are detached from it at once. Handles listed more than once are linked
+    /// once, at their first position.
+    pub fn link_children(&mut self, parent_handle: Handle<UiNode>, children: &[Handle<UiNode>]) {
+        let mut unique = FxHashSet::default();
+        let children = children
+            .iter()
+            .cloned()
+            .filter(|child| unique.insert(*child))
+            .collect::<Vec<_>>();
+
+        // Group children by their previous parents, so every parent is visited only once.
+        let mut detached: FxHashMap<Handle<UiNode>, FxHashSet<Handle<UiNode>>> = Default::default();
+        for &child_handle in children.iter() {
+            assert_ne!(child_handle, parent_handle);
+            let child = &mut self.nodes[child_handle];
+            let prev_parent = child.parent();
+            if prev_parent.is_some() {
+                detached
+                    .entry(prev_parent)
+                    .or_default()
+                    .insert(child_handle);
+            }
+            child.set_parent(parent_handle);
+        }
+        for (prev_parent, children) in detached.iter() {
+            self.nodes[*prev_parent].remove_children(children);
+        }
+
+        self.nodes[parent_handle].add_children(&children);
+        self.sort_children_by_z_index(parent_handle);
+        for &child_handle in children.iter() {
+            self.sync_global_enabled(child_handle);
+        }
     }
 
     /// Unlinks specified node from its parent, so node will become root.
@@ -1976,11 +3659,40 @@ impl UserInterface {
         self.nodes.borrow(node_handle)
     }
 
+    /// Changes name of a node immediately, unlike [`WidgetMessage::name`]. Composite widgets
+    /// refer to their parts by handles, so parts could be renamed freely too.
+    pub fn set_node_name<N: AsRef<str>>(&mut self, node_handle: Handle<UiNode>, name: N) {
+        self.nodes[node_handle].set_name(name);
+    }
+
     #[inline]
     pub fn try_get_node(&self, node_handle: Handle<UiNode>) -> Option<&UiNode> {
         self.nodes.try_borrow(node_handle)
     }
 
+    #[inline]
+    pub fn try_get_node_mut(&mut self, node_handle: Handle<UiNode>) -> Option<&mut UiNode> {
+        let node = self.nodes.try_borrow_mut(node_handle)?;
+        node.invalidate_visual();
+        Some(node)
+    }
+
+    /// Checks whether given node handle is valid or not. Handles become invalid when their nodes
+    /// are removed, so long-living controllers could use this to check cached handles without
+    /// borrowing nodes.
+    #[inline]
+    pub fn is_valid_handle(&self, node_handle: Handle<UiNode>) -> bool {
+        self.nodes.is_valid_handle(node_handle)
+    }
+
+    /// Returns a handle of a node at given index of the node pool with current generation, or
+    /// [`Handle::NONE`] if there is no node at the index. Comparing generation of a cached handle
+    /// with the current one tells whether the slot was reused by another node.
+    #[inline]
+    pub fn handle_from_index(&self, index: u32) -> Handle<UiNode> {
+        self.nodes.handle_from_index(index)
+    }
+
     pub fn copy_node(&mut self, node: Handle<UiNode>) -> Handle<UiNode> {
         let mut map = NodeHandleMapping::default();
 
@@ -2063,17 +3775,93 @@ impl UserInterface {
 
         copy_handle
     }
+
+    /// Builds a hierarchy of widgets using given function and moves it out of the user
+    /// interface into a template. The hierarchy won't be visible and won't receive any
+    /// messages, use [`Self::instantiate`] to put a copy of it in the user interface.
+    pub fn build_detached<F>(&mut self, func: F) -> UiTemplate
+    where
+        F: FnOnce(&mut BuildContext) -> Handle<UiNode>,
+    {
+        let root = func(&mut self.build_ctx());
+
+        self.unlink_node_internal(root);
+
+        let mut nodes = FxHashMap::default();
+        let mut stack = vec![root];
+        while let Some(handle) = stack.pop() {
+            let mut node = self.nodes.free(handle);
+            stack.extend_from_slice(node.children());
+            node.layout_events_sender = None;
+            self.preview_set.remove(&handle);
+            nodes.insert(handle, node);
+        }
+
+        UiTemplate { nodes, root }
+    }
+
+    /// Creates a new instance of the template and attaches it to the given parent. If parent
+    /// is [`Handle::NONE`] the instance will be attached to the root canvas. Handles that
+    /// widgets of the template store to each other are remapped to the handles of the new
+    /// instance. Returns handle of the root node of the instance.
+    pub fn instantiate(&mut self, template: &UiTemplate, parent: Handle<UiNode>) -> Handle<UiNode> {
+        let mut map = NodeHandleMapping::default();
+
+        let root = self.instantiate_recursive(template, template.root, &mut map);
+
+        for &node_handle in map.hash_map.values() {
+            self.nodes[node_handle].resolve(&map);
+        }
+
+        if parent.is_some() {
+            self.link_nodes_internal(root, parent, false);
+        }
+
+        root
+    }
+
+    fn instantiate_recursive(
+        &mut self,
+        template: &UiTemplate,
+        node_handle: Handle<UiNode>,
+        map: &mut NodeHandleMapping,
+    ) -> Handle<UiNode> {
+        let node = &template.nodes[&node_handle];
+        let mut instance = UiNode(node.clone_boxed());
+
+        let mut instance_children = Vec::new();
+        for &child in node.children() {
+            instance_children.push(self.instantiate_recursive(template, child, map));
+        }
+
+        instance.set_children(instance_children);
+        let instance_handle = self.add_node(instance);
+        map.add_mapping(node_handle, instance_handle);
+        instance_handle
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         border::BorderBuilder,
-        core::algebra::Vector2,
-        message::MessageDirection,
+        brush::Brush,
+        button::{ButtonBuilder, ButtonMessage},
+        canvas::CanvasBuilder,
+        core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
+        message::{
+            ButtonState, ImeEvent, KeyCode, MessageDirection, MouseButton, NavigationDirection,
+            OsEvent, TouchPhase, WheelDelta,
+        },
+        scroll_bar::{ScrollBar, ScrollBarBuilder, ScrollBarMessage},
+        stack_panel::StackPanelBuilder,
+        text::{Text, TextBuilder},
+        text_box::{TextBox, TextBoxBuilder},
         widget::{WidgetBuilder, WidgetMessage},
-        UserInterface,
+        CaptureMode, HorizontalAlignment, NavigationEdgeBehavior, NavigationSettings, Thickness,
+        UiNode, UserInterface, VerticalAlignment,
     };
+    use std::{cell::Cell, rc::Rc};
 
     #[test]
     fn center() {
@@ -2094,4 +3882,1253 @@ mod test {
         let actual_position = ui.node(widget).actual_local_position();
         assert_eq!(actual_position, expected_position);
     }
+
+    #[test]
+    fn disabled_parent_disables_children() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let parent =
+            BorderBuilder::new(WidgetBuilder::new().with_child(child)).build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        assert!(ui.is_enabled(child));
+
+        ui.send_message(WidgetMessage::enabled(
+            parent,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        assert!(!ui.is_enabled(parent));
+        assert!(!ui.is_enabled(child));
+        assert!(ui.node(child).enabled());
+
+        // Node linked to a disabled parent is disabled right away.
+        let other_child = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert!(ui.is_enabled(other_child));
+        ui.send_message(WidgetMessage::link_with(
+            other_child,
+            MessageDirection::ToWidget,
+            parent,
+        ));
+        while ui.poll_message().is_some() {}
+        assert!(!ui.is_enabled(other_child));
+    }
+
+    #[test]
+    fn explicit_size_respects_max_size() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(80.0)
+                .with_height(80.0)
+                .with_max_size(Vector2::new(60.0, 60.0)),
+        )
+        .build(&mut ui.build_ctx());
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(100.0)
+                .with_height(100.0)
+                .with_child(child),
+        )
+        .with_stroke_thickness(Thickness::zero())
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.node(child).desired_size(), Vector2::new(60.0, 60.0));
+        assert_eq!(ui.node(child).actual_size(), Vector2::new(60.0, 60.0));
+    }
+
+    #[test]
+    fn actual_size_does_not_exceed_desired_size() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(10.0))
+                .with_min_size(Vector2::new(50.0, 50.0))
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_vertical_alignment(VerticalAlignment::Top),
+        )
+        .build(&mut ui.build_ctx());
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(40.0)
+                .with_height(40.0)
+                .with_child(child),
+        )
+        .with_stroke_thickness(Thickness::zero())
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        let child_ref = ui.node(child);
+        let margin = child_ref.margin().axes_margin();
+        assert_eq!(child_ref.desired_size(), Vector2::new(40.0, 40.0));
+        assert!(child_ref.actual_size().x <= child_ref.desired_size().x - margin.x);
+        assert!(child_ref.actual_size().y <= child_ref.desired_size().y - margin.y);
+    }
+
+    #[test]
+    fn template_instantiation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let node_count = ui.nodes().alive_count();
+        let template = ui.build_detached(|ctx| {
+            let child = BorderBuilder::new(WidgetBuilder::new()).build(ctx);
+            BorderBuilder::new(WidgetBuilder::new().with_child(child)).build(ctx)
+        });
+        assert_eq!(template.node_count(), 2);
+        assert_eq!(ui.nodes().alive_count(), node_count);
+
+        let parent = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let first = ui.instantiate(&template, parent);
+        let second = ui.instantiate(&template, parent);
+        assert_ne!(first, second);
+        assert_eq!(ui.node(parent).children(), &[first, second]);
+        for instance in [first, second] {
+            assert_eq!(ui.node(instance).parent(), parent);
+            let child = ui.node(instance).children()[0];
+            assert_eq!(ui.node(child).parent(), instance);
+        }
+    }
+
+    #[test]
+    fn binding_does_not_echo_pulled_values() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+
+        let model = Rc::new(Cell::new(5.0));
+        let writes = Rc::new(Cell::new(0));
+        ui.bind::<ScrollBarMessage, _, _>(
+            scroll_bar,
+            {
+                let model = model.clone();
+                move || model.get()
+            },
+            {
+                let model = model.clone();
+                let writes = writes.clone();
+                move |value| {
+                    model.set(value);
+                    writes.set(writes.get() + 1);
+                }
+            },
+        );
+
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value, 5.0);
+        assert_eq!(writes.get(), 0);
+
+        ui.send_message(ScrollBarMessage::value(
+            scroll_bar,
+            MessageDirection::ToWidget,
+            7.0,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(model.get(), 7.0);
+        assert_eq!(writes.get(), 1);
+    }
+
+    #[test]
+    fn ime_commit_inserts_text() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let text_box =
+            TextBoxBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(20.0))
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+
+        // Focus the text box.
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(5.0, 5.0),
+        });
+        ui.process_os_event(&OsEvent::MouseInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        ui.process_os_event(&OsEvent::MouseInput {
+            button: MouseButton::Left,
+            state: ButtonState::Released,
+        });
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, text_box);
+
+        ui.process_os_event(&OsEvent::Ime(ImeEvent::Preedit("ka".to_owned())));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(text_box).cast::<TextBox>().unwrap().text(), "");
+
+        ui.process_os_event(&OsEvent::Ime(ImeEvent::Commit("\u{304B}".to_owned())));
+        while ui.poll_message().is_some() {}
+        assert_eq!(
+            ui.node(text_box).cast::<TextBox>().unwrap().text(),
+            "\u{304B}"
+        );
+    }
+
+    #[test]
+    fn localized_text_follows_language() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let localization = ui.localization_mut();
+        localization.add_string("en", "score", "Score: {0}");
+        localization.add_string("de", "score", "Punkte: {0}");
+        ui.set_language("en");
+
+        let text = TextBuilder::new(WidgetBuilder::new())
+            .with_localized_key("score")
+            .with_localized_args(vec!["10".to_owned()])
+            .build(&mut ui.build_ctx());
+        assert_eq!(ui.node(text).cast::<Text>().unwrap().text(), "Score: 10");
+
+        ui.set_language("de");
+        assert_eq!(ui.node(text).cast::<Text>().unwrap().text(), "Punkte: 10");
+    }
+
+    #[test]
+    fn stale_handles() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let a = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert!(ui.is_valid_handle(a));
+        assert!(ui.try_get_node_mut(a).is_some());
+
+        ui.send_message(WidgetMessage::remove(a, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+        assert!(!ui.is_valid_handle(a));
+        assert!(ui.try_get_node(a).is_none());
+
+        // Slot of removed node is reused with different generation.
+        let b = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert_eq!(b.index(), a.index());
+        assert_eq!(ui.handle_from_index(a.index()), b);
+        assert_ne!(b.generation(), a.generation());
+        assert!(!ui.is_valid_handle(a));
+    }
+
+    #[test]
+    fn take_reserve_sub_graph() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let parent =
+            BorderBuilder::new(WidgetBuilder::new().with_child(child)).build(&mut ui.build_ctx());
+        ui.keyboard_focus_node = child;
+
+        let sub_graph = ui.take_reserve_sub_graph(parent);
+        assert!(ui.try_get_node(parent).is_none());
+        assert!(ui.try_get_node(child).is_none());
+        assert!(ui.keyboard_focus_node.is_none());
+        assert!(!ui.node(ui.root()).children().contains(&parent));
+
+        // New nodes must not take reserved handles.
+        let other = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert_ne!(other.index(), parent.index());
+        assert_ne!(other.index(), child.index());
+
+        assert_eq!(ui.put_sub_graph_back(sub_graph), parent);
+        assert_eq!(ui.node(parent).parent(), ui.root());
+        assert_eq!(ui.node(parent).children(), &[child]);
+        assert_eq!(ui.node(child).parent(), parent);
+
+        let sub_graph = ui.take_reserve_sub_graph(parent);
+        ui.forget_sub_graph(sub_graph);
+        assert!(!ui.is_valid_handle(parent));
+        assert!(!ui.is_valid_handle(child));
+    }
+
+    #[test]
+    fn layout_complete_callback() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let border =
+            BorderBuilder::new(WidgetBuilder::new().with_width(100.0)).build(&mut ui.build_ctx());
+
+        let width = Rc::new(Cell::new(0.0));
+        let calls = Rc::new(Cell::new(0));
+        ui.on_layout_complete({
+            let width = width.clone();
+            let calls = calls.clone();
+            Box::new(move |ui| {
+                width.set(ui.node(border).actual_size().x);
+                calls.set(calls.get() + 1);
+            })
+        });
+        assert_eq!(calls.get(), 0);
+
+        ui.update(screen_size, 0.0);
+        assert_eq!(width.get(), 100.0);
+
+        // Callback is fired only once.
+        ui.update(screen_size, 0.0);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn link_children() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let children = (0..4)
+            .map(|_| BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx()))
+            .collect::<Vec<_>>();
+        let a = BorderBuilder::new(WidgetBuilder::new().with_children(children.iter().cloned()))
+            .build(&mut ui.build_ctx());
+        assert_eq!(ui.node(a).children(), &children[..]);
+        for child in children.iter() {
+            assert_eq!(ui.node(*child).parent(), a);
+            assert!(!ui.node(ui.root()).children().contains(child));
+        }
+
+        // Move part of the children to another parent.
+        let b = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        ui.link_children(b, &children[1..3]);
+        assert_eq!(ui.node(a).children(), &[children[0], children[3]]);
+        assert_eq!(ui.node(b).children(), &children[1..3]);
+        assert_eq!(ui.node(children[1]).parent(), b);
+
+        // Duplicates are linked once.
+        ui.link_children(b, &[children[0], children[3], children[0]]);
+        assert!(ui.node(a).children().is_empty());
+        assert_eq!(
+            ui.node(b).children(),
+            &[children[1], children[2], children[0], children[3]]
+        );
+    }
+
+    #[test]
+    fn incremental_redraw() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let borders = (0..2)
+            .map(|i| {
+                BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_desired_position(Vector2::new(0.0, i as f32 * 100.0))
+                        .with_width(100.0)
+                        .with_height(50.0),
+                )
+                .build(&mut ui.build_ctx())
+            })
+            .collect::<Vec<_>>();
+        ui.update(screen_size, 0.0);
+
+        let vertices = ui.draw().get_vertices().to_vec();
+        assert_eq!(ui.redraw_statistics().reused_nodes, 0);
+
+        // Nothing changed - everything is reused.
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.draw().get_vertices().len(), vertices.len());
+        let statistics = ui.redraw_statistics();
+        assert_eq!(statistics.redrawn_nodes, 0);
+        assert_eq!(statistics.reused_nodes, 3);
+        assert_eq!(statistics.dirty_area(), 0.0);
+
+        ui.send_message(WidgetMessage::background(
+            borders[1],
+            MessageDirection::ToWidget,
+            Brush::Solid(Color::RED),
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.draw().get_vertices().len(), vertices.len());
+        // Only the border is redrawn, the root canvas just passed the message up.
+        let statistics = ui.redraw_statistics();
+        assert_eq!(statistics.redrawn_nodes, 1);
+        assert_eq!(
+            statistics.dirty_region,
+            Some(Rect::new(0.0, 100.0, 100.0, 50.0))
+        );
+
+        // Input does not change look of plain widgets.
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(50.0, 120.0),
+        });
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(60.0, 125.0),
+        });
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        ui.draw();
+        assert_eq!(ui.redraw_statistics().redrawn_nodes, 0);
+    }
+
+    #[test]
+    fn dirty_region_contains_every_redrawn_node() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        // The node that is drawn later is up and to the left of the node that is drawn first.
+        let borders = [Vector2::new(100.0, 100.0), Vector2::new(0.0, 0.0)]
+            .iter()
+            .map(|&position| {
+                BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_desired_position(position)
+                        .with_width(100.0)
+                        .with_height(50.0),
+                )
+                .build(&mut ui.build_ctx())
+            })
+            .collect::<Vec<_>>();
+        ui.update(screen_size, 0.0);
+        ui.draw();
+
+        for &border in borders.iter() {
+            ui.send_message(WidgetMessage::background(
+                border,
+                MessageDirection::ToWidget,
+                Brush::Solid(Color::RED),
+            ));
+        }
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        ui.draw();
+        assert_eq!(
+            ui.redraw_statistics().dirty_region,
+            Some(Rect::new(0.0, 0.0, 200.0, 150.0))
+        );
+    }
+
+    #[test]
+    fn wheel_delta() {
+        let lines = WheelDelta::Lines(Vector2::new(0.0, -2.0));
+        assert_eq!(
+            lines.pixels(),
+            Vector2::new(0.0, -2.0 * WheelDelta::LINE_HEIGHT)
+        );
+        assert_eq!(lines.notches(), -2);
+
+        // Small touchpad movement is a fraction of a line.
+        let pixels = WheelDelta::Pixels(Vector2::new(3.0, WheelDelta::LINE_HEIGHT * 0.5));
+        assert_eq!(pixels.lines().y, 0.5);
+        assert_eq!(pixels.notches(), 0);
+    }
+
+    #[test]
+    fn clip_children() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let mut badges = Vec::new();
+        for clip_children in [true, false] {
+            let badge = BorderBuilder::new(
+                WidgetBuilder::new()
+                    .with_desired_position(Vector2::new(80.0, 80.0))
+                    .with_width(40.0)
+                    .with_height(40.0),
+            )
+            .build(&mut ui.build_ctx());
+            CanvasBuilder::new(
+                WidgetBuilder::new()
+                    .with_desired_position(Vector2::new(100.0, 100.0))
+                    .with_width(100.0)
+                    .with_height(100.0)
+                    .with_clip_children(clip_children)
+                    .with_child(badge),
+            )
+            .build(&mut ui.build_ctx());
+            badges.push(badge);
+        }
+        // Clip bounds are calculated by the layout pass, no need to draw.
+        ui.update(screen_size, 0.0);
+
+        assert_eq!(
+            ui.node(badges[0]).clip_bounds(),
+            Rect::new(180.0, 180.0, 20.0, 20.0)
+        );
+        assert_eq!(
+            ui.node(badges[1]).clip_bounds(),
+            Rect::new(180.0, 180.0, 40.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn fully_clipped_node_is_not_picked() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(-100.0, -100.0))
+                .with_width(10.0)
+                .with_height(10.0),
+        )
+        .build(&mut ui.build_ctx());
+        CanvasBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(100.0, 100.0))
+                .with_width(100.0)
+                .with_height(100.0)
+                .with_child(child),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        ui.draw();
+
+        assert_eq!(
+            ui.node(child).screen_bounds(),
+            Rect::new(0.0, 0.0, 10.0, 10.0)
+        );
+        assert_ne!(ui.hit_test(Vector2::new(0.0, 0.0)), child);
+        assert_ne!(ui.hit_test(Vector2::new(5.0, 5.0)), child);
+    }
+
+    #[test]
+    fn measure_subtree() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let content = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(300.0)
+                .with_height(200.0)
+                .with_margin(Thickness::uniform(5.0)),
+        )
+        .build(&mut ui.build_ctx());
+        let panel = StackPanelBuilder::new(WidgetBuilder::new().with_child(content))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        let desired_size = ui.node(panel).desired_size();
+
+        let size = ui.measure_subtree(panel, Vector2::new(f32::INFINITY, f32::INFINITY));
+        assert_eq!(size, Vector2::new(310.0, 210.0));
+
+        // Live layout is restored by the next pass.
+        assert!(!ui.node(panel).is_measure_valid());
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.node(panel).desired_size(), desired_size);
+    }
+
+    #[test]
+    fn hover_through_capture() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let a = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        let b = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(200.0, 0.0))
+                .with_width(100.0)
+                .with_height(100.0),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+
+        for (mode, hovered) in [(CaptureMode::Exclusive, a), (CaptureMode::HoverThrough, b)] {
+            ui.process_os_event(&OsEvent::CursorMoved {
+                position: Vector2::new(50.0, 50.0),
+            });
+            assert!(ui.capture_mouse_with_mode(a, mode));
+            while ui.poll_message().is_some() {}
+
+            ui.process_os_event(&OsEvent::CursorMoved {
+                position: Vector2::new(250.0, 50.0),
+            });
+            let mut mouse_move_destination = Handle::NONE;
+            while let Some(message) = ui.poll_message() {
+                if let Some(WidgetMessage::MouseMove { .. }) = message.data::<WidgetMessage>() {
+                    mouse_move_destination = message.destination();
+                }
+            }
+            // Input always goes to captured node.
+            assert_eq!(mouse_move_destination, a);
+            assert_eq!(ui.hover_path().first(), Some(&hovered));
+            assert_eq!(ui.node(b).is_mouse_directly_over, hovered == b);
+
+            ui.release_mouse_capture();
+        }
+    }
+
+    #[test]
+    fn rename_node() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let child =
+            BorderBuilder::new(WidgetBuilder::new().with_name("Old")).build(&mut ui.build_ctx());
+        assert_eq!(ui.node(child).name(), "Old");
+
+        ui.set_node_name(child, "New");
+        assert_eq!(ui.node(child).name(), "New");
+        assert_eq!(ui.find_by_name_down(ui.root(), "New"), child);
+        assert!(ui.find_by_name_down(ui.root(), "Old").is_none());
+    }
+
+    #[test]
+    fn link_to_root() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let child = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let parent =
+            BorderBuilder::new(WidgetBuilder::new().with_child(child)).build(&mut ui.build_ctx());
+        assert_eq!(ui.node(child).parent(), parent);
+
+        ui.link_to_root(child);
+        assert_eq!(ui.node(child).parent(), ui.root());
+        assert!(ui.node(ui.root()).children().contains(&child));
+        assert!(!ui.node(parent).children().contains(&child));
+    }
+
+    #[test]
+    fn antialiasing() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        let vertices = ui.draw().get_vertices().len();
+        let triangles = ui.draw().get_triangles().len();
+
+        ui.set_antialiasing(true);
+        assert!(ui.antialiasing());
+        ui.draw();
+        // Each filled rectangle gets a fringe of 4 vertices and 8 triangles.
+        assert_eq!(ui.draw().get_vertices().len(), vertices + 4);
+        assert_eq!(ui.draw().get_triangles().len(), triangles + 8);
+        // Fringe fades out with color of the rectangle.
+        let colors = ui
+            .draw()
+            .get_vertices()
+            .iter()
+            .map(|vertex| vertex.color)
+            .collect::<Vec<_>>();
+        assert!(colors.contains(&Color::from_rgba(255, 255, 255, 0)));
+        assert!(!colors.contains(&Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn node_id() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let first = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let id = ui.node(first).id();
+        assert_ne!(id, 0);
+        assert_ne!(id, ui.node(ui.root()).id());
+        assert_eq!(ui.find_by_id(id), first);
+
+        // Pool slot is reused, but the id is not.
+        ui.remove_node(first);
+        let second = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert_eq!(second.index(), first.index());
+        assert_ne!(ui.node(second).id(), id);
+        assert!(ui.find_by_id(id).is_none());
+
+        let copy = ui.copy_node(second);
+        assert_ne!(ui.node(copy).id(), ui.node(second).id());
+    }
+
+    #[test]
+    fn tab_navigation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let first = ButtonBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let _not_focusable = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let second = ButtonBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        StackPanelBuilder::new(WidgetBuilder::new().with_child(second)).build(&mut ui.build_ctx());
+        let third = ButtonBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let _explicitly_not_focusable =
+            ButtonBuilder::new(WidgetBuilder::new().with_focusable(false))
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let press_tab = |ui: &mut UserInterface, shift| {
+            ui.keyboard_modifiers.shift = shift;
+            ui.process_os_event(&OsEvent::KeyboardInput {
+                button: KeyCode::Tab,
+                state: ButtonState::Pressed,
+            })
+        };
+
+        assert!(press_tab(&mut ui, false));
+        assert_eq!(ui.keyboard_focus_node(), first);
+        press_tab(&mut ui, false);
+        assert_eq!(ui.keyboard_focus_node(), second);
+        press_tab(&mut ui, false);
+        assert_eq!(ui.keyboard_focus_node(), third);
+        press_tab(&mut ui, false);
+        assert_eq!(ui.keyboard_focus_node(), first);
+        press_tab(&mut ui, true);
+        assert_eq!(ui.keyboard_focus_node(), third);
+
+        // Tab does not reach focused node.
+        while ui.poll_message().is_some() {}
+        ui.process_os_event(&OsEvent::Character('\t'));
+        assert!(ui.poll_message().is_none());
+    }
+
+    #[test]
+    fn tooltip_delay() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let tooltip = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(200.0)
+                .with_height(50.0)
+                .with_visibility(false),
+        )
+        .build(&mut ui.build_ctx());
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(1000.0)
+                .with_height(1000.0)
+                .with_tooltip(tooltip),
+        )
+        .build(&mut ui.build_ctx());
+        ui.set_tooltip_delay(0.5);
+
+        // Picking uses draw commands, so the interface is drawn as in a real frame.
+        let update = |ui: &mut UserInterface, dt| {
+            ui.update(screen_size, dt);
+            while ui.poll_message().is_some() {}
+            ui.draw();
+        };
+
+        update(&mut ui, 0.0);
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(990.0, 990.0),
+        });
+        update(&mut ui, 0.0);
+        update(&mut ui, 0.25);
+        assert!(!ui.node(tooltip).visibility());
+        update(&mut ui, 0.25);
+        assert!(ui.node(tooltip).visibility());
+
+        // Tooltip near bottom-right corner is moved on screen.
+        update(&mut ui, 0.0);
+        update(&mut ui, 0.0);
+        update(&mut ui, 0.0);
+        let bounds = ui.node(tooltip).screen_bounds();
+        assert!(bounds.x() + bounds.w() <= screen_size.x);
+        assert!(bounds.y() + bounds.h() <= screen_size.y);
+    }
+
+    #[test]
+    fn tooltip_hides_on_cursor_move() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let tooltip = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(200.0)
+                .with_height(50.0)
+                .with_visibility(false),
+        )
+        .build(&mut ui.build_ctx());
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(1000.0)
+                .with_height(1000.0)
+                .with_tooltip(tooltip),
+        )
+        .build(&mut ui.build_ctx());
+        ui.set_tooltip_delay(0.5);
+
+        // Picking uses draw commands, so the interface is drawn as in a real frame.
+        let update = |ui: &mut UserInterface, dt| {
+            ui.update(screen_size, dt);
+            while ui.poll_message().is_some() {}
+            ui.draw();
+        };
+
+        update(&mut ui, 0.0);
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(500.0, 500.0),
+        });
+        update(&mut ui, 0.0);
+        update(&mut ui, 0.5);
+        assert!(ui.node(tooltip).visibility());
+
+        // Small jitter keeps the tooltip.
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(500.0, 502.0),
+        });
+        update(&mut ui, 0.0);
+        assert!(ui.node(tooltip).visibility());
+
+        // Movement hides the tooltip and restarts the delay.
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(600.0, 600.0),
+        });
+        update(&mut ui, 0.0);
+        assert!(!ui.node(tooltip).visibility());
+        update(&mut ui, 0.25);
+        assert!(!ui.node(tooltip).visibility());
+        update(&mut ui, 0.25);
+        assert!(ui.node(tooltip).visibility());
+    }
+
+    #[test]
+    fn z_index_on_link() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let overlay =
+            BorderBuilder::new(WidgetBuilder::new().with_z_index(1)).build(&mut ui.build_ctx());
+        let content = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let panel =
+            CanvasBuilder::new(WidgetBuilder::new().with_child(overlay).with_child(content))
+                .build(&mut ui.build_ctx());
+        assert_eq!(ui.node(panel).children(), &[content, overlay]);
+
+        let more_content = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        ui.send_message(WidgetMessage::link_with(
+            more_content,
+            MessageDirection::ToWidget,
+            panel,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(panel).children(), &[content, more_content, overlay]);
+
+        // Topmost node stays below nodes with higher z-index.
+        ui.send_message(WidgetMessage::topmost(content, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(panel).children(), &[more_content, content, overlay]);
+    }
+
+    #[test]
+    fn remove_node() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let child = ButtonBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let parent = StackPanelBuilder::new(WidgetBuilder::new().with_child(child))
+            .build(&mut ui.build_ctx());
+        let count = ui.nodes().alive_count();
+
+        ui.keyboard_focus_node = child;
+        ui.captured_node = child;
+
+        ui.remove_node(parent);
+        assert!(!ui.is_valid_handle(parent));
+        assert!(!ui.is_valid_handle(child));
+        assert!(ui.nodes().alive_count() < count - 1);
+        assert!(ui.keyboard_focus_node.is_none());
+        assert!(ui.captured_node.is_none());
+        assert!(!ui.node(ui.root()).children().contains(&parent));
+
+        // Removal of already removed node and root canvas does nothing.
+        let count = ui.nodes().alive_count();
+        ui.remove_node(parent);
+        ui.remove_node(ui.root());
+        assert_eq!(ui.nodes().alive_count(), count);
+    }
+
+    #[test]
+    fn remove_node_with_shared_tooltip() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let tooltip = BorderBuilder::new(WidgetBuilder::new().with_visibility(false))
+            .build(&mut ui.build_ctx());
+        let a = BorderBuilder::new(WidgetBuilder::new().with_tooltip(tooltip))
+            .build(&mut ui.build_ctx());
+        let b = BorderBuilder::new(WidgetBuilder::new().with_tooltip(tooltip))
+            .build(&mut ui.build_ctx());
+        let parent = StackPanelBuilder::new(WidgetBuilder::new().with_child(a).with_child(b))
+            .build(&mut ui.build_ctx());
+        let count = ui.nodes().alive_count();
+
+        ui.remove_node(parent);
+        assert!(!ui.is_valid_handle(tooltip));
+        assert_eq!(ui.nodes().alive_count(), count - 4);
+    }
+
+    #[test]
+    fn nested_disabled_opacity() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let mut node = Handle::NONE;
+        for _ in 0..4 {
+            let mut builder = WidgetBuilder::new()
+                .with_width(100.0)
+                .with_height(100.0)
+                .with_background(Brush::Solid(Color::WHITE));
+            if node.is_some() {
+                builder = builder.with_child(node);
+            }
+            node = BorderBuilder::new(builder).build(&mut ui.build_ctx());
+        }
+        ui.send_message(WidgetMessage::enabled(
+            node,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+
+        // Every node of the disabled sub-tree is equally faded.
+        let opacities = ui
+            .draw()
+            .get_commands()
+            .iter()
+            .map(|command| command.opacity)
+            .collect::<Vec<_>>();
+        assert!(opacities.contains(&0.4));
+        assert!(opacities.iter().all(|opacity| *opacity >= 0.4));
+    }
+
+    #[test]
+    fn nested_opacity() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_opacity(Some(0.5))
+                .with_background(Brush::Solid(Color::WHITE)),
+        )
+        .build(&mut ui.build_ctx());
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(100.0)
+                .with_height(100.0)
+                .with_opacity(Some(0.5))
+                .with_background(Brush::Solid(Color::WHITE))
+                .with_child(child),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let opacities = ui
+            .draw()
+            .get_commands()
+            .iter()
+            .map(|command| command.opacity)
+            .collect::<Vec<_>>();
+        assert!(opacities.contains(&0.5));
+        assert!(opacities.contains(&0.25));
+    }
+
+    #[test]
+    fn incremental_layout() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let children = (0..100)
+            .map(|_| {
+                BorderBuilder::new(WidgetBuilder::new().with_height(5.0)).build(&mut ui.build_ctx())
+            })
+            .collect::<Vec<_>>();
+        StackPanelBuilder::new(WidgetBuilder::new().with_children(children.iter().cloned()))
+            .build(&mut ui.build_ctx());
+
+        ui.update(screen_size, 0.0);
+        let statistics = ui.layout_statistics();
+        assert!(statistics.measured_nodes > 100);
+        assert!(statistics.arranged_nodes > 100);
+
+        // Nothing has changed, so layout pass is skipped.
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.layout_statistics(), Default::default());
+
+        // Only the changed node and its ancestors (the panel and the root canvas) are measured
+        // again, siblings reuse their cached desired size.
+        ui.send_message(WidgetMessage::height(
+            children[50],
+            MessageDirection::ToWidget,
+            10.0,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        assert!(ui.layout_statistics().measured_nodes <= 3);
+    }
+
+    #[test]
+    fn self_handle() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let widget = WidgetBuilder::new().build();
+        assert!(widget.handle().is_none());
+
+        let border = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        assert_eq!(ui.node(border).handle(), border);
+        assert_eq!(ui.node(ui.root()).handle(), ui.root());
+    }
+
+    #[test]
+    fn focus_navigation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let mut make = |x, y, focusable| {
+            BorderBuilder::new(
+                WidgetBuilder::new()
+                    .with_desired_position(Vector2::new(x, y))
+                    .with_width(100.0)
+                    .with_height(100.0)
+                    .with_focusable(focusable),
+            )
+            .build(&mut ui.build_ctx())
+        };
+        let top_left = make(0.0, 0.0, true);
+        let _not_focusable = make(200.0, 0.0, false);
+        let top_right = make(400.0, 0.0, true);
+        let bottom_left = make(0.0, 400.0, true);
+        let bottom_right = make(420.0, 380.0, true);
+        ui.update(screen_size, 0.0);
+
+        // Nothing is focused, so the top-left node gets focus first.
+        assert!(ui.navigate_focus(NavigationDirection::Right));
+        assert_eq!(ui.keyboard_focus_node(), top_left);
+        assert!(ui.process_os_event(&OsEvent::Navigate(NavigationDirection::Right)));
+        assert_eq!(ui.keyboard_focus_node(), top_right);
+        assert!(ui.navigate_focus(NavigationDirection::Down));
+        assert_eq!(ui.keyboard_focus_node(), bottom_right);
+        assert!(ui.navigate_focus(NavigationDirection::Left));
+        assert_eq!(ui.keyboard_focus_node(), bottom_left);
+
+        // There is nothing below.
+        assert!(!ui.navigate_focus(NavigationDirection::Down));
+        assert_eq!(ui.keyboard_focus_node(), bottom_left);
+
+        ui.set_navigation_settings(NavigationSettings {
+            edge_behavior: NavigationEdgeBehavior::Wrap,
+            ..Default::default()
+        });
+        assert!(ui.navigate_focus(NavigationDirection::Down));
+        assert_eq!(ui.keyboard_focus_node(), top_left);
+
+        let mut focus_messages = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            if let Some(msg @ (WidgetMessage::GotFocus | WidgetMessage::LostFocus)) =
+                message.data::<WidgetMessage>()
+            {
+                focus_messages.push((message.destination(), msg.clone()));
+            }
+        }
+        assert_eq!(
+            focus_messages.last(),
+            Some(&(top_left, WidgetMessage::GotFocus))
+        );
+    }
+
+    #[test]
+    fn multi_touch() {
+        fn touch(
+            ui: &mut UserInterface,
+            id: u64,
+            phase: TouchPhase,
+            x: f32,
+        ) -> Vec<(Handle<UiNode>, WidgetMessage)> {
+            ui.process_os_event(&OsEvent::Touch {
+                id,
+                phase,
+                position: Vector2::new(x, 50.0),
+            });
+            let mut messages = Vec::new();
+            while let Some(message) = ui.poll_message() {
+                if let Some(msg) = message.data::<WidgetMessage>() {
+                    messages.push((message.destination(), msg.clone()));
+                }
+            }
+            messages
+        }
+
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let a = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        let b = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(200.0, 0.0))
+                .with_width(100.0)
+                .with_height(100.0),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+        let pos = |x| Vector2::new(x, 50.0);
+
+        // First touch is reported as mouse press as well.
+        let messages = touch(&mut ui, 1, TouchPhase::Started, 50.0);
+        let started = WidgetMessage::TouchStarted {
+            id: 1,
+            pos: pos(50.0),
+        };
+        assert!(messages.contains(&(a, started)));
+        let mouse_down = WidgetMessage::MouseDown {
+            pos: pos(50.0),
+            button: MouseButton::Left,
+        };
+        assert!(messages.contains(&(a, mouse_down)));
+
+        let messages = touch(&mut ui, 2, TouchPhase::Started, 250.0);
+        let started = WidgetMessage::TouchStarted {
+            id: 2,
+            pos: pos(250.0),
+        };
+        assert!(messages.contains(&(b, started)));
+        assert!(!messages
+            .iter()
+            .any(|(_, msg)| matches!(msg, WidgetMessage::MouseDown { .. })));
+
+        // Second finger stays bound to its node even over another node.
+        let messages = touch(&mut ui, 2, TouchPhase::Moved, 50.0);
+        let moved = WidgetMessage::TouchMoved {
+            id: 2,
+            pos: pos(50.0),
+        };
+        assert!(messages.contains(&(b, moved)));
+
+        let messages = touch(&mut ui, 2, TouchPhase::Ended, 50.0);
+        let ended = WidgetMessage::TouchEnded {
+            id: 2,
+            pos: pos(50.0),
+        };
+        assert!(messages.contains(&(b, ended)));
+
+        let messages = touch(&mut ui, 1, TouchPhase::Ended, 50.0);
+        let mouse_up = WidgetMessage::MouseUp {
+            pos: pos(50.0),
+            button: MouseButton::Left,
+        };
+        assert!(messages.contains(&(a, mouse_up)));
+    }
+
+    #[test]
+    fn cancelled_touch_does_not_click() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let button = ButtonBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+
+        let touch = |ui: &mut UserInterface, phase| {
+            ui.process_os_event(&OsEvent::Touch {
+                id: 1,
+                phase,
+                position: Vector2::new(50.0, 50.0),
+            });
+            let mut messages = Vec::new();
+            while let Some(message) = ui.poll_message() {
+                messages.push(message);
+            }
+            messages
+        };
+
+        let messages = touch(&mut ui, TouchPhase::Started);
+        assert!(messages.iter().any(|message| {
+            let destination = message.destination();
+            matches!(
+                message.data::<WidgetMessage>(),
+                Some(WidgetMessage::MouseDown { .. })
+            ) && (destination == button || ui.is_node_child_of(destination, button))
+        }));
+        assert!(ui.captured_node.is_some());
+
+        let mut messages = touch(&mut ui, TouchPhase::Cancelled);
+        // Long press must not be fired for the cancelled touch.
+        ui.update(screen_size, 1.0);
+        while let Some(message) = ui.poll_message() {
+            messages.push(message);
+        }
+        assert!(!messages.iter().any(|message| {
+            message.data::<ButtonMessage>() == Some(&ButtonMessage::Click)
+                || matches!(
+                    message.data::<WidgetMessage>(),
+                    Some(WidgetMessage::LongPress { .. })
+                )
+        }));
+        assert!(ui.captured_node.is_none());
+        assert!(ui.primary_touch.is_none());
+    }
+
+    // Presses and releases left mouse button at the given positions, returns destinations of
+    // double click messages.
+    fn click(
+        ui: &mut UserInterface,
+        press: Vector2<f32>,
+        release: Vector2<f32>,
+    ) -> Vec<Handle<UiNode>> {
+        for (position, state) in [
+            (press, ButtonState::Pressed),
+            (release, ButtonState::Released),
+        ] {
+            ui.process_os_event(&OsEvent::CursorMoved { position });
+            ui.process_os_event(&OsEvent::MouseInput {
+                button: MouseButton::Left,
+                state,
+            });
+        }
+        let mut double_clicks = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            if let Some(WidgetMessage::DoubleClick { .. }) = message.data::<WidgetMessage>() {
+                double_clicks.push(message.destination());
+            }
+        }
+        double_clicks
+    }
+
+    fn two_borders(ui: &mut UserInterface) -> (Handle<UiNode>, Handle<UiNode>) {
+        let mut border = |x| {
+            BorderBuilder::new(
+                WidgetBuilder::new()
+                    .with_desired_position(Vector2::new(x, 0.0))
+                    .with_width(100.0)
+                    .with_height(100.0),
+            )
+            .build(&mut ui.build_ctx())
+        };
+        let a = border(0.0);
+        let b = border(100.0);
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+        (a, b)
+    }
+
+    #[test]
+    fn double_click() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let (a, _) = two_borders(&mut ui);
+
+        let position = Vector2::new(50.0, 50.0);
+        assert!(click(&mut ui, position, position).is_empty());
+        assert_eq!(click(&mut ui, position, position), vec![a]);
+        // Third click starts a new sequence.
+        assert!(click(&mut ui, position, position).is_empty());
+    }
+
+    #[test]
+    fn release_over_other_node_is_not_click() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let (_, b) = two_borders(&mut ui);
+
+        // Press on the first node and release on the second one nearby, it is not a click, so
+        // the next click on the second node is not a double click.
+        assert!(click(&mut ui, Vector2::new(95.0, 50.0), Vector2::new(105.0, 50.0)).is_empty());
+        let position = Vector2::new(105.0, 50.0);
+        assert!(click(&mut ui, position, position).is_empty());
+        assert_eq!(click(&mut ui, position, position), vec![b]);
+    }
+
+    #[test]
+    fn thickness_arithmetic() {
+        let a = Thickness::new(1.0, 2.0, 3.0, 4.0);
+        let b = Thickness::left_right(1.0) + Thickness::top_bottom(2.0);
+        assert_eq!(a - b, Thickness::new(0.0, 0.0, 2.0, 2.0));
+        assert_eq!(a.horizontal(), 4.0);
+        assert_eq!(a.vertical(), 6.0);
+
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let deflated = a.deflate_rect(&rect);
+        assert_eq!(deflated, Rect::new(1.0, 2.0, 6.0, 4.0));
+        assert_eq!(a.inflate_rect(&deflated), rect);
+    }
+
+    #[test]
+    fn padding_offsets_children() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_height(20.0)
+                .with_margin(Thickness::uniform(1.0)),
+        )
+        .build(&mut ui.build_ctx());
+        let panel = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_width(100.0)
+                .with_padding(Thickness::uniform(10.0))
+                .with_child(child),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let panel_ref = ui.node(panel);
+        assert_eq!(panel_ref.desired_size(), Vector2::new(100.0, 42.0));
+        let child_ref = ui.node(child);
+        assert_eq!(child_ref.actual_local_position(), Vector2::new(11.0, 11.0));
+        assert_eq!(child_ref.actual_size(), Vector2::new(78.0, 20.0));
+    }
 }
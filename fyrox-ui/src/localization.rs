@@ -0,0 +1,110 @@
+//! String tables for localization of UI text. See [`LocalizationTable`] docs for more info.
+
+use fxhash::FxHashMap;
+
+/// A set of string tables, one per language. Text widgets could reference strings by keys
+/// (see [`crate::text::TextBuilder::with_localized_key`]), so the UI could be shown in different
+/// languages without rebuilding it. Strings could contain placeholders like `{0}`, `{1}`, etc.
+/// which are replaced with format arguments.
+#[derive(Default, Debug, Clone)]
+pub struct LocalizationTable {
+    strings: FxHashMap<String, FxHashMap<String, String>>,
+    language: String,
+}
+
+impl LocalizationTable {
+    /// Creates new empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a string for a key in given language. Returns previous string of the key, if any.
+    pub fn add_string<L, K, V>(&mut self, language: L, key: K, value: V) -> Option<String>
+    where
+        L: AsRef<str>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.strings
+            .entry(language.as_ref().to_owned())
+            .or_default()
+            .insert(key.as_ref().to_owned(), value.as_ref().to_owned())
+    }
+
+    /// Sets active language. Use [`crate::UserInterface::set_language`] to refresh localized
+    /// text widgets as well.
+    pub fn set_language<L: AsRef<str>>(&mut self, language: L) {
+        self.language = language.as_ref().to_owned();
+    }
+
+    /// Returns active language.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Returns a string of the key in active language.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .get(&self.language)
+            .and_then(|strings| strings.get(key))
+            .map(|value| value.as_str())
+    }
+
+    /// Returns a string of the key in active language with placeholders replaced by arguments.
+    /// If there is no such string, the key itself is used, so missing translations are easy to
+    /// spot.
+    pub fn format(&self, key: &str, args: &[String]) -> String {
+        format_string(self.get(key).unwrap_or(key), args)
+    }
+}
+
+/// Replaces `{N}` placeholders in the template with N-th argument. Placeholders with indices
+/// out of bounds of arguments are left as is.
+pub fn format_string(template: &str, args: &[String]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(begin) = rest.find('{') {
+        result.push_str(&rest[..begin]);
+        rest = &rest[begin..];
+
+        let arg = rest.find('}').and_then(|end| {
+            rest[1..end]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| args.get(index))
+                .map(|arg| (arg, end))
+        });
+
+        match arg {
+            Some((arg, end)) => {
+                result.push_str(arg);
+                rest = &rest[(end + 1)..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::localization::{format_string, LocalizationTable};
+
+    #[test]
+    fn format() {
+        let args = ["a".to_owned(), "b".to_owned()];
+        assert_eq!(format_string("{1} and {0}", &args), "b and a");
+        assert_eq!(format_string("{2} {x} {", &args), "{2} {x} {");
+
+        let mut table = LocalizationTable::new();
+        table.add_string("en", "greeting", "Hello, {0}!");
+        table.add_string("fr", "greeting", "Bonjour, {0}!");
+        table.set_language("fr");
+        assert_eq!(table.format("greeting", &args), "Bonjour, a!");
+        assert_eq!(table.format("missing", &args), "missing");
+    }
+}
@@ -275,7 +275,7 @@ impl Control for MenuItem {
                         message.set_handled(true);
                     }
                 }
-                WidgetMessage::MouseEnter => {
+                WidgetMessage::MouseEnter if message.destination() == self.handle() => {
                     // While parent menu active it is possible to open submenus
                     // by simple mouse hover.
                     let menu = find_menu(self.parent(), ui);
@@ -290,6 +290,76 @@ pub enum MouseButton {
     Other(u16),
 }
 
+/// Input method editor (IME) event, used to enter text in languages like Japanese, Chinese or
+/// Korean, where a character is composed from multiple key presses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// Composition (pre-edit) text has changed. Empty text means that composition has ended.
+    Preedit(String),
+    /// Composition is finished and the text should be inserted.
+    Commit(String),
+}
+
+/// Scroll amount of a mouse wheel or a touchpad. Positive values mean scrolling up (or left).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WheelDelta {
+    /// Amount in lines along horizontal and vertical axes, this is what mouse wheels report.
+    Lines(Vector2<f32>),
+    /// Amount in pixels along horizontal and vertical axes, touchpads with precise scrolling
+    /// report this.
+    Pixels(Vector2<f32>),
+}
+
+impl WheelDelta {
+    /// Height of a line in pixels, it is used to convert pixel amounts to lines.
+    pub const LINE_HEIGHT: f32 = 17.0;
+
+    /// Returns scroll amount in lines, pixel amounts are converted using [`Self::LINE_HEIGHT`].
+    /// The result could be fractional.
+    pub fn lines(&self) -> Vector2<f32> {
+        match *self {
+            WheelDelta::Lines(lines) => lines,
+            WheelDelta::Pixels(pixels) => pixels.scale(1.0 / Self::LINE_HEIGHT),
+        }
+    }
+
+    /// Returns scroll amount in pixels, line amounts are converted using [`Self::LINE_HEIGHT`].
+    pub fn pixels(&self) -> Vector2<f32> {
+        match *self {
+            WheelDelta::Lines(lines) => lines.scale(Self::LINE_HEIGHT),
+            WheelDelta::Pixels(pixels) => pixels,
+        }
+    }
+
+    /// Returns vertical scroll amount as a whole number of wheel notches (rounded towards zero),
+    /// for code that only needs discrete steps.
+    pub fn notches(&self) -> i32 {
+        self.lines().y as i32
+    }
+}
+
+/// Phase of a touch on a touch screen.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum TouchPhase {
+    /// A finger touched the screen.
+    Started,
+    /// A finger moved along the screen.
+    Moved,
+    /// A finger was lifted from the screen.
+    Ended,
+    /// The touch was interrupted by OS (for example when the window lost focus).
+    Cancelled,
+}
+
+/// Direction of focus navigation, see [`crate::UserInterface::navigate_focus`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum NavigationDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
 pub enum OsEvent {
     MouseInput {
         button: MouseButton,
@@ -304,7 +374,18 @@ pub enum OsEvent {
     },
     Character(char),
     KeyboardModifiers(KeyboardModifiers),
-    MouseWheel(f32, f32),
+    MouseWheel(WheelDelta),
+    Ime(ImeEvent),
+    /// A touch on a touch screen. Every finger has its own unique id for the whole duration of
+    /// the touch.
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        position: Vector2<f32>,
+    },
+    /// A request to move keyboard focus in given direction. There is no standard source of such
+    /// events, a game should map D-pad, stick or any other input of a controller to them.
+    Navigate(NavigationDirection),
 }
 
 #[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy, Default)]
@@ -104,16 +104,34 @@ impl<T: NumericType> NumericUpDown<T> {
         clamp(value, self.min_value, self.max_value)
     }
 
+    fn sync_text_field(&self, ui: &UserInterface) {
+        ui.send_message(TextBoxMessage::text(
+            self.field,
+            MessageDirection::ToWidget,
+            format!("{:.1$}", self.value, self.precision),
+        ));
+    }
+
     fn try_parse_value(&mut self, ui: &mut UserInterface) {
         // Parse input only when focus is lost from text field.
         if let Some(field) = ui.node(self.field).cast::<TextBox>() {
-            if let Ok(value) = field.text().parse::<T>() {
-                let value = self.clamp_value(value);
-                ui.send_message(NumericUpDownMessage::value(
-                    self.handle(),
-                    MessageDirection::ToWidget,
-                    value,
-                ));
+            match field.text().parse::<T>() {
+                Ok(value) => {
+                    let value = self.clamp_value(value);
+                    if value == self.value {
+                        // Value won't change, but text could be out of range or differently
+                        // formatted, so show actual value.
+                        self.sync_text_field(ui);
+                    } else {
+                        ui.send_message(NumericUpDownMessage::value(
+                            self.handle(),
+                            MessageDirection::ToWidget,
+                            value,
+                        ));
+                    }
+                }
+                // Revert to last valid value.
+                Err(_) => self.sync_text_field(ui),
             }
         }
     }
@@ -175,12 +193,7 @@ impl<T: NumericType> Control for NumericUpDown<T> {
                 if self.value != clamped {
                     self.value = clamped;
 
-                    // Sync text field.
-                    ui.send_message(TextBoxMessage::text(
-                        self.field,
-                        MessageDirection::ToWidget,
-                        format!("{:.1$}", self.value, self.precision),
-                    ));
+                    self.sync_text_field(ui);
 
                     let mut msg = NumericUpDownMessage::value(
                         self.handle,
@@ -350,3 +363,61 @@ impl<T: NumericType> NumericUpDownBuilder<T> {
         ctx.add_node(UiNode::new(node))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, pool::Handle},
+        message::MessageDirection,
+        numeric::{NumericUpDown, NumericUpDownBuilder},
+        text_box::{TextBox, TextBoxMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        UiNode, UserInterface,
+    };
+
+    fn enter_text(ui: &mut UserInterface, numeric: Handle<UiNode>, text: &str) -> (f32, String) {
+        let field = ui.node(numeric).cast::<NumericUpDown<f32>>().unwrap().field;
+        ui.send_message(TextBoxMessage::text(
+            field,
+            MessageDirection::ToWidget,
+            text.to_owned(),
+        ));
+        ui.send_message(WidgetMessage::lost_focus(
+            field,
+            MessageDirection::FromWidget,
+        ));
+        while ui.poll_message().is_some() {}
+
+        (
+            ui.node(numeric).cast::<NumericUpDown<f32>>().unwrap().value,
+            ui.node(field).cast::<TextBox>().unwrap().text(),
+        )
+    }
+
+    #[test]
+    fn text_entry() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let numeric = NumericUpDownBuilder::new(WidgetBuilder::new())
+            .with_min_value(0.0f32)
+            .with_max_value(10.0)
+            .with_value(1.0)
+            .with_precision(1)
+            .build(&mut ui.build_ctx());
+
+        assert_eq!(enter_text(&mut ui, numeric, "2.5"), (2.5, "2.5".to_owned()));
+        // Out of range values are clamped.
+        assert_eq!(
+            enter_text(&mut ui, numeric, "20"),
+            (10.0, "10.0".to_owned())
+        );
+        assert_eq!(
+            enter_text(&mut ui, numeric, "30"),
+            (10.0, "10.0".to_owned())
+        );
+        // Invalid input reverts to last valid value.
+        assert_eq!(
+            enter_text(&mut ui, numeric, "abc"),
+            (10.0, "10.0".to_owned())
+        );
+    }
+}
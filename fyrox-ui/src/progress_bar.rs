@@ -11,21 +11,35 @@ use crate::{
 use std::{
     any::{Any, TypeId},
     ops::{Deref, DerefMut},
+    sync::mpsc::Sender,
 };
 
+/// Time (in seconds) that the highlight needs to pass through the whole bar in indeterminate mode.
+pub const INDETERMINATE_PERIOD: f32 = 1.5;
+
+/// Width of the highlight in indeterminate mode relative to the width of the bar.
+pub const INDETERMINATE_SEGMENT: f32 = 0.25;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProgressBarMessage {
     Progress(f32),
+    /// Switches the bar between determinate (fills proportionally to progress) and indeterminate
+    /// (shows a moving highlight) modes.
+    Indeterminate(bool),
 }
 
 impl ProgressBarMessage {
     define_constructor!(ProgressBarMessage:Progress => fn progress(f32), layout: false);
+    define_constructor!(ProgressBarMessage:Indeterminate => fn indeterminate(bool), layout: false);
 }
 
 #[derive(Clone)]
 pub struct ProgressBar {
     widget: Widget,
     progress: f32,
+    indeterminate: bool,
+    // Position of the highlight in indeterminate mode, [0; 1) range.
+    phase: f32,
     indicator: Handle<UiNode>,
     body: Handle<UiNode>,
 }
@@ -49,10 +63,24 @@ impl Control for ProgressBar {
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
         let size = self.widget.arrange_override(ui, final_size);
 
+        let (x, width) = if self.indeterminate {
+            // Highlight enters from the left edge and leaves through the right one.
+            let width = size.x * INDETERMINATE_SEGMENT;
+            ((size.x + width) * self.phase - width, width)
+        } else {
+            (0.0, size.x * self.progress)
+        };
+
+        ui.send_message(WidgetMessage::desired_position(
+            self.indicator,
+            MessageDirection::ToWidget,
+            Vector2::new(x, 0.0),
+        ));
+
         ui.send_message(WidgetMessage::width(
             self.indicator,
             MessageDirection::ToWidget,
-            size.x * self.progress,
+            width,
         ));
 
         ui.send_message(WidgetMessage::height(
@@ -64,17 +92,31 @@ impl Control for ProgressBar {
         size
     }
 
+    fn update(&mut self, dt: f32, _sender: &Sender<UiMessage>) {
+        if self.indeterminate {
+            self.phase = (self.phase + dt / INDETERMINATE_PERIOD).fract();
+            self.invalidate_arrange();
+        }
+    }
+
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
         if message.destination() == self.handle {
-            if let Some(&ProgressBarMessage::Progress(progress)) =
-                message.data::<ProgressBarMessage>()
-            {
-                if progress != self.progress {
-                    self.set_progress(progress);
-                    self.invalidate_layout();
+            match message.data::<ProgressBarMessage>() {
+                Some(&ProgressBarMessage::Progress(progress)) => {
+                    if progress != self.progress {
+                        self.set_progress(progress);
+                        self.invalidate_layout();
+                    }
                 }
+                Some(&ProgressBarMessage::Indeterminate(indeterminate)) => {
+                    if indeterminate != self.indeterminate {
+                        self.set_indeterminate(indeterminate);
+                        self.invalidate_layout();
+                    }
+                }
+                None => {}
             }
         }
     }
@@ -88,6 +130,20 @@ impl ProgressBar {
     pub fn progress(&self) -> f32 {
         self.progress
     }
+
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        self.indeterminate = indeterminate;
+        self.phase = 0.0;
+    }
+
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate
+    }
+
+    /// Returns position of the highlight in indeterminate mode in [0; 1) range.
+    pub fn phase(&self) -> f32 {
+        self.phase
+    }
 }
 
 pub struct ProgressBarBuilder {
@@ -95,6 +151,7 @@ pub struct ProgressBarBuilder {
     body: Option<Handle<UiNode>>,
     indicator: Option<Handle<UiNode>>,
     progress: f32,
+    indeterminate: bool,
 }
 
 impl ProgressBarBuilder {
@@ -104,6 +161,7 @@ impl ProgressBarBuilder {
             body: None,
             indicator: None,
             progress: 0.0,
+            indeterminate: false,
         }
     }
 
@@ -122,6 +180,13 @@ impl ProgressBarBuilder {
         self
     }
 
+    /// Sets whether the bar should show a moving highlight instead of actual progress. Useful
+    /// when the amount of work is unknown.
+    pub fn with_indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
     pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
         let body = self
             .body
@@ -141,6 +206,8 @@ impl ProgressBarBuilder {
         let progress_bar = ProgressBar {
             widget: self.widget_builder.with_child(body).build(),
             progress: self.progress,
+            indeterminate: self.indeterminate,
+            phase: 0.0,
             indicator,
             body,
         };
@@ -148,3 +215,57 @@ impl ProgressBarBuilder {
         ctx.add_node(UiNode::new(progress_bar))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        message::MessageDirection,
+        progress_bar::{
+            ProgressBar, ProgressBarBuilder, ProgressBarMessage, INDETERMINATE_PERIOD,
+            INDETERMINATE_SEGMENT,
+        },
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+
+    #[test]
+    fn indeterminate() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let progress_bar =
+            ProgressBarBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(10.0))
+                .with_progress(0.5)
+                .with_indeterminate(true)
+                .build(&mut ui.build_ctx());
+
+        // Phase depends only on elapsed time, not on the amount of frames.
+        for _ in 0..4 {
+            ui.update(screen_size, INDETERMINATE_PERIOD * 0.125);
+        }
+        let bar = ui.node(progress_bar).cast::<ProgressBar>().unwrap();
+        let (phase, indicator) = (bar.phase(), bar.indicator);
+        assert!((phase - 0.5).abs() < 1e-5);
+
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        let width = 100.0 * INDETERMINATE_SEGMENT;
+        assert_eq!(ui.node(indicator).width(), width);
+        assert_eq!(
+            ui.node(indicator).desired_local_position().x,
+            (100.0 + width) * phase - width
+        );
+
+        ui.send_message(ProgressBarMessage::indeterminate(
+            progress_bar,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(indicator).width(), 50.0);
+        assert_eq!(ui.node(indicator).desired_local_position().x, 0.0);
+    }
+}
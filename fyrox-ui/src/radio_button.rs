@@ -0,0 +1,318 @@
+//! Radio button is a check box that belongs to a group, only one radio button of a group can be
+//! checked at a time. Checking a radio button unchecks every other radio button of its group.
+//!
+//! Radio buttons with the same named group (see [`RadioButtonBuilder::with_group`]) form one
+//! group regardless of their place in the tree. Radio buttons without a group are grouped with
+//! their siblings that have no group either.
+
+use crate::{
+    border::BorderBuilder,
+    core::{algebra::Vector2, pool::Handle},
+    define_constructor,
+    grid::{Column, GridBuilder, Row},
+    message::{MessageDirection, UiMessage},
+    vector_image::{Primitive, VectorImageBuilder},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, HorizontalAlignment, NodeHandleMapping, Thickness, UiNode,
+    UserInterface, VerticalAlignment, BRUSH_DARK, BRUSH_LIGHT, BRUSH_TEXT,
+};
+use std::{
+    any::{Any, TypeId},
+    ops::{Deref, DerefMut},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadioButtonMessage {
+    /// Checks or unchecks a radio button. A radio button responds with the same message (with
+    /// [`MessageDirection::FromWidget`]) only if its state has actually changed, so clicking
+    /// already checked radio button won't produce any response.
+    Check(bool),
+}
+
+impl RadioButtonMessage {
+    define_constructor!(RadioButtonMessage:Check => fn checked(bool), layout: false);
+}
+
+#[derive(Clone)]
+pub struct RadioButton {
+    pub widget: Widget,
+    pub checked: bool,
+    pub group: Option<String>,
+    pub check_mark: Handle<UiNode>,
+}
+
+crate::define_widget_deref!(RadioButton);
+
+impl RadioButton {
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn is_in_same_group(&self, other: &RadioButton) -> bool {
+        match self.group {
+            Some(ref group) => other.group.as_ref() == Some(group),
+            None => other.group.is_none() && other.parent() == self.parent(),
+        }
+    }
+}
+
+impl Control for RadioButton {
+    fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn resolve(&mut self, node_map: &NodeHandleMapping) {
+        node_map.resolve(&mut self.check_mark);
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::MouseDown { .. } => {
+                    if message.destination() == self.handle()
+                        || self.widget.has_descendant(message.destination(), ui)
+                    {
+                        ui.capture_mouse(self.handle());
+                    }
+                }
+                WidgetMessage::MouseUp { .. } => {
+                    if message.destination() == self.handle()
+                        || self.widget.has_descendant(message.destination(), ui)
+                    {
+                        ui.release_mouse_capture();
+
+                        // Radio button cannot be unchecked by a click.
+                        ui.send_message(RadioButtonMessage::checked(
+                            self.handle(),
+                            MessageDirection::ToWidget,
+                            true,
+                        ));
+                    }
+                }
+                _ => (),
+            }
+        } else if let Some(&RadioButtonMessage::Check(value)) = message.data::<RadioButtonMessage>()
+        {
+            if message.direction() == MessageDirection::ToWidget
+                && message.destination() == self.handle()
+                && self.checked != value
+            {
+                self.checked = value;
+
+                ui.send_message(message.reverse());
+
+                ui.send_message(WidgetMessage::visibility(
+                    self.check_mark,
+                    MessageDirection::ToWidget,
+                    value,
+                ));
+
+                if value {
+                    // Uncheck the rest of the group. This radio button is not in the pool while
+                    // it handles the message, so it won't be visited.
+                    for (handle, node) in ui.nodes().pair_iter() {
+                        if let Some(radio_button) = node.cast::<RadioButton>() {
+                            if radio_button.checked && self.is_in_same_group(radio_button) {
+                                ui.send_message(RadioButtonMessage::checked(
+                                    handle,
+                                    MessageDirection::ToWidget,
+                                    false,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct RadioButtonBuilder {
+    widget_builder: WidgetBuilder,
+    checked: bool,
+    group: Option<String>,
+    content: Handle<UiNode>,
+}
+
+impl RadioButtonBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            checked: false,
+            group: None,
+            content: Handle::NONE,
+        }
+    }
+
+    /// Sets initial state of the radio button. Other radio buttons of the group are not
+    /// affected, make sure that only one radio button of a group is initially checked.
+    pub fn checked(mut self, value: bool) -> Self {
+        self.checked = value;
+        self
+    }
+
+    /// Puts the radio button into a named group. Without a group, the radio button is grouped
+    /// with its siblings.
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_owned());
+        self
+    }
+
+    pub fn with_content(mut self, content: Handle<UiNode>) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let check_mark = VectorImageBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(self.checked)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_foreground(BRUSH_TEXT),
+        )
+        .with_primitives(vec![Primitive::Circle {
+            center: Vector2::new(4.0, 4.0),
+            radius: 4.0,
+            segments: 16,
+        }])
+        .build(ctx);
+
+        let background = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_min_size(Vector2::new(16.0, 16.0))
+                .with_background(BRUSH_DARK)
+                .with_foreground(BRUSH_LIGHT)
+                .with_child(check_mark),
+        )
+        .with_stroke_thickness(Thickness::uniform(1.0))
+        .build(ctx);
+
+        if self.content.is_some() {
+            ctx[self.content].set_row(0).set_column(1);
+        }
+
+        let grid = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_child(background)
+                .with_child(self.content),
+        )
+        .add_row(Row::stretch())
+        .add_column(Column::auto())
+        .add_column(Column::auto())
+        .build(ctx);
+
+        let radio_button = RadioButton {
+            widget: self.widget_builder.with_child(grid).build(),
+            checked: self.checked,
+            group: self.group,
+            check_mark,
+        };
+        ctx.add_node(UiNode::new(radio_button))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, pool::Handle},
+        message::{MessageDirection, MouseButton},
+        radio_button::{RadioButton, RadioButtonBuilder, RadioButtonMessage},
+        stack_panel::StackPanelBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        UiNode, UserInterface,
+    };
+
+    fn is_checked(ui: &UserInterface, radio_button: Handle<UiNode>) -> bool {
+        ui.node(radio_button).cast::<RadioButton>().unwrap().checked
+    }
+
+    fn click(ui: &mut UserInterface, radio_button: Handle<UiNode>) -> usize {
+        ui.send_message(WidgetMessage::mouse_down(
+            radio_button,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+        ui.send_message(WidgetMessage::mouse_up(
+            radio_button,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+
+        // Count changes of the state of the clicked radio button.
+        let mut changes = 0;
+        while let Some(message) = ui.poll_message() {
+            if message.destination() == radio_button
+                && message.direction() == MessageDirection::FromWidget
+                && message.data::<RadioButtonMessage>().is_some()
+            {
+                changes += 1;
+            }
+        }
+        changes
+    }
+
+    #[test]
+    fn siblings_group() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let a = RadioButtonBuilder::new(WidgetBuilder::new())
+            .checked(true)
+            .build(&mut ui.build_ctx());
+        let b = RadioButtonBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        StackPanelBuilder::new(WidgetBuilder::new().with_child(a).with_child(b))
+            .build(&mut ui.build_ctx());
+        let other = RadioButtonBuilder::new(WidgetBuilder::new())
+            .checked(true)
+            .build(&mut ui.build_ctx());
+
+        assert_eq!(click(&mut ui, b), 1);
+        assert!(!is_checked(&ui, a));
+        assert!(is_checked(&ui, b));
+        // Not a sibling, so not in the group.
+        assert!(is_checked(&ui, other));
+
+        // Clicking checked radio button does nothing.
+        assert_eq!(click(&mut ui, b), 0);
+        assert!(is_checked(&ui, b));
+    }
+
+    #[test]
+    fn named_group() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        let a = RadioButtonBuilder::new(WidgetBuilder::new())
+            .with_group("Quality")
+            .checked(true)
+            .build(&mut ui.build_ctx());
+        StackPanelBuilder::new(WidgetBuilder::new().with_child(a)).build(&mut ui.build_ctx());
+        let b = RadioButtonBuilder::new(WidgetBuilder::new())
+            .with_group("Quality")
+            .build(&mut ui.build_ctx());
+        let c = RadioButtonBuilder::new(WidgetBuilder::new())
+            .with_group("Mode")
+            .checked(true)
+            .build(&mut ui.build_ctx());
+
+        ui.send_message(RadioButtonMessage::checked(
+            b,
+            MessageDirection::ToWidget,
+            true,
+        ));
+        while ui.poll_message().is_some() {}
+
+        assert!(!is_checked(&ui, a));
+        assert!(is_checked(&ui, b));
+        assert!(is_checked(&ui, c));
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     decorator::DecoratorBuilder,
     define_constructor,
     grid::{Column, GridBuilder, Row},
-    message::{MessageDirection, UiMessage},
+    message::{KeyCode, MessageDirection, UiMessage},
     text::{TextBuilder, TextMessage},
     utils::{make_arrow, ArrowDirection},
     widget::{Widget, WidgetBuilder, WidgetMessage},
@@ -45,6 +45,12 @@ pub struct ScrollBar {
     pub max: f32,
     pub value: f32,
     pub step: f32,
+    /// Larger step that is used when user clicks on the track or scrolls the mouse wheel over
+    /// the scroll bar.
+    pub page_step: f32,
+    /// If set, every new value is rounded to the nearest multiple of `step` (counting from
+    /// `min`).
+    pub snap_to_step: bool,
     pub orientation: Orientation,
     pub is_dragging: bool,
     pub offset: Vector2<f32>,
@@ -68,8 +74,12 @@ impl Control for ScrollBar {
     }
 
     fn resolve(&mut self, node_map: &NodeHandleMapping) {
-        node_map.resolve(&mut self.increase);
-        node_map.resolve(&mut self.decrease);
+        if self.increase.is_some() {
+            node_map.resolve(&mut self.increase);
+        }
+        if self.decrease.is_some() {
+            node_map.resolve(&mut self.decrease);
+        }
         node_map.resolve(&mut self.indicator);
         if self.value_text.is_some() {
             node_map.resolve(&mut self.value_text);
@@ -83,9 +93,14 @@ impl Control for ScrollBar {
         // Adjust indicator position according to current value
         let percent = (self.value - self.min) / (self.max - self.min);
 
-        let field_size = ui.node(self.field).actual_size();
+        // Custom templates could lack some parts, the bar does nothing in this case.
+        let (field, indicator) =
+            match (ui.try_get_node(self.field), ui.try_get_node(self.indicator)) {
+                (Some(field), Some(indicator)) => (field, indicator),
+                _ => return size,
+            };
 
-        let indicator = ui.node(self.indicator);
+        let field_size = field.actual_size();
         match self.orientation {
             Orientation::Horizontal => {
                 ui.send_message(WidgetMessage::height(
@@ -93,16 +108,15 @@ impl Control for ScrollBar {
                     MessageDirection::ToWidget,
                     field_size.y,
                 ));
-                ui.send_message(WidgetMessage::width(
-                    self.decrease,
-                    MessageDirection::ToWidget,
-                    field_size.y,
-                ));
-                ui.send_message(WidgetMessage::width(
-                    self.increase,
-                    MessageDirection::ToWidget,
-                    field_size.y,
-                ));
+                for button in [self.decrease, self.increase] {
+                    if button.is_some() {
+                        ui.send_message(WidgetMessage::width(
+                            button,
+                            MessageDirection::ToWidget,
+                            field_size.y,
+                        ));
+                    }
+                }
 
                 let position = Vector2::new(
                     percent * (field_size.x - indicator.actual_size().x).max(0.0),
@@ -120,16 +134,15 @@ impl Control for ScrollBar {
                     MessageDirection::ToWidget,
                     field_size.x,
                 ));
-                ui.send_message(WidgetMessage::height(
-                    self.decrease,
-                    MessageDirection::ToWidget,
-                    field_size.x,
-                ));
-                ui.send_message(WidgetMessage::height(
-                    self.increase,
-                    MessageDirection::ToWidget,
-                    field_size.x,
-                ));
+                for button in [self.decrease, self.increase] {
+                    if button.is_some() {
+                        ui.send_message(WidgetMessage::height(
+                            button,
+                            MessageDirection::ToWidget,
+                            field_size.x,
+                        ));
+                    }
+                }
 
                 let position = Vector2::new(
                     0.0,
@@ -170,7 +183,7 @@ impl Control for ScrollBar {
                 match *msg {
                     ScrollBarMessage::Value(value) => {
                         let old_value = self.value;
-                        let new_value = math::clampf(value, self.min, self.max);
+                        let new_value = math::clampf(self.snap(value), self.min, self.max);
                         if (new_value - old_value).abs() > f32::EPSILON {
                             self.value = new_value;
                             self.invalidate_arrange();
@@ -179,7 +192,7 @@ impl Control for ScrollBar {
                                 ui.send_message(TextMessage::text(
                                     self.value_text,
                                     MessageDirection::ToWidget,
-                                    format!("{:.1$}", value, self.value_precision),
+                                    format!("{:.1$}", self.value, self.value_precision),
                                 ));
                             }
 
@@ -245,6 +258,68 @@ impl Control for ScrollBar {
                 }
             }
         } else if let Some(msg) = message.data::<WidgetMessage>() {
+            match *msg {
+                WidgetMessage::MouseDown { pos, .. } if !message.handled() => {
+                    // Click on the track outside of the indicator moves the indicator towards
+                    // the cursor by a page.
+                    if let (Some(field), Some(indicator)) =
+                        (ui.try_get_node(self.field), ui.try_get_node(self.indicator))
+                    {
+                        let indicator_bounds = indicator.screen_bounds();
+                        if field.screen_bounds().contains(pos) && !indicator_bounds.contains(pos) {
+                            let before = match self.orientation {
+                                Orientation::Horizontal => pos.x < indicator_bounds.x(),
+                                Orientation::Vertical => pos.y < indicator_bounds.y(),
+                            };
+                            ui.send_message(ScrollBarMessage::value(
+                                self.handle(),
+                                MessageDirection::ToWidget,
+                                if before {
+                                    self.value - self.page_step
+                                } else {
+                                    self.value + self.page_step
+                                },
+                            ));
+                            message.set_handled(true);
+                        }
+                    }
+                }
+                WidgetMessage::KeyDown(key) if !message.handled() => {
+                    // Keys are received from the focused part (usually the indicator) of the bar.
+                    let delta = match key {
+                        KeyCode::Left | KeyCode::Up => Some(-self.step),
+                        KeyCode::Right | KeyCode::Down => Some(self.step),
+                        _ => None,
+                    };
+                    if let Some(delta) = delta {
+                        ui.send_message(ScrollBarMessage::value(
+                            self.handle(),
+                            MessageDirection::ToWidget,
+                            self.value + delta,
+                        ));
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::MouseWheel { delta, .. } if !message.handled() => {
+                    let new_value = math::clampf(
+                        self.snap(self.value - delta.lines().y * self.page_step),
+                        self.min,
+                        self.max,
+                    );
+                    // Same as in scroll viewer, the message is handled only if the value has
+                    // changed, so the wheel could scroll outer widgets when the limit is reached.
+                    if (new_value - self.value).abs() > f32::EPSILON {
+                        ui.send_message(ScrollBarMessage::value(
+                            self.handle(),
+                            MessageDirection::ToWidget,
+                            new_value,
+                        ));
+                        message.set_handled(true);
+                    }
+                }
+                _ => (),
+            }
+
             if message.destination() == self.indicator {
                 match msg {
                     WidgetMessage::MouseDown { pos, .. } => {
@@ -262,9 +337,8 @@ impl Control for ScrollBar {
                         message.set_handled(true);
                     }
                     WidgetMessage::MouseMove { pos: mouse_pos, .. } => {
-                        if self.indicator.is_some() {
-                            let canvas =
-                                ui.borrow_by_name_up(self.indicator, ScrollBar::PART_CANVAS);
+                        // Use the handle, not the name of the canvas, the name could be changed.
+                        if let Some(canvas) = ui.try_get_node(self.field) {
                             let indicator_size = ui.nodes.borrow(self.indicator).actual_size();
                             if self.is_dragging {
                                 let percent = match self.orientation {
@@ -305,8 +379,39 @@ impl Control for ScrollBar {
     }
 }
 
+fn missing_parts(
+    ui: &UserInterface,
+    field: Handle<UiNode>,
+    indicator: Handle<UiNode>,
+) -> Vec<&'static str> {
+    [
+        (field, ScrollBar::PART_CANVAS),
+        (indicator, ScrollBar::PART_INDICATOR),
+    ]
+    .iter()
+    .filter(|(part, _)| ui.try_get_node(*part).is_none())
+    .map(|(_, name)| *name)
+    .collect()
+}
+
 impl ScrollBar {
+    /// Name of a canvas that hosts the indicator. The indicator is moved inside of it.
     pub const PART_CANVAS: &'static str = "PART_Canvas";
+    /// Name of a node that can be dragged to change the value. It must be a child of the canvas.
+    pub const PART_INDICATOR: &'static str = "PART_Indicator";
+    /// Name of an optional button that increases the value by the step.
+    pub const PART_INCREASE: &'static str = "PART_Increase";
+    /// Name of an optional button that decreases the value by the step.
+    pub const PART_DECREASE: &'static str = "PART_Decrease";
+    /// Name of an optional text that shows current value.
+    pub const PART_VALUE_TEXT: &'static str = "PART_ValueText";
+
+    /// Returns names of required parts ([`Self::PART_CANVAS`] and [`Self::PART_INDICATOR`]) that
+    /// are missing. The scroll bar still works without them, but it cannot show or change its
+    /// value by dragging, so this could be used to validate custom parts and templates.
+    pub fn missing_parts(&self, ui: &UserInterface) -> Vec<&'static str> {
+        missing_parts(ui, self.field, self.indicator)
+    }
 
     pub fn new(
         widget: Widget,
@@ -322,6 +427,8 @@ impl ScrollBar {
             max: 100.0,
             value: 0.0,
             step: 1.0,
+            page_step: 10.0,
+            snap_to_step: false,
             orientation: Orientation::Vertical,
             is_dragging: false,
             offset: Default::default(),
@@ -354,6 +461,51 @@ impl ScrollBar {
     pub fn step(&self) -> f32 {
         self.step
     }
+
+    pub fn set_page_step(&mut self, page_step: f32) -> &mut Self {
+        self.page_step = page_step;
+        self
+    }
+
+    pub fn page_step(&self) -> f32 {
+        self.page_step
+    }
+
+    pub fn set_snap_to_step(&mut self, snap_to_step: bool) -> &mut Self {
+        self.snap_to_step = snap_to_step;
+        self
+    }
+
+    pub fn is_snap_to_step(&self) -> bool {
+        self.snap_to_step
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        if self.snap_to_step && self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        }
+    }
+}
+
+/// A factory that builds whole visual tree of a scroll bar, see
+/// [`ScrollBarBuilder::with_template`].
+pub type ScrollBarTemplate = Box<dyn FnOnce(&mut BuildContext, Orientation) -> Handle<UiNode>>;
+
+struct ScrollBarParts {
+    body: Handle<UiNode>,
+    increase: Handle<UiNode>,
+    decrease: Handle<UiNode>,
+    indicator: Handle<UiNode>,
+    field: Handle<UiNode>,
+    value_text: Handle<UiNode>,
+}
+
+// Custom parts could be invalid, the bar works without them. Use `ScrollBar::missing_parts` to
+// find out why a bar does not respond.
+fn is_valid_part(ctx: &BuildContext, part: Handle<UiNode>) -> bool {
+    ctx.ui.try_get_node(part).is_some()
 }
 
 pub struct ScrollBarBuilder {
@@ -362,13 +514,17 @@ pub struct ScrollBarBuilder {
     max: Option<f32>,
     value: Option<f32>,
     step: Option<f32>,
+    page_step: Option<f32>,
+    snap_to_step: bool,
     orientation: Option<Orientation>,
     increase: Option<Handle<UiNode>>,
     decrease: Option<Handle<UiNode>>,
     indicator: Option<Handle<UiNode>>,
     body: Option<Handle<UiNode>>,
     show_value: bool,
+    show_buttons: bool,
     value_precision: usize,
+    template: Option<ScrollBarTemplate>,
 }
 
 impl ScrollBarBuilder {
@@ -379,13 +535,17 @@ impl ScrollBarBuilder {
             max: None,
             value: None,
             step: None,
+            page_step: None,
+            snap_to_step: false,
             orientation: None,
             increase: None,
             decrease: None,
             indicator: None,
             body: None,
             show_value: false,
+            show_buttons: true,
             value_precision: 3,
+            template: None,
         }
     }
 
@@ -414,6 +574,20 @@ impl ScrollBarBuilder {
         self
     }
 
+    /// Sets a step that is used when user clicks on the track or scrolls the mouse wheel. By
+    /// default it is ten times larger than the step.
+    pub fn with_page_step(mut self, page_step: f32) -> Self {
+        self.page_step = Some(page_step);
+        self
+    }
+
+    /// Enables rounding of every value to the nearest multiple of the step, so dragging the
+    /// indicator moves it in discrete steps.
+    pub fn with_snap_to_step(mut self, snap_to_step: bool) -> Self {
+        self.snap_to_step = snap_to_step;
+        self
+    }
+
     pub fn with_increase(mut self, increase: Handle<UiNode>) -> Self {
         self.increase = Some(increase);
         self
@@ -439,72 +613,108 @@ impl ScrollBarBuilder {
         self
     }
 
+    /// Sets whether the arrow buttons should be created. Without the buttons the indicator uses
+    /// full length of the bar, which is useful for slider-like bars. Do not set custom buttons by
+    /// [`Self::with_increase`] and [`Self::with_decrease`] if the buttons are hidden, they won't
+    /// be used.
+    pub fn with_show_buttons(mut self, state: bool) -> Self {
+        self.show_buttons = state;
+        self
+    }
+
+    /// Replaces the default visual tree of the scroll bar with the one built by the template.
+    /// The scroll bar finds its parts in the tree by names: [`ScrollBar::PART_CANVAS`] and
+    /// [`ScrollBar::PART_INDICATOR`] are required (debug builds panic if the template does not
+    /// have them), the others are optional. Custom parts, buttons visibility and
+    /// [`Self::show_value`] are ignored when the template is set.
+    pub fn with_template(mut self, template: ScrollBarTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
     pub fn with_value_precision(mut self, precision: usize) -> Self {
         self.value_precision = precision;
         self
     }
 
-    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
-        let orientation = self.orientation.unwrap_or(Orientation::Horizontal);
-
-        let increase = self.increase.unwrap_or_else(|| {
-            ButtonBuilder::new(WidgetBuilder::new())
-                .with_content(match orientation {
-                    Orientation::Horizontal => make_arrow(ctx, ArrowDirection::Right, 8.0),
-                    Orientation::Vertical => make_arrow(ctx, ArrowDirection::Bottom, 8.0),
-                })
-                .build(ctx)
-        });
+    fn build_default_parts(
+        &self,
+        ctx: &mut BuildContext,
+        orientation: Orientation,
+        value: f32,
+    ) -> ScrollBarParts {
+        let (increase, decrease) = if self.show_buttons {
+            let increase = self.increase.unwrap_or_else(|| {
+                ButtonBuilder::new(WidgetBuilder::new().with_name(ScrollBar::PART_INCREASE))
+                    .with_content(match orientation {
+                        Orientation::Horizontal => make_arrow(ctx, ArrowDirection::Right, 8.0),
+                        Orientation::Vertical => make_arrow(ctx, ArrowDirection::Bottom, 8.0),
+                    })
+                    .build(ctx)
+            });
 
-        match orientation {
-            Orientation::Vertical => {
-                ctx[increase].set_height(30.0).set_row(2).set_column(0);
-            }
-            Orientation::Horizontal => {
-                ctx[increase].set_width(30.0).set_row(0).set_column(2);
+            if is_valid_part(ctx, increase) {
+                match orientation {
+                    Orientation::Vertical => {
+                        ctx[increase].set_height(30.0).set_row(2).set_column(0);
+                    }
+                    Orientation::Horizontal => {
+                        ctx[increase].set_width(30.0).set_row(0).set_column(2);
+                    }
+                }
             }
-        }
 
-        let decrease = self.decrease.unwrap_or_else(|| {
-            ButtonBuilder::new(WidgetBuilder::new())
-                .with_content(match orientation {
-                    Orientation::Horizontal => make_arrow(ctx, ArrowDirection::Left, 8.0),
-                    Orientation::Vertical => make_arrow(ctx, ArrowDirection::Top, 8.0),
-                })
-                .build(ctx)
-        });
+            let decrease = self.decrease.unwrap_or_else(|| {
+                ButtonBuilder::new(WidgetBuilder::new().with_name(ScrollBar::PART_DECREASE))
+                    .with_content(match orientation {
+                        Orientation::Horizontal => make_arrow(ctx, ArrowDirection::Left, 8.0),
+                        Orientation::Vertical => make_arrow(ctx, ArrowDirection::Top, 8.0),
+                    })
+                    .build(ctx)
+            });
 
-        ctx[decrease].set_row(0).set_column(0);
+            if is_valid_part(ctx, decrease) {
+                ctx[decrease].set_row(0).set_column(0);
 
-        match orientation {
-            Orientation::Vertical => ctx[decrease].set_height(30.0),
-            Orientation::Horizontal => ctx[decrease].set_width(30.0),
+                match orientation {
+                    Orientation::Vertical => ctx[decrease].set_height(30.0),
+                    Orientation::Horizontal => ctx[decrease].set_width(30.0),
+                };
+            }
+
+            (increase, decrease)
+        } else {
+            (Handle::NONE, Handle::NONE)
         };
 
         let indicator = self.indicator.unwrap_or_else(|| {
             DecoratorBuilder::new(
-                BorderBuilder::new(WidgetBuilder::new().with_foreground(Brush::LinearGradient {
-                    from: Vector2::new(0.5, 0.0),
-                    to: Vector2::new(0.5, 1.0),
-                    stops: vec![
-                        GradientPoint {
-                            stop: 0.0,
-                            color: COLOR_DARKEST,
-                        },
-                        GradientPoint {
-                            stop: 0.25,
-                            color: COLOR_LIGHTEST,
-                        },
-                        GradientPoint {
-                            stop: 0.75,
-                            color: COLOR_LIGHTEST,
-                        },
-                        GradientPoint {
-                            stop: 1.0,
-                            color: COLOR_DARKEST,
-                        },
-                    ],
-                }))
+                BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_name(ScrollBar::PART_INDICATOR)
+                        .with_foreground(Brush::LinearGradient {
+                            from: Vector2::new(0.5, 0.0),
+                            to: Vector2::new(0.5, 1.0),
+                            stops: vec![
+                                GradientPoint {
+                                    stop: 0.0,
+                                    color: COLOR_DARKEST,
+                                },
+                                GradientPoint {
+                                    stop: 0.25,
+                                    color: COLOR_LIGHTEST,
+                                },
+                                GradientPoint {
+                                    stop: 0.75,
+                                    color: COLOR_LIGHTEST,
+                                },
+                                GradientPoint {
+                                    stop: 1.0,
+                                    color: COLOR_DARKEST,
+                                },
+                            ],
+                        }),
+                )
                 .with_stroke_thickness(Thickness::uniform(1.0)),
             )
             .with_normal_brush(BRUSH_LIGHT)
@@ -513,26 +723,26 @@ impl ScrollBarBuilder {
             .build(ctx)
         });
 
-        match orientation {
-            Orientation::Vertical => {
-                ctx[indicator]
-                    .set_min_size(Vector2::new(0.0, 30.0))
-                    .set_width(30.0);
-            }
-            Orientation::Horizontal => {
-                ctx[indicator]
-                    .set_min_size(Vector2::new(30.0, 0.0))
-                    .set_height(30.0);
+        if is_valid_part(ctx, indicator) {
+            match orientation {
+                Orientation::Vertical => {
+                    ctx[indicator]
+                        .set_min_size(Vector2::new(0.0, 30.0))
+                        .set_width(30.0);
+                }
+                Orientation::Horizontal => {
+                    ctx[indicator]
+                        .set_min_size(Vector2::new(30.0, 0.0))
+                        .set_height(30.0);
+                }
             }
         }
 
-        let min = self.min.unwrap_or(0.0);
-        let max = self.max.unwrap_or(100.0);
-        let value = math::clampf(self.value.unwrap_or(0.0), min, max);
-
-        let value_text = if self.show_value {
+        // Value is shown on the indicator, so there is nowhere to show it without one.
+        let value_text = if self.show_value && ctx.ui.try_get_node(indicator).is_some() {
             let value_text = TextBuilder::new(
                 WidgetBuilder::new()
+                    .with_name(ScrollBar::PART_VALUE_TEXT)
                     .with_visibility(self.show_value)
                     .with_horizontal_alignment(HorizontalAlignment::Center)
                     .with_vertical_alignment(VerticalAlignment::Center)
@@ -561,12 +771,12 @@ impl ScrollBarBuilder {
             WidgetBuilder::new()
                 .with_name(ScrollBar::PART_CANVAS)
                 .on_column(match orientation {
-                    Orientation::Horizontal => 1,
-                    Orientation::Vertical => 0,
+                    Orientation::Horizontal if self.show_buttons => 1,
+                    _ => 0,
                 })
                 .on_row(match orientation {
-                    Orientation::Horizontal => 0,
-                    Orientation::Vertical => 1,
+                    Orientation::Vertical if self.show_buttons => 1,
+                    _ => 0,
                 })
                 .with_child(indicator),
         )
@@ -579,12 +789,16 @@ impl ScrollBarBuilder {
                 .with_child(increase),
         )
         .add_rows(match orientation {
-            Orientation::Horizontal => vec![Row::stretch()],
-            Orientation::Vertical => vec![Row::auto(), Row::stretch(), Row::auto()],
+            Orientation::Vertical if self.show_buttons => {
+                vec![Row::auto(), Row::stretch(), Row::auto()]
+            }
+            _ => vec![Row::stretch()],
         })
         .add_columns(match orientation {
-            Orientation::Horizontal => vec![Column::auto(), Column::stretch(), Column::auto()],
-            Orientation::Vertical => vec![Column::stretch()],
+            Orientation::Horizontal if self.show_buttons => {
+                vec![Column::auto(), Column::stretch(), Column::auto()]
+            }
+            _ => vec![Column::stretch()],
         })
         .build(ctx);
 
@@ -597,22 +811,253 @@ impl ScrollBarBuilder {
         });
         ctx.link(grid, body);
 
+        ScrollBarParts {
+            body,
+            increase,
+            decrease,
+            indicator,
+            field,
+            value_text,
+        }
+    }
+
+    pub fn build(mut self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let orientation = self.orientation.unwrap_or(Orientation::Horizontal);
+
+        let min = self.min.unwrap_or(0.0);
+        let max = self.max.unwrap_or(100.0);
+        let value = math::clampf(self.value.unwrap_or(0.0), min, max);
+
+        let parts = match self.template.take() {
+            Some(template) => {
+                let body = template(ctx, orientation);
+                let find = |name| ctx.ui.find_by_name_down(body, name);
+                let parts = ScrollBarParts {
+                    body,
+                    increase: find(ScrollBar::PART_INCREASE),
+                    decrease: find(ScrollBar::PART_DECREASE),
+                    indicator: find(ScrollBar::PART_INDICATOR),
+                    field: find(ScrollBar::PART_CANVAS),
+                    value_text: find(ScrollBar::PART_VALUE_TEXT),
+                };
+                let missing = missing_parts(ctx.ui, parts.field, parts.indicator);
+                debug_assert!(
+                    missing.is_empty(),
+                    "ScrollBar template does not have required parts: {:?}",
+                    missing
+                );
+                parts
+            }
+            None => self.build_default_parts(ctx, orientation, value),
+        };
+
+        let step = self.step.unwrap_or(1.0);
+
         let node = UiNode::new(ScrollBar {
-            widget: self.widget_builder.with_child(body).build(),
+            widget: self.widget_builder.with_child(parts.body).build(),
             min,
             max,
             value,
-            step: self.step.unwrap_or(1.0),
+            step,
+            page_step: self.page_step.unwrap_or(step * 10.0),
+            snap_to_step: self.snap_to_step,
             orientation,
             is_dragging: false,
             offset: Vector2::default(),
-            increase,
-            decrease,
-            indicator,
-            field,
-            value_text,
+            increase: parts.increase,
+            decrease: parts.decrease,
+            indicator: parts.indicator,
+            field: parts.field,
+            value_text: parts.value_text,
             value_precision: self.value_precision,
         });
         ctx.add_node(node)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        canvas::CanvasBuilder,
+        core::{algebra::Vector2, pool::Handle},
+        message::{MessageDirection, MouseButton, WheelDelta},
+        scroll_bar::{ScrollBar, ScrollBarBuilder, ScrollBarMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        BuildContext, Orientation, UserInterface,
+    };
+
+    #[test]
+    fn page_step_and_snapping() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar =
+            ScrollBarBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(30.0))
+                .with_orientation(Orientation::Horizontal)
+                .with_step(5.0)
+                .with_snap_to_step(true)
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        let value = |ui: &UserInterface| ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value();
+
+        ui.send_message(ScrollBarMessage::value(
+            scroll_bar,
+            MessageDirection::ToWidget,
+            12.0,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(value(&ui), 10.0);
+
+        // Click on the track right of the indicator pages forward.
+        ui.send_message(WidgetMessage::mouse_down(
+            scroll_bar,
+            MessageDirection::FromWidget,
+            Vector2::new(200.0, 15.0),
+            MouseButton::Left,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(value(&ui), 60.0);
+
+        ui.send_message(WidgetMessage::mouse_wheel(
+            scroll_bar,
+            MessageDirection::FromWidget,
+            Vector2::new(200.0, 15.0),
+            1.0,
+            WheelDelta::Lines(Vector2::new(0.0, 1.0)),
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(value(&ui), 10.0);
+    }
+
+    #[test]
+    fn without_buttons() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar =
+            ScrollBarBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(30.0))
+                .with_orientation(Orientation::Horizontal)
+                .with_show_buttons(false)
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let scroll_bar_ref = ui.node(scroll_bar).cast::<ScrollBar>().unwrap();
+        assert!(scroll_bar_ref.increase.is_none());
+        assert!(scroll_bar_ref.decrease.is_none());
+        // Whole length of the bar except the border is occupied by the track.
+        assert_eq!(ui.node(scroll_bar_ref.field).actual_size().x, 298.0);
+    }
+
+    #[test]
+    fn template() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new().with_width(300.0))
+            .with_template(Box::new(|ctx: &mut BuildContext, _: Orientation| {
+                let indicator =
+                    BorderBuilder::new(WidgetBuilder::new().with_name(ScrollBar::PART_INDICATOR))
+                        .build(ctx);
+                CanvasBuilder::new(
+                    WidgetBuilder::new()
+                        .with_name(ScrollBar::PART_CANVAS)
+                        .with_child(indicator),
+                )
+                .build(ctx)
+            }))
+            .build(&mut ui.build_ctx());
+
+        let scroll_bar_ref = ui.node(scroll_bar).cast::<ScrollBar>().unwrap();
+        assert_eq!(
+            scroll_bar_ref.field,
+            ui.find_by_name_down(scroll_bar, ScrollBar::PART_CANVAS)
+        );
+        assert_eq!(
+            scroll_bar_ref.indicator,
+            ui.find_by_name_down(scroll_bar, ScrollBar::PART_INDICATOR)
+        );
+        assert!(scroll_bar_ref.increase.is_none());
+        assert!(scroll_bar_ref.value_text.is_none());
+        assert!(scroll_bar_ref.missing_parts(&ui).is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "PART_Indicator")]
+    fn template_without_indicator() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+        ScrollBarBuilder::new(WidgetBuilder::new())
+            .with_template(Box::new(|ctx: &mut BuildContext, _: Orientation| {
+                CanvasBuilder::new(WidgetBuilder::new().with_name(ScrollBar::PART_CANVAS))
+                    .build(ctx)
+            }))
+            .build(&mut ui.build_ctx());
+    }
+
+    #[test]
+    fn missing_indicator() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new())
+            .with_indicator(Handle::NONE)
+            .show_value(true)
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        assert_eq!(
+            ui.node(scroll_bar)
+                .cast::<ScrollBar>()
+                .unwrap()
+                .missing_parts(&ui),
+            vec![ScrollBar::PART_INDICATOR]
+        );
+
+        // The bar must still respond to messages, even if it cannot show anything.
+        ui.send_message(ScrollBarMessage::value(
+            scroll_bar,
+            MessageDirection::ToWidget,
+            50.0,
+        ));
+        ui.send_message(WidgetMessage::mouse_down(
+            scroll_bar,
+            MessageDirection::FromWidget,
+            Vector2::new(50.0, 15.0),
+            MouseButton::Left,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        assert_eq!(
+            ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value(),
+            50.0
+        );
+    }
+
+    #[test]
+    fn drag_after_renaming_parts() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let scroll_bar =
+            ScrollBarBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(30.0))
+                .with_orientation(Orientation::Horizontal)
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let scroll_bar_ref = ui.node(scroll_bar).cast::<ScrollBar>().unwrap();
+        let (field, indicator) = (scroll_bar_ref.field, scroll_bar_ref.indicator);
+        ui.set_node_name(field, "Track");
+
+        let pos = ui.node(indicator).screen_position;
+        ui.send_message(WidgetMessage::mouse_down(
+            indicator,
+            MessageDirection::FromWidget,
+            pos,
+            MouseButton::Left,
+        ));
+        ui.send_message(WidgetMessage::mouse_move(
+            indicator,
+            MessageDirection::FromWidget,
+            pos + Vector2::new(100.0, 0.0),
+            Default::default(),
+        ));
+        while ui.poll_message().is_some() {}
+        let value = ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value();
+        assert!(value > 0.0);
+    }
+}
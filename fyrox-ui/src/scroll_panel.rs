@@ -84,16 +84,12 @@ impl Control for ScrollPanel {
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
-        let mut children_size = Vector2::<f32>::default();
-        for child_handle in self.widget.children() {
-            let desired_size = ui.node(*child_handle).desired_size();
-            children_size.x = children_size.x.max(desired_size.x);
-            children_size.y = children_size.y.max(desired_size.y);
-        }
+        let children_size = self.children_size(ui);
+        let scroll = self.clamp_scroll(children_size, final_size);
 
         let child_rect = Rect::new(
-            -self.scroll.x,
-            -self.scroll.y,
+            -scroll.x,
+            -scroll.y,
             if self.horizontal_scroll_allowed {
                 children_size.x.max(final_size.x)
             } else {
@@ -157,7 +153,8 @@ impl Control for ScrollPanel {
                                 || (relative_position.x + size.x) > self.actual_size().x
                                 || (relative_position.y + size.y) > self.actual_size().y
                             {
-                                relative_position += self.scroll;
+                                relative_position +=
+                                    self.clamp_scroll(self.children_size(ui), self.actual_size());
                                 // This check is needed because it possible that given handle is not in
                                 // sub-tree of current scroll panel.
                                 if parent == self.handle {
@@ -194,6 +191,33 @@ impl ScrollPanel {
             horizontal_scroll_allowed: false,
         }
     }
+
+    fn children_size(&self, ui: &UserInterface) -> Vector2<f32> {
+        let mut children_size = Vector2::<f32>::default();
+        for child_handle in self.widget.children() {
+            let desired_size = ui.node(*child_handle).desired_size();
+            children_size.x = children_size.x.max(desired_size.x);
+            children_size.y = children_size.y.max(desired_size.y);
+        }
+        children_size
+    }
+
+    /// Clamps scroll to content bounds, so content won't slide out of view if scroll value
+    /// is larger than content (for example when content has shrunk).
+    fn clamp_scroll(
+        &self,
+        children_size: Vector2<f32>,
+        viewport_size: Vector2<f32>,
+    ) -> Vector2<f32> {
+        let max_scroll = Vector2::new(
+            (children_size.x - viewport_size.x).max(0.0),
+            (children_size.y - viewport_size.y).max(0.0),
+        );
+        Vector2::new(
+            self.scroll.x.max(0.0).min(max_scroll.x),
+            self.scroll.y.max(0.0).min(max_scroll.y),
+        )
+    }
 }
 
 pub struct ScrollPanelBuilder {
@@ -230,3 +254,58 @@ impl ScrollPanelBuilder {
         }))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        core::algebra::Vector2,
+        message::MessageDirection,
+        scroll_panel::{ScrollPanelBuilder, ScrollPanelMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        UserInterface,
+    };
+
+    #[test]
+    fn scroll_is_clamped_to_content() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let content =
+            BorderBuilder::new(WidgetBuilder::new().with_height(300.0)).build(&mut ui.build_ctx());
+        let scroll_panel = ScrollPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_width(100.0)
+                .with_height(100.0)
+                .with_child(content),
+        )
+        .build(&mut ui.build_ctx());
+
+        let scroll = |ui: &mut UserInterface, value: f32| {
+            ui.send_message(ScrollPanelMessage::vertical_scroll(
+                scroll_panel,
+                MessageDirection::ToWidget,
+                value,
+            ));
+            while ui.poll_message().is_some() {}
+            ui.update(screen_size, 0.0);
+            ui.node(content).actual_local_position().y
+        };
+
+        assert_eq!(scroll(&mut ui, 50.0), -50.0);
+        // Past the end of the content.
+        assert_eq!(scroll(&mut ui, 1000.0), -200.0);
+        // Past the beginning of the content.
+        assert_eq!(scroll(&mut ui, -50.0), 0.0);
+
+        // Shrunk content stays in view.
+        scroll(&mut ui, 1000.0);
+        ui.send_message(WidgetMessage::height(
+            content,
+            MessageDirection::ToWidget,
+            150.0,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.node(content).actual_local_position().y, -50.0);
+    }
+}
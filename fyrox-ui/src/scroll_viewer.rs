@@ -2,7 +2,7 @@ use crate::{
     core::{algebra::Vector2, pool::Handle},
     define_constructor,
     grid::{Column, GridBuilder, Row},
-    message::{MessageDirection, UiMessage},
+    message::{MessageDirection, UiMessage, WheelDelta},
     scroll_bar::{ScrollBar, ScrollBarBuilder, ScrollBarMessage},
     scroll_panel::{ScrollPanelBuilder, ScrollPanelMessage},
     widget::{Widget, WidgetBuilder, WidgetMessage},
@@ -19,11 +19,43 @@ pub enum ScrollViewerMessage {
     /// Adjusts vertical and horizontal scroll values so given node will be in "view box"
     /// of scroll viewer.
     BringIntoView(Handle<UiNode>),
+    /// Switches scroll bars between floating over the content and taking space in layout.
+    OverlayScrollBars(bool),
 }
 
 impl ScrollViewerMessage {
     define_constructor!(ScrollViewerMessage:Content => fn content(Handle<UiNode>), layout: false);
     define_constructor!(ScrollViewerMessage:BringIntoView=> fn bring_into_view(Handle<UiNode>), layout: true);
+    define_constructor!(ScrollViewerMessage:OverlayScrollBars => fn overlay_scroll_bars(bool), layout: false);
+}
+
+/// Defines when a scroll bar of a scroll viewer should be visible.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollBarVisibility {
+    /// Scroll bar is visible only if content does not fit in the viewer.
+    Auto,
+    /// Scroll bar is always visible.
+    Visible,
+    /// Scroll bar is always hidden, content still can be scrolled by mouse wheel or by messages.
+    Hidden,
+}
+
+impl Default for ScrollBarVisibility {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+impl ScrollBarVisibility {
+    fn is_visible(self, scroll_bar: &ScrollBar) -> bool {
+        match self {
+            ScrollBarVisibility::Auto => {
+                (scroll_bar.max_value() - scroll_bar.min_value()).abs() >= f32::EPSILON
+            }
+            ScrollBarVisibility::Visible => true,
+            ScrollBarVisibility::Hidden => false,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -33,10 +65,41 @@ pub struct ScrollViewer {
     pub scroll_panel: Handle<UiNode>,
     pub v_scroll_bar: Handle<UiNode>,
     pub h_scroll_bar: Handle<UiNode>,
+    pub v_scroll_bar_visibility: ScrollBarVisibility,
+    pub h_scroll_bar_visibility: ScrollBarVisibility,
+    /// Amount of pixels to scroll per one line of mouse wheel.
+    pub line_height: f32,
+    /// Grid over the content that hosts scroll bars in overlay mode.
+    pub overlay_grid: Handle<UiNode>,
+    pub overlay_scroll_bars: bool,
 }
 
 crate::define_widget_deref!(ScrollViewer);
 
+// Changes value of a scroll bar by given amount, returns `true` if the value has actually changed.
+fn scroll_by(ui: &UserInterface, scroll_bar: Handle<UiNode>, amount: f32) -> bool {
+    if scroll_bar.is_none() || amount == 0.0 {
+        return false;
+    }
+
+    if let Some(scroll_bar_ref) = ui.node(scroll_bar).cast::<ScrollBar>() {
+        let old_value = scroll_bar_ref.value();
+        let new_value = (old_value - amount)
+            .max(scroll_bar_ref.min_value())
+            .min(scroll_bar_ref.max_value());
+        if (old_value - new_value).abs() > f32::EPSILON {
+            ui.send_message(ScrollBarMessage::value(
+                scroll_bar,
+                MessageDirection::ToWidget,
+                new_value,
+            ));
+            return true;
+        }
+    }
+
+    false
+}
+
 impl ScrollViewer {
     pub fn new(
         widget: Widget,
@@ -51,6 +114,39 @@ impl ScrollViewer {
             scroll_panel: content_presenter,
             v_scroll_bar,
             h_scroll_bar,
+            v_scroll_bar_visibility: Default::default(),
+            h_scroll_bar_visibility: Default::default(),
+            line_height: WheelDelta::LINE_HEIGHT,
+            overlay_grid: Handle::NONE,
+            overlay_scroll_bars: false,
+        }
+    }
+
+    pub fn set_line_height(&mut self, line_height: f32) {
+        self.line_height = line_height;
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    // Converts wheel delta to pixels using line height of the viewer. Vertical scroll is turned
+    // into horizontal when Shift is held and there is something to scroll horizontally.
+    fn wheel_amount(&self, ui: &UserInterface, delta: &WheelDelta) -> Vector2<f32> {
+        let amount = match *delta {
+            WheelDelta::Lines(lines) => lines.scale(self.line_height),
+            WheelDelta::Pixels(pixels) => pixels,
+        };
+        let can_scroll_horizontally = ui
+            .try_get_node(self.h_scroll_bar)
+            .and_then(|h_scroll_bar| h_scroll_bar.cast::<ScrollBar>())
+            .map_or(false, |h_scroll_bar| {
+                h_scroll_bar.max_value() > h_scroll_bar.min_value()
+            });
+        if ui.keyboard_modifiers().shift && amount.x == 0.0 && can_scroll_horizontally {
+            Vector2::new(amount.y, 0.0)
+        } else {
+            amount
         }
     }
 
@@ -81,6 +177,7 @@ impl Control for ScrollViewer {
         node_map.resolve(&mut self.scroll_panel);
         node_map.resolve(&mut self.v_scroll_bar);
         node_map.resolve(&mut self.h_scroll_bar);
+        node_map.resolve(&mut self.overlay_grid);
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
@@ -111,19 +208,16 @@ impl Control for ScrollViewer {
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
-        if let Some(WidgetMessage::MouseWheel { amount, .. }) = message.data::<WidgetMessage>() {
-            if self.v_scroll_bar.is_some() && !message.handled() {
-                if let Some(v_scroll_bar) = ui.node(self.v_scroll_bar).cast::<ScrollBar>() {
-                    let old_value = v_scroll_bar.value();
-                    let new_value = old_value - amount * 17.0;
-                    if (old_value - new_value).abs() > f32::EPSILON {
-                        message.set_handled(true);
-                    }
-                    ui.send_message(ScrollBarMessage::value(
-                        self.v_scroll_bar,
-                        MessageDirection::ToWidget,
-                        new_value,
-                    ));
+        if let Some(WidgetMessage::MouseWheel { delta, .. }) = message.data::<WidgetMessage>() {
+            // Scroll chaining: the message is marked as handled only if the scroll value has
+            // actually changed. When nested viewer reaches its limit, the message bubbles further
+            // and scrolls outer viewer, so inner viewer won't trap all wheel input.
+            if !message.handled() {
+                let amount = self.wheel_amount(ui, delta);
+                let h_scrolled = scroll_by(ui, self.h_scroll_bar, amount.x);
+                let v_scrolled = scroll_by(ui, self.v_scroll_bar, amount.y);
+                if h_scrolled || v_scrolled {
+                    message.set_handled(true);
                 }
             }
         } else if let Some(msg) = message.data::<ScrollPanelMessage>() {
@@ -174,9 +268,8 @@ impl Control for ScrollViewer {
                         {
                             if let Some(scroll_bar) = ui.node(self.v_scroll_bar).cast::<ScrollBar>()
                             {
-                                let visibility = (scroll_bar.max_value() - scroll_bar.min_value())
-                                    .abs()
-                                    >= f32::EPSILON;
+                                let visibility =
+                                    self.v_scroll_bar_visibility.is_visible(scroll_bar);
                                 ui.send_message(WidgetMessage::visibility(
                                     self.v_scroll_bar,
                                     MessageDirection::ToWidget,
@@ -188,9 +281,8 @@ impl Control for ScrollViewer {
                         {
                             if let Some(scroll_bar) = ui.node(self.h_scroll_bar).cast::<ScrollBar>()
                             {
-                                let visibility = (scroll_bar.max_value() - scroll_bar.min_value())
-                                    .abs()
-                                    >= f32::EPSILON;
+                                let visibility =
+                                    self.h_scroll_bar_visibility.is_visible(scroll_bar);
                                 ui.send_message(WidgetMessage::visibility(
                                     self.h_scroll_bar,
                                     MessageDirection::ToWidget,
@@ -226,6 +318,27 @@ impl Control for ScrollViewer {
                             handle,
                         ));
                     }
+                    &ScrollViewerMessage::OverlayScrollBars(overlay) => {
+                        if self.overlay_scroll_bars != overlay && self.overlay_grid.is_some() {
+                            self.overlay_scroll_bars = overlay;
+                            // Scroll bars occupy the same cells in both grids, so it is enough
+                            // to move them to another grid.
+                            let panel = if overlay {
+                                self.overlay_grid
+                            } else {
+                                ui.node(self.overlay_grid).parent()
+                            };
+                            for scroll_bar in [self.v_scroll_bar, self.h_scroll_bar] {
+                                if scroll_bar.is_some() {
+                                    ui.send_message(WidgetMessage::link(
+                                        scroll_bar,
+                                        MessageDirection::ToWidget,
+                                        panel,
+                                    ));
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -239,6 +352,10 @@ pub struct ScrollViewerBuilder {
     v_scroll_bar: Option<Handle<UiNode>>,
     horizontal_scroll_allowed: bool,
     vertical_scroll_allowed: bool,
+    h_scroll_bar_visibility: ScrollBarVisibility,
+    v_scroll_bar_visibility: ScrollBarVisibility,
+    overlay_scroll_bars: bool,
+    line_height: f32,
 }
 
 impl ScrollViewerBuilder {
@@ -250,6 +367,10 @@ impl ScrollViewerBuilder {
             v_scroll_bar: None,
             horizontal_scroll_allowed: false,
             vertical_scroll_allowed: true,
+            h_scroll_bar_visibility: Default::default(),
+            v_scroll_bar_visibility: Default::default(),
+            overlay_scroll_bars: false,
+            line_height: WheelDelta::LINE_HEIGHT,
         }
     }
 
@@ -278,6 +399,34 @@ impl ScrollViewerBuilder {
         self
     }
 
+    pub fn with_vertical_scroll_bar_visibility(mut self, visibility: ScrollBarVisibility) -> Self {
+        self.v_scroll_bar_visibility = visibility;
+        self
+    }
+
+    pub fn with_horizontal_scroll_bar_visibility(
+        mut self,
+        visibility: ScrollBarVisibility,
+    ) -> Self {
+        self.h_scroll_bar_visibility = visibility;
+        self
+    }
+
+    /// If set to `true`, scroll bars will float over the content instead of taking space in
+    /// layout. Scroll bars do not overlap each other in the corner. The mode could be changed
+    /// later by [`ScrollViewerMessage::OverlayScrollBars`].
+    pub fn with_overlay_scroll_bars(mut self, overlay: bool) -> Self {
+        self.overlay_scroll_bars = overlay;
+        self
+    }
+
+    /// Sets amount of pixels to scroll per one line of mouse wheel. Default is
+    /// [`WheelDelta::LINE_HEIGHT`].
+    pub fn with_line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
     pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
         let content_presenter = ScrollPanelBuilder::new(
             WidgetBuilder::new()
@@ -295,6 +444,10 @@ impl ScrollViewerBuilder {
                 .build(ctx)
         });
         ctx[v_scroll_bar].set_row(0).set_column(1);
+        if self.v_scroll_bar_visibility != ScrollBarVisibility::Auto {
+            ctx[v_scroll_bar]
+                .set_visibility(self.v_scroll_bar_visibility == ScrollBarVisibility::Visible);
+        }
 
         let h_scroll_bar = self.h_scroll_bar.unwrap_or_else(|| {
             ScrollBarBuilder::new(WidgetBuilder::new().with_height(22.0))
@@ -302,29 +455,221 @@ impl ScrollViewerBuilder {
                 .build(ctx)
         });
         ctx[h_scroll_bar].set_row(1).set_column(0);
+        if self.h_scroll_bar_visibility != ScrollBarVisibility::Auto {
+            ctx[h_scroll_bar]
+                .set_visibility(self.h_scroll_bar_visibility == ScrollBarVisibility::Visible);
+        }
+
+        // In overlay mode scroll bars are hosted by a grid with the same layout that covers the
+        // content, hidden scroll bars collapse their rows and columns, so the other bar takes the
+        // corner only if it is free.
+        let (overlay_grid, grid) = if self.overlay_scroll_bars {
+            let overlay_grid = scroll_bars_grid(
+                WidgetBuilder::new()
+                    .with_child(h_scroll_bar)
+                    .with_child(v_scroll_bar),
+                ctx,
+            );
+            let grid = scroll_bars_grid(
+                WidgetBuilder::new()
+                    .with_child(content_presenter)
+                    .with_child(overlay_grid),
+                ctx,
+            );
+            (overlay_grid, grid)
+        } else {
+            let overlay_grid = scroll_bars_grid(WidgetBuilder::new(), ctx);
+            let grid = scroll_bars_grid(
+                WidgetBuilder::new()
+                    .with_child(content_presenter)
+                    .with_child(overlay_grid)
+                    .with_child(h_scroll_bar)
+                    .with_child(v_scroll_bar),
+                ctx,
+            );
+            (overlay_grid, grid)
+        };
 
         let sv = ScrollViewer {
-            widget: self
-                .widget_builder
-                .with_child(
-                    GridBuilder::new(
-                        WidgetBuilder::new()
-                            .with_child(content_presenter)
-                            .with_child(h_scroll_bar)
-                            .with_child(v_scroll_bar),
-                    )
-                    .add_row(Row::stretch())
-                    .add_row(Row::auto())
-                    .add_column(Column::stretch())
-                    .add_column(Column::auto())
-                    .build(ctx),
-                )
-                .build(),
+            widget: self.widget_builder.with_child(grid).build(),
             content: self.content,
             v_scroll_bar,
             h_scroll_bar,
             scroll_panel: content_presenter,
+            v_scroll_bar_visibility: self.v_scroll_bar_visibility,
+            h_scroll_bar_visibility: self.h_scroll_bar_visibility,
+            line_height: self.line_height,
+            overlay_grid,
+            overlay_scroll_bars: self.overlay_scroll_bars,
         };
         ctx.add_node(UiNode::new(sv))
     }
 }
+
+fn scroll_bars_grid(widget_builder: WidgetBuilder, ctx: &mut BuildContext) -> Handle<UiNode> {
+    GridBuilder::new(widget_builder)
+        .add_row(Row::stretch())
+        .add_row(Row::auto())
+        .add_column(Column::stretch())
+        .add_column(Column::auto())
+        .build(ctx)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        core::algebra::Vector2,
+        message::{KeyboardModifiers, MessageDirection, OsEvent, WheelDelta},
+        scroll_bar::ScrollBar,
+        scroll_viewer::{ScrollViewer, ScrollViewerBuilder, ScrollViewerMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        UserInterface,
+    };
+
+    #[test]
+    fn content_is_hosted_by_viewer() {
+        let mut ui = UserInterface::new(Vector2::new(1000.0, 1000.0));
+
+        let content = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let scroll_viewer = ScrollViewerBuilder::new(WidgetBuilder::new())
+            .with_content(content)
+            .build(&mut ui.build_ctx());
+
+        assert!(ui.is_node_child_of(content, scroll_viewer));
+    }
+
+    #[test]
+    fn mouse_wheel() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let content = BorderBuilder::new(WidgetBuilder::new().with_width(500.0).with_height(500.0))
+            .build(&mut ui.build_ctx());
+        let scroll_viewer =
+            ScrollViewerBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+                .with_content(content)
+                .with_horizontal_scroll_allowed(true)
+                .with_line_height(10.0)
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+
+        let values = |ui: &UserInterface| {
+            let viewer = ui.node(scroll_viewer).cast::<ScrollViewer>().unwrap();
+            let value = |scroll_bar| ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value();
+            Vector2::new(value(viewer.h_scroll_bar), value(viewer.v_scroll_bar))
+        };
+        let wheel = |ui: &mut UserInterface, lines: f32| {
+            ui.send_message(WidgetMessage::mouse_wheel(
+                content,
+                MessageDirection::FromWidget,
+                Vector2::new(50.0, 50.0),
+                lines,
+                WheelDelta::Lines(Vector2::new(0.0, lines)),
+            ));
+            while ui.poll_message().is_some() {}
+        };
+
+        wheel(&mut ui, -2.0);
+        assert_eq!(values(&ui), Vector2::new(0.0, 20.0));
+
+        // Scroll value is clamped at the limits.
+        wheel(&mut ui, 10.0);
+        assert_eq!(values(&ui), Vector2::new(0.0, 0.0));
+
+        // Shift turns vertical scroll into horizontal.
+        ui.process_os_event(&OsEvent::KeyboardModifiers(KeyboardModifiers {
+            shift: true,
+            ..Default::default()
+        }));
+        wheel(&mut ui, -3.0);
+        assert_eq!(values(&ui), Vector2::new(30.0, 0.0));
+    }
+
+    #[test]
+    fn overlay_scroll_bars() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let content = BorderBuilder::new(WidgetBuilder::new().with_width(500.0).with_height(500.0))
+            .build(&mut ui.build_ctx());
+        let scroll_viewer =
+            ScrollViewerBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+                .with_content(content)
+                .with_horizontal_scroll_allowed(true)
+                .with_overlay_scroll_bars(true)
+                .build(&mut ui.build_ctx());
+        let update = |ui: &mut UserInterface| {
+            for _ in 0..2 {
+                ui.update(screen_size, 0.0);
+                while ui.poll_message().is_some() {}
+            }
+        };
+        update(&mut ui);
+
+        let viewer = ui.node(scroll_viewer).cast::<ScrollViewer>().unwrap();
+        let (v_scroll_bar, h_scroll_bar, scroll_panel) = (
+            viewer.v_scroll_bar,
+            viewer.h_scroll_bar,
+            viewer.scroll_panel,
+        );
+        let bounds = |ui: &UserInterface, node| ui.node(node).screen_bounds();
+
+        // Content takes the whole viewer, bars do not overlap in the corner.
+        assert_eq!(
+            ui.node(scroll_panel).actual_size(),
+            Vector2::new(100.0, 100.0)
+        );
+        let v = bounds(&ui, v_scroll_bar);
+        let h = bounds(&ui, h_scroll_bar);
+        assert!(v.h() > 0.0 && h.w() > 0.0);
+        assert!(v.y() + v.h() <= h.y());
+        assert!(h.x() + h.w() <= v.x());
+
+        // Scroll bars take space in layout when the mode is switched off.
+        ui.send_message(ScrollViewerMessage::overlay_scroll_bars(
+            scroll_viewer,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        update(&mut ui);
+        let size = ui.node(scroll_panel).actual_size();
+        assert!(size.x < 100.0 && size.y < 100.0);
+    }
+
+    #[test]
+    fn shift_wheel_without_horizontal_range() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let content = BorderBuilder::new(WidgetBuilder::new().with_width(50.0).with_height(500.0))
+            .build(&mut ui.build_ctx());
+        let scroll_viewer =
+            ScrollViewerBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(100.0))
+                .with_content(content)
+                .with_line_height(10.0)
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+
+        // Shift+wheel falls back to vertical scroll, when there is nothing to scroll horizontally.
+        ui.process_os_event(&OsEvent::KeyboardModifiers(KeyboardModifiers {
+            shift: true,
+            ..Default::default()
+        }));
+        ui.send_message(WidgetMessage::mouse_wheel(
+            content,
+            MessageDirection::FromWidget,
+            Vector2::new(50.0, 50.0),
+            -2.0,
+            WheelDelta::Lines(Vector2::new(0.0, -2.0)),
+        ));
+        while ui.poll_message().is_some() {}
+
+        let viewer = ui.node(scroll_viewer).cast::<ScrollViewer>().unwrap();
+        let value = |scroll_bar| ui.node(scroll_bar).cast::<ScrollBar>().unwrap().value();
+        assert_eq!(value(viewer.h_scroll_bar), 0.0);
+        assert_eq!(value(viewer.v_scroll_bar), 20.0);
+    }
+}
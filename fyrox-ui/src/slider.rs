@@ -0,0 +1,224 @@
+//! Slider is a scroll bar that looks like a thin track with a round thumb, see [`SliderBuilder`]
+//! docs for more info.
+
+use crate::{
+    border::BorderBuilder,
+    brush::Brush,
+    canvas::CanvasBuilder,
+    core::{algebra::Vector2, color::Color, pool::Handle},
+    scroll_bar::{ScrollBar, ScrollBarBuilder},
+    vector_image::{Primitive, VectorImageBuilder},
+    widget::WidgetBuilder,
+    BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, VerticalAlignment,
+    BRUSH_LIGHT, BRUSH_LIGHTEST,
+};
+
+const THUMB_SIZE: f32 = 16.0;
+const TRACK_THICKNESS: f32 = 4.0;
+
+/// Builds a [`ScrollBar`] without arrow buttons, with a thin track and a round thumb. Since it
+/// is an ordinary scroll bar, it is controlled and reports its value changes by
+/// [`crate::scroll_bar::ScrollBarMessage`]. Clicking on the track moves the thumb towards the
+/// cursor by ten steps, arrow keys move it by a step when the slider has keyboard focus.
+pub struct SliderBuilder {
+    widget_builder: WidgetBuilder,
+    min: f32,
+    max: f32,
+    value: f32,
+    step: f32,
+    orientation: Orientation,
+}
+
+impl SliderBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            min: 0.0,
+            max: 100.0,
+            value: 0.0,
+            step: 1.0,
+            orientation: Orientation::Horizontal,
+        }
+    }
+
+    pub fn with_min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn with_max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        ScrollBarBuilder::new(self.widget_builder)
+            .with_min(self.min)
+            .with_max(self.max)
+            .with_value(self.value)
+            .with_step(self.step)
+            .with_orientation(self.orientation)
+            .with_template(Box::new(|ctx: &mut BuildContext, orientation| {
+                let track = BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_hit_test_visibility(false)
+                        .with_background(BRUSH_LIGHT)
+                        .with_margin(Thickness::uniform(THUMB_SIZE * 0.5))
+                        .with_width(match orientation {
+                            Orientation::Horizontal => f32::NAN,
+                            Orientation::Vertical => TRACK_THICKNESS,
+                        })
+                        .with_height(match orientation {
+                            Orientation::Horizontal => TRACK_THICKNESS,
+                            Orientation::Vertical => f32::NAN,
+                        })
+                        .with_horizontal_alignment(match orientation {
+                            Orientation::Horizontal => HorizontalAlignment::Stretch,
+                            Orientation::Vertical => HorizontalAlignment::Center,
+                        })
+                        .with_vertical_alignment(match orientation {
+                            Orientation::Horizontal => VerticalAlignment::Center,
+                            Orientation::Vertical => VerticalAlignment::Stretch,
+                        }),
+                )
+                .with_stroke_thickness(Thickness::zero())
+                .build(ctx);
+
+                // The scroll bar stretches the thumb across the track, so the circle is centered
+                // in a transparent (but still clickable) border.
+                let circle = VectorImageBuilder::new(
+                    WidgetBuilder::new()
+                        .with_hit_test_visibility(false)
+                        .with_foreground(BRUSH_LIGHTEST)
+                        .with_width(THUMB_SIZE)
+                        .with_height(THUMB_SIZE)
+                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                        .with_vertical_alignment(VerticalAlignment::Center),
+                )
+                .with_primitives(vec![Primitive::Circle {
+                    center: Vector2::new(THUMB_SIZE * 0.5, THUMB_SIZE * 0.5),
+                    radius: THUMB_SIZE * 0.5,
+                    segments: 16,
+                }])
+                .build(ctx);
+
+                let thumb = BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_name(ScrollBar::PART_INDICATOR)
+                        .with_focusable(true)
+                        .with_background(Brush::Solid(Color::TRANSPARENT))
+                        .with_min_size(Vector2::new(THUMB_SIZE, THUMB_SIZE))
+                        .with_child(circle),
+                )
+                .with_stroke_thickness(Thickness::zero())
+                .build(ctx);
+
+                let canvas = CanvasBuilder::new(
+                    WidgetBuilder::new()
+                        .with_name(ScrollBar::PART_CANVAS)
+                        .with_child(thumb),
+                )
+                .build(ctx);
+
+                // Transparent background makes the whole area of the slider clickable, the track
+                // itself is too thin to be hit.
+                BorderBuilder::new(
+                    WidgetBuilder::new()
+                        .with_background(Brush::Solid(Color::TRANSPARENT))
+                        .with_child(track)
+                        .with_child(canvas),
+                )
+                .with_stroke_thickness(Thickness::zero())
+                .build(ctx)
+            }))
+            .build(ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        message::{ButtonState, KeyCode, MessageDirection, MouseButton, OsEvent},
+        scroll_bar::ScrollBar,
+        slider::SliderBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        UserInterface,
+    };
+
+    #[test]
+    fn click_on_track() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let slider =
+            SliderBuilder::new(WidgetBuilder::new().with_width(200.0)).build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        // Picking uses draw commands.
+        ui.draw();
+
+        let height = ui.node(slider).actual_size().y;
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(180.0, height * 0.5),
+        });
+        ui.process_os_event(&OsEvent::MouseInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        });
+        while ui.poll_message().is_some() {}
+
+        // The thumb moves towards the cursor by ten steps.
+        assert_eq!(ui.node(slider).cast::<ScrollBar>().unwrap().value(), 10.0);
+    }
+
+    #[test]
+    fn keyboard_steps() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let slider = SliderBuilder::new(WidgetBuilder::new().with_width(200.0))
+            .with_value(50.0)
+            .with_step(5.0)
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let scroll_bar = ui.node(slider).cast::<ScrollBar>().unwrap();
+        assert!(scroll_bar.increase.is_none() && scroll_bar.decrease.is_none());
+        let thumb = scroll_bar.indicator;
+
+        // Key presses are sent to the focused thumb and bubble up to the slider.
+        ui.send_message(WidgetMessage::key_down(
+            thumb,
+            MessageDirection::FromWidget,
+            KeyCode::Right,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(slider).cast::<ScrollBar>().unwrap().value(), 55.0);
+
+        ui.send_message(WidgetMessage::key_down(
+            thumb,
+            MessageDirection::FromWidget,
+            KeyCode::Left,
+        ));
+        ui.send_message(WidgetMessage::key_down(
+            thumb,
+            MessageDirection::FromWidget,
+            KeyCode::Left,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(slider).cast::<ScrollBar>().unwrap().value(), 45.0);
+    }
+}
@@ -49,6 +49,8 @@ impl Control for StackPanel {
     fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
+        let padding = self.widget.padding();
+
         let mut child_constraint = Vector2::new(f32::INFINITY, f32::INFINITY);
 
         match self.orientation {
@@ -59,10 +61,12 @@ impl Control for StackPanel {
                     child_constraint.x = self.widget.width();
                 }
 
-                child_constraint.x = child_constraint
+                child_constraint.x = (child_constraint
                     .x
                     .min(self.max_width())
-                    .max(self.min_width());
+                    .max(self.min_width())
+                    - padding.horizontal())
+                .max(0.0);
             }
             Orientation::Horizontal => {
                 child_constraint.y = available_size.y;
@@ -71,10 +75,12 @@ impl Control for StackPanel {
                     child_constraint.y = self.widget.height();
                 }
 
-                child_constraint.y = child_constraint
+                child_constraint.y = (child_constraint
                     .y
                     .min(self.max_height())
-                    .max(self.min_height());
+                    .max(self.min_height())
+                    - padding.vertical())
+                .max(0.0);
             }
         }
 
@@ -101,14 +107,17 @@ impl Control for StackPanel {
             }
         }
 
-        measured_size
+        measured_size + padding.axes_margin()
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
         scope_profile!();
 
-        let mut width = final_size.x;
-        let mut height = final_size.y;
+        let padding = self.widget.padding();
+
+        let mut width = (final_size.x - padding.horizontal()).max(0.0);
+        let mut height = (final_size.y - padding.vertical()).max(0.0);
+        let inner_size = Vector2::new(width, height);
 
         match self.orientation {
             Orientation::Vertical => height = 0.0,
@@ -120,8 +129,8 @@ impl Control for StackPanel {
             match self.orientation {
                 Orientation::Vertical => {
                     let child_bounds = Rect::new(
-                        0.0,
-                        height,
+                        padding.left,
+                        padding.top + height,
                         width.max(child.desired_size().x),
                         child.desired_size().y,
                     );
@@ -131,8 +140,8 @@ impl Control for StackPanel {
                 }
                 Orientation::Horizontal => {
                     let child_bounds = Rect::new(
-                        width,
-                        0.0,
+                        padding.left + width,
+                        padding.top,
                         child.desired_size().x,
                         height.max(child.desired_size().y),
                     );
@@ -145,14 +154,14 @@ impl Control for StackPanel {
 
         match self.orientation {
             Orientation::Vertical => {
-                height = height.max(final_size.y);
+                height = height.max(inner_size.y);
             }
             Orientation::Horizontal => {
-                width = width.max(final_size.x);
+                width = width.max(inner_size.x);
             }
         }
 
-        Vector2::new(width, height)
+        Vector2::new(width, height) + padding.axes_margin()
     }
 
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
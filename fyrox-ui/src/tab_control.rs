@@ -3,6 +3,7 @@ use crate::{
     brush::Brush,
     button::{ButtonBuilder, ButtonMessage},
     core::{color::Color, pool::Handle},
+    define_constructor,
     grid::{Column, GridBuilder, Row},
     message::{MessageDirection, UiMessage},
     widget::{Widget, WidgetBuilder, WidgetMessage},
@@ -13,6 +14,18 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum TabControlMessage {
+    /// Switches active tab, content of other tabs is hidden. When active tab was changed, the
+    /// tab control responds with the same message with [`MessageDirection::FromWidget`], it is
+    /// also sent when user clicks a header of a tab. Indices out of range are ignored.
+    ActiveTab(Option<usize>),
+}
+
+impl TabControlMessage {
+    define_constructor!(TabControlMessage:ActiveTab => fn active_tab(Option<usize>), layout: false);
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Tab {
     header_button: Handle<UiNode>,
@@ -23,10 +36,18 @@ pub struct Tab {
 pub struct TabControl {
     widget: Widget,
     tabs: Vec<Tab>,
+    active_tab: Option<usize>,
 }
 
 crate::define_widget_deref!(TabControl);
 
+impl TabControl {
+    /// Returns index of the tab with visible content.
+    pub fn active_tab(&self) -> Option<usize> {
+        self.active_tab
+    }
+}
+
 impl Control for TabControl {
     fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
         if type_id == TypeId::of::<Self>() {
@@ -52,16 +73,32 @@ impl Control for TabControl {
                     && tab.header_button.is_some()
                     && tab.content.is_some()
                 {
-                    for (j, other_tab) in self.tabs.iter().enumerate() {
-                        ui.send_message(WidgetMessage::visibility(
-                            other_tab.content,
-                            MessageDirection::ToWidget,
-                            j == i,
-                        ));
-                    }
+                    ui.send_message(TabControlMessage::active_tab(
+                        self.handle(),
+                        MessageDirection::ToWidget,
+                        Some(i),
+                    ));
                     break;
                 }
             }
+        } else if let Some(&TabControlMessage::ActiveTab(active_tab)) =
+            message.data::<TabControlMessage>()
+        {
+            if message.destination() == self.handle()
+                && message.direction() == MessageDirection::ToWidget
+                && self.active_tab != active_tab
+                && active_tab.map_or(true, |i| i < self.tabs.len())
+            {
+                for (i, tab) in self.tabs.iter().enumerate() {
+                    ui.send_message(WidgetMessage::visibility(
+                        tab.content,
+                        MessageDirection::ToWidget,
+                        active_tab == Some(i),
+                    ));
+                }
+                self.active_tab = active_tab;
+                ui.send_message(message.reverse());
+            }
         }
     }
 }
@@ -158,8 +195,104 @@ impl TabControlBuilder {
                     content,
                 })
                 .collect(),
+            active_tab: if tab_count > 0 { Some(0) } else { None },
         };
 
         ctx.add_node(UiNode::new(tc))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        button::ButtonMessage,
+        core::{algebra::Vector2, pool::Handle},
+        message::MessageDirection,
+        tab_control::{TabControl, TabControlBuilder, TabControlMessage, TabDefinition},
+        text::TextBuilder,
+        widget::WidgetBuilder,
+        UiNode, UserInterface,
+    };
+
+    #[test]
+    fn switch_tabs() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let mut tab = |height: f32| {
+            let ctx = &mut ui.build_ctx();
+            TabDefinition {
+                header: TextBuilder::new(WidgetBuilder::new()).build(ctx),
+                content: BorderBuilder::new(WidgetBuilder::new().with_height(height)).build(ctx),
+            }
+        };
+        let first = tab(100.0);
+        let second = tab(50.0);
+        let (first_content, second_content) = (first.content, second.content);
+        let tab_control = TabControlBuilder::new(WidgetBuilder::new())
+            .with_tab(first)
+            .with_tab(second)
+            .build(&mut ui.build_ctx());
+        let content_panel = ui.node(first_content).parent();
+
+        let tab_control_ref = |ui: &UserInterface| -> TabControl {
+            ui.node(tab_control).cast::<TabControl>().unwrap().clone()
+        };
+        let header_button = |ui: &UserInterface, i: usize| -> Handle<UiNode> {
+            tab_control_ref(ui).tabs[i].header_button
+        };
+
+        ui.update(screen_size, 0.0);
+        assert_eq!(tab_control_ref(&ui).active_tab(), Some(0));
+        assert_eq!(ui.node(content_panel).desired_size().y, 100.0);
+
+        let click = |ui: &mut UserInterface, i: usize| {
+            ui.send_message(ButtonMessage::click(
+                header_button(ui, i),
+                MessageDirection::FromWidget,
+            ));
+            let mut responses = Vec::new();
+            while let Some(message) = ui.poll_message() {
+                if message.destination() == tab_control
+                    && message.direction() == MessageDirection::FromWidget
+                {
+                    if let Some(msg) = message.data::<TabControlMessage>() {
+                        responses.push(msg.clone());
+                    }
+                }
+            }
+            responses
+        };
+
+        assert_eq!(
+            click(&mut ui, 1),
+            vec![TabControlMessage::ActiveTab(Some(1))]
+        );
+        // Clicking active tab changes nothing.
+        assert!(click(&mut ui, 1).is_empty());
+
+        ui.update(screen_size, 0.0);
+        assert_eq!(tab_control_ref(&ui).active_tab(), Some(1));
+        assert!(!ui.node(first_content).visibility());
+        assert!(ui.node(second_content).visibility());
+        // Hidden content does not take space in layout.
+        assert_eq!(ui.node(first_content).desired_size(), Vector2::default());
+        assert_eq!(ui.node(content_panel).desired_size().y, 50.0);
+
+        // Out of range index is ignored.
+        ui.send_message(TabControlMessage::active_tab(
+            tab_control,
+            MessageDirection::ToWidget,
+            Some(2),
+        ));
+        while let Some(message) = ui.poll_message() {
+            assert!(
+                message.data::<TabControlMessage>().is_none()
+                    || message.direction() == MessageDirection::ToWidget
+            );
+        }
+        assert_eq!(tab_control_ref(&ui).active_tab(), Some(1));
+        assert!(ui.node(second_content).visibility());
+    }
+}
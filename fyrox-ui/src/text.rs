@@ -3,7 +3,8 @@ use crate::{
     core::{algebra::Vector2, color::Color, pool::Handle},
     define_constructor,
     draw::DrawingContext,
-    formatted_text::{FormattedText, FormattedTextBuilder, WrapMode},
+    formatted_text::{FormattedText, FormattedTextBuilder, TextOutline, TextShadow, WrapMode},
+    localization::LocalizationTable,
     message::{MessageDirection, UiMessage},
     ttf::SharedFont,
     widget::{Widget, WidgetBuilder},
@@ -22,6 +23,8 @@ pub enum TextMessage {
     Font(SharedFont),
     VerticalAlignment(VerticalAlignment),
     HorizontalAlignment(HorizontalAlignment),
+    Outline(Option<TextOutline>),
+    Shadow(Option<TextShadow>),
 }
 
 impl TextMessage {
@@ -30,12 +33,16 @@ impl TextMessage {
     define_constructor!(TextMessage:Font => fn font(SharedFont), layout: false);
     define_constructor!(TextMessage:VerticalAlignment => fn vertical_alignment(VerticalAlignment), layout: false);
     define_constructor!(TextMessage:HorizontalAlignment => fn horizontal_alignment(HorizontalAlignment), layout: false);
+    define_constructor!(TextMessage:Outline => fn outline(Option<TextOutline>), layout: false);
+    define_constructor!(TextMessage:Shadow => fn shadow(Option<TextShadow>), layout: false);
 }
 
 #[derive(Clone)]
 pub struct Text {
     widget: Widget,
     formatted_text: RefCell<FormattedText>,
+    localized_key: Option<String>,
+    localized_args: Vec<String>,
 }
 
 crate::define_widget_deref!(Text);
@@ -73,6 +80,8 @@ impl Control for Text {
             if let Some(msg) = message.data::<TextMessage>() {
                 match msg {
                     TextMessage::Text(text) => {
+                        // Explicitly set text overrides localized one.
+                        self.localized_key = None;
                         self.formatted_text.borrow_mut().set_text(text);
                         self.invalidate_layout();
                     }
@@ -98,6 +107,14 @@ impl Control for Text {
                             .set_vertical_alignment(vertical_alignment);
                         self.invalidate_layout();
                     }
+                    &TextMessage::Outline(outline) => {
+                        self.formatted_text.borrow_mut().set_outline(outline);
+                        self.invalidate_layout();
+                    }
+                    &TextMessage::Shadow(shadow) => {
+                        self.formatted_text.borrow_mut().set_shadow(shadow);
+                        self.invalidate_layout();
+                    }
                 }
             }
         }
@@ -113,6 +130,8 @@ impl Text {
                     .with_font(crate::DEFAULT_FONT.clone())
                     .build(),
             ),
+            localized_key: None,
+            localized_args: Default::default(),
         }
     }
 
@@ -135,6 +154,28 @@ impl Text {
     pub fn horizontal_alignment(&self) -> HorizontalAlignment {
         self.formatted_text.borrow().horizontal_alignment()
     }
+
+    pub fn outline(&self) -> Option<TextOutline> {
+        self.formatted_text.borrow().outline()
+    }
+
+    pub fn shadow(&self) -> Option<TextShadow> {
+        self.formatted_text.borrow().shadow()
+    }
+
+    /// Returns key of a string in localization table, that is used as text, if any.
+    pub fn localized_key(&self) -> Option<&str> {
+        self.localized_key.as_deref()
+    }
+
+    /// Resolves localized text using given table, does nothing if the text is not localized.
+    pub fn localize(&mut self, table: &LocalizationTable) {
+        if let Some(key) = self.localized_key.as_ref() {
+            let text = table.format(key, &self.localized_args);
+            self.formatted_text.borrow_mut().set_text(text);
+            self.invalidate_layout();
+        }
+    }
 }
 
 pub struct TextBuilder {
@@ -144,6 +185,13 @@ pub struct TextBuilder {
     vertical_text_alignment: VerticalAlignment,
     horizontal_text_alignment: HorizontalAlignment,
     wrap: WrapMode,
+    outline: Option<TextOutline>,
+    shadow: Option<TextShadow>,
+    line_spacing: f32,
+    letter_spacing: f32,
+    tab_width: Option<f32>,
+    localized_key: Option<String>,
+    localized_args: Vec<String>,
 }
 
 impl TextBuilder {
@@ -155,6 +203,13 @@ impl TextBuilder {
             vertical_text_alignment: VerticalAlignment::Top,
             horizontal_text_alignment: HorizontalAlignment::Left,
             wrap: WrapMode::NoWrap,
+            outline: None,
+            shadow: None,
+            line_spacing: 1.0,
+            letter_spacing: 0.0,
+            tab_width: None,
+            localized_key: None,
+            localized_args: Default::default(),
         }
     }
 
@@ -163,6 +218,20 @@ impl TextBuilder {
         self
     }
 
+    /// Sets key of a string in localization table of the UI, the string will be used as text
+    /// instead of the one set by [`Self::with_text`]. The text is updated when the language of
+    /// the UI changes (see [`UserInterface::set_language`]).
+    pub fn with_localized_key<K: AsRef<str>>(mut self, key: K) -> Self {
+        self.localized_key = Some(key.as_ref().to_owned());
+        self
+    }
+
+    /// Sets arguments that will replace `{0}`, `{1}`, etc. placeholders in localized string.
+    pub fn with_localized_args(mut self, args: Vec<String>) -> Self {
+        self.localized_args = args;
+        self
+    }
+
     pub fn with_font(mut self, font: SharedFont) -> Self {
         self.font = Some(font);
         self
@@ -188,28 +257,70 @@ impl TextBuilder {
         self
     }
 
+    /// Sets multiplier of vertical distance between lines of the text.
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Sets extra horizontal distance (in pixels) between characters of the text.
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    /// Sets advance (in pixels) of a tab character.
+    pub fn with_tab_width(mut self, tab_width: f32) -> Self {
+        self.tab_width = Some(tab_width);
+        self
+    }
+
+    /// Sets outline of given color and thickness (in pixels) around the text.
+    pub fn with_outline(mut self, color: Color, thickness: f32) -> Self {
+        self.outline = Some(TextOutline { color, thickness });
+        self
+    }
+
+    /// Sets shadow of given color, shifted by given offset (in pixels) relative to the text.
+    pub fn with_shadow(mut self, color: Color, offset: Vector2<f32>) -> Self {
+        self.shadow = Some(TextShadow { color, offset });
+        self
+    }
+
     pub fn build(mut self, ui: &mut BuildContext) -> Handle<UiNode> {
         let font = if let Some(font) = self.font {
             font
         } else {
-            crate::DEFAULT_FONT.clone()
+            ui.default_font()
         };
 
         if self.widget_builder.foreground.is_none() {
             self.widget_builder.foreground = Some(Brush::Solid(Color::opaque(220, 220, 220)));
         }
 
+        let text = match self.localized_key.as_ref() {
+            Some(key) => ui.localization().format(key, &self.localized_args),
+            None => self.text.unwrap_or_default(),
+        };
+
         let text = Text {
             widget: self.widget_builder.build(),
             formatted_text: RefCell::new(
                 FormattedTextBuilder::new()
-                    .with_text(self.text.unwrap_or_default())
+                    .with_text(text)
                     .with_vertical_alignment(self.vertical_text_alignment)
                     .with_horizontal_alignment(self.horizontal_text_alignment)
                     .with_font(font)
                     .with_wrap(self.wrap)
+                    .with_outline(self.outline)
+                    .with_shadow(self.shadow)
+                    .with_line_spacing(self.line_spacing)
+                    .with_letter_spacing(self.letter_spacing)
+                    .with_tab_width(self.tab_width)
                     .build(),
             ),
+            localized_key: self.localized_key,
+            localized_args: self.localized_args,
         };
         ui.add_node(UiNode::new(text))
     }
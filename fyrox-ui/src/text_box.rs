@@ -117,6 +117,8 @@ pub struct TextBox {
     commit_mode: TextCommitMode,
     multiline: bool,
     editable: bool,
+    /// Composition (pre-edit) text of an input method editor, drawn at caret position.
+    composition: Option<FormattedText>,
 }
 
 impl Debug for TextBox {
@@ -379,6 +381,31 @@ impl TextBox {
         self.formatted_text.borrow().text()
     }
 
+    fn caret_local_position(&self) -> Vector2<f32> {
+        let text = self.formatted_text.borrow();
+
+        let font = text.get_font();
+        let mut caret_pos = Vector2::default();
+
+        let font = font.0.lock().unwrap();
+        if let Some(line) = text.get_lines().get(self.caret_position.line) {
+            let text = text.get_raw_text();
+            caret_pos += Vector2::new(line.x_offset, line.y_offset);
+            for (offset, char_index) in (line.begin..line.end).enumerate() {
+                if offset >= self.caret_position.offset {
+                    break;
+                }
+                if let Some(glyph) = font.glyphs().get(text[char_index].glyph_index as usize) {
+                    caret_pos.x += glyph.advance;
+                } else {
+                    caret_pos.x += font.height();
+                }
+            }
+        }
+
+        caret_pos
+    }
+
     pub fn set_wrap(&mut self, wrap: WrapMode) -> &mut Self {
         self.formatted_text.borrow_mut().set_wrap(wrap);
         self
@@ -523,27 +550,29 @@ impl Control for TextBox {
         let screen_position = bounds.position;
         drawing_context.draw_text(bounds, screen_position, &self.formatted_text.borrow());
 
-        if self.caret_visible {
-            let text = self.formatted_text.borrow();
+        let caret_pos = screen_position + self.caret_local_position();
 
-            let font = text.get_font();
-            let mut caret_pos = screen_position;
+        if let Some(composition) = self.composition.as_ref() {
+            drawing_context.draw_text(self.clip_bounds(), caret_pos, composition);
 
+            // Underline composition text to distinguish it from committed text.
+            let font = composition.get_font();
+            let font = font.0.lock().unwrap();
+            let width = composition.get_range_width(0..composition.get_raw_text().len());
+            let underline_bounds =
+                Rect::new(caret_pos.x, caret_pos.y + font.ascender(), width, 1.0);
+            drawing_context.push_rect_filled(&underline_bounds, None);
+            drawing_context.commit(
+                self.clip_bounds(),
+                self.widget.foreground(),
+                CommandTexture::None,
+                None,
+            );
+        }
+
+        if self.caret_visible {
+            let font = self.formatted_text.borrow().get_font();
             let font = font.0.lock().unwrap();
-            if let Some(line) = text.get_lines().get(self.caret_position.line) {
-                let text = text.get_raw_text();
-                caret_pos += Vector2::new(line.x_offset, line.y_offset);
-                for (offset, char_index) in (line.begin..line.end).enumerate() {
-                    if offset >= self.caret_position.offset {
-                        break;
-                    }
-                    if let Some(glyph) = font.glyphs().get(text[char_index].glyph_index as usize) {
-                        caret_pos.x += glyph.advance;
-                    } else {
-                        caret_pos.x += font.height();
-                    }
-                }
-            }
 
             let caret_bounds = Rect::new(caret_pos.x, caret_pos.y, 2.0, font.height());
             drawing_context.push_rect_filled(&caret_bounds, None);
@@ -562,9 +591,11 @@ impl Control for TextBox {
             if self.blink_timer >= self.blink_interval {
                 self.blink_timer = 0.0;
                 self.caret_visible = !self.caret_visible;
+                self.widget.invalidate_visual();
             }
-        } else {
+        } else if self.caret_visible {
             self.caret_visible = false;
+            self.widget.invalidate_visual();
         }
     }
 
@@ -733,6 +764,20 @@ impl Control for TextBox {
                         }
                         _ => (),
                     },
+                    WidgetMessage::ImeComposition(composition) if self.editable => {
+                        self.composition = if composition.is_empty() {
+                            None
+                        } else {
+                            let mut text = FormattedTextBuilder::new()
+                                .with_font(self.formatted_text.borrow().get_font())
+                                .with_text(composition.clone())
+                                .with_brush(self.widget.foreground())
+                                .with_constraint(Vector2::new(f32::INFINITY, f32::INFINITY))
+                                .build();
+                            text.build();
+                            Some(text)
+                        };
+                    }
                     WidgetMessage::GotFocus => {
                         self.reset_blink();
                         self.selection_range = None;
@@ -741,6 +786,7 @@ impl Control for TextBox {
                     WidgetMessage::LostFocus => {
                         self.selection_range = None;
                         self.has_focus = false;
+                        self.composition = None;
 
                         if self.commit_mode == TextCommitMode::LostFocus
                             || self.commit_mode == TextCommitMode::LostFocusPlusEnter
@@ -793,6 +839,18 @@ impl Control for TextBox {
                     }
                     _ => {}
                 }
+
+                // Text, caret and selection are drawn by the text box itself.
+                if self.selecting
+                    || !matches!(
+                        msg,
+                        WidgetMessage::MouseMove { .. }
+                            | WidgetMessage::MouseEnter
+                            | WidgetMessage::MouseLeave
+                    )
+                {
+                    self.invalidate_visual();
+                }
             } else if let Some(TextBoxMessage::Text(new_text)) = message.data::<TextBoxMessage>() {
                 if message.direction() == MessageDirection::ToWidget {
                     let mut equals = false;
@@ -924,6 +982,9 @@ impl TextBoxBuilder {
         if self.widget_builder.background.is_none() {
             self.widget_builder.background = Some(BRUSH_DARKER);
         }
+        if self.widget_builder.focusable.is_none() {
+            self.widget_builder.focusable = Some(true);
+        }
         if self.widget_builder.cursor.is_none() {
             self.widget_builder.cursor = Some(CursorIcon::Text);
         }
@@ -937,7 +998,7 @@ impl TextBoxBuilder {
             formatted_text: RefCell::new(
                 FormattedTextBuilder::new()
                     .with_text(self.text)
-                    .with_font(self.font.unwrap_or_else(|| crate::DEFAULT_FONT.clone()))
+                    .with_font(self.font.unwrap_or_else(|| ctx.default_font()))
                     .with_horizontal_alignment(self.horizontal_alignment)
                     .with_vertical_alignment(self.vertical_alignment)
                     .with_wrap(self.wrap)
@@ -953,6 +1014,7 @@ impl TextBoxBuilder {
             commit_mode: self.commit_mode,
             multiline: self.multiline,
             editable: self.editable,
+            composition: None,
         };
 
         ctx.add_node(UiNode::new(text_box))
@@ -1,5 +1,5 @@
 use crate::{
-    core::{algebra::Vector2, io, rectpack::RectPacker},
+    core::{algebra::Vector2, io, math::Rect, rectpack::RectPacker},
     draw::SharedTexture,
 };
 use fxhash::FxHashMap;
@@ -29,9 +29,81 @@ pub struct Font {
     char_map: FxHashMap<u32, usize>,
     atlas: Vec<u8>,
     atlas_size: usize,
+    /// Source font, used to rasterize glyphs that were not in the initial char set. Bitmap fonts
+    /// do not have it.
+    source: Option<fontdue::Font>,
+    packer: RectPacker<usize>,
+    dirty_region: Option<Rect<usize>>,
+    render_mode: FontRenderMode,
     pub texture: Option<SharedTexture>,
 }
 
+const ATLAS_BORDER: usize = 2;
+const MIN_ATLAS_SIZE: usize = 64;
+
+/// Defines how glyphs of a font are stored in the atlas and rendered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FontRenderMode {
+    /// Glyphs are stored as coverage bitmaps. Text is sharp only when rendered at the size the
+    /// font was loaded with, and becomes blurry when scaled.
+    Bitmap,
+    /// Glyphs are stored as signed distance fields, where 0.5 is the edge of a glyph. Such text
+    /// stays sharp when scaled and allows cheap outlines and glows. Distance fields are computed
+    /// on CPU from rasterized glyphs, so it is intended mostly for small char sets (like Latin).
+    Sdf {
+        /// Distance (in pixels) covered by the field on each side of a glyph edge.
+        spread: usize,
+    },
+}
+
+impl Default for FontRenderMode {
+    fn default() -> Self {
+        Self::Bitmap
+    }
+}
+
+/// Converts coverage bitmap into a signed distance field with given spread, the field is larger
+/// than the source bitmap by the spread on each side. Returns pixels of the field.
+fn make_distance_field(pixels: &[u8], width: usize, height: usize, spread: usize) -> Vec<u8> {
+    let field_width = width + 2 * spread;
+    let field_height = height + 2 * spread;
+
+    let is_inside = |x: isize, y: isize| -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < width
+            && (y as usize) < height
+            && pixels[y as usize * width + x as usize] >= 128
+    };
+
+    let radius = spread as isize;
+    let mut field = vec![0; field_width * field_height];
+    for y in 0..field_height {
+        for x in 0..field_width {
+            let sx = x as isize - radius;
+            let sy = y as isize - radius;
+            let inside = is_inside(sx, sy);
+
+            // Find the nearest pixel of opposite state in the spread window.
+            let mut min_sqr_distance = (radius * radius + 1) as f32;
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if is_inside(sx + dx, sy + dy) != inside {
+                        min_sqr_distance = min_sqr_distance.min((dx * dx + dy * dy) as f32);
+                    }
+                }
+            }
+
+            let distance = min_sqr_distance.sqrt().min(spread as f32);
+            let signed_distance = if inside { distance } else { -distance };
+            let value = 0.5 + signed_distance / (2.0 * spread as f32);
+            field[y * field_width + x] = (value.max(0.0).min(1.0) * 255.0) as u8;
+        }
+    }
+
+    field
+}
+
 #[derive(Debug, Clone)]
 pub struct SharedFont(pub Arc<Mutex<Font>>);
 
@@ -138,10 +210,23 @@ impl Font {
         ]
     }
 
+    /// Creates new font from given data. Glyphs of the given char set are rasterized immediately,
+    /// other glyphs are rasterized on first use (see [`Self::rasterize_glyph`]), so the char set
+    /// could be empty to keep memory usage proportional to the amount of glyphs actually used.
     pub fn from_memory(
         data: Vec<u8>,
         height: f32,
         char_set: &[Range<u32>],
+    ) -> Result<Self, &'static str> {
+        Self::from_memory_with_mode(data, height, char_set, FontRenderMode::Bitmap)
+    }
+
+    /// Same as [`Self::from_memory`], but allows to choose how glyphs will be rendered.
+    pub fn from_memory_with_mode(
+        data: Vec<u8>,
+        height: f32,
+        char_set: &[Range<u32>],
+        render_mode: FontRenderMode,
     ) -> Result<Self, &'static str> {
         let fontdue_font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
         let font_metrics = fontdue_font.horizontal_line_metrics(height).unwrap();
@@ -154,43 +239,241 @@ impl Font {
             char_map: FxHashMap::default(),
             atlas: Vec::new(),
             atlas_size: 0,
+            source: Some(fontdue_font),
+            packer: RectPacker::new(0, 0),
+            dirty_region: None,
+            render_mode,
             texture: None,
         };
 
-        let mut index = 0;
         for range in char_set {
             for unicode in range.start..range.end {
-                if let Some(character) = std::char::from_u32(unicode) {
-                    let (metrics, bitmap) = fontdue_font.rasterize(character, height);
-
-                    font.glyphs.push(FontGlyph {
-                        left: metrics.xmin as f32,
-                        top: metrics.ymin as f32,
-                        pixels: bitmap,
-                        advance: metrics.advance_width,
-                        tex_coords: Default::default(),
-                        bitmap_width: metrics.width,
-                        bitmap_height: metrics.height,
-                    });
-
-                    font.char_map.insert(unicode, index);
-                    index += 1;
+                if let Some(glyph) =
+                    std::char::from_u32(unicode).and_then(|character| font.rasterize(character))
+                {
+                    font.char_map.insert(unicode, font.glyphs.len());
+                    font.glyphs.push(glyph);
                 }
             }
         }
 
-        font.pack();
+        font.pack(font.compute_atlas_size(ATLAS_BORDER));
 
         Ok(font)
     }
 
+    fn rasterize(&self, character: char) -> Option<FontGlyph> {
+        let (metrics, bitmap) = self.source.as_ref()?.rasterize(character, self.height);
+
+        Some(match self.render_mode {
+            FontRenderMode::Sdf { spread } if metrics.width > 0 && metrics.height > 0 => {
+                FontGlyph {
+                    left: metrics.xmin as f32 - spread as f32,
+                    top: metrics.ymin as f32 - spread as f32,
+                    pixels: make_distance_field(&bitmap, metrics.width, metrics.height, spread),
+                    advance: metrics.advance_width,
+                    tex_coords: Default::default(),
+                    bitmap_width: metrics.width + 2 * spread,
+                    bitmap_height: metrics.height + 2 * spread,
+                }
+            }
+            _ => FontGlyph {
+                left: metrics.xmin as f32,
+                top: metrics.ymin as f32,
+                pixels: bitmap,
+                advance: metrics.advance_width,
+                tex_coords: Default::default(),
+                bitmap_width: metrics.width,
+                bitmap_height: metrics.height,
+            },
+        })
+    }
+
+    /// Returns the way glyphs of the font are stored and rendered.
+    pub fn render_mode(&self) -> FontRenderMode {
+        self.render_mode
+    }
+
+    /// Returns index of a glyph for the given character, rasterizing the glyph and putting it
+    /// into the atlas if needed. The atlas grows when there is no free space for a new glyph.
+    /// Changed part of the atlas is reported by [`Self::take_dirty_region`]. Returns `None` if
+    /// the code is not a valid character or if the font is a bitmap font without such glyph.
+    pub fn rasterize_glyph(&mut self, unicode: u32) -> Option<usize> {
+        if let Some(index) = self.glyph_index(unicode) {
+            return Some(index);
+        }
+
+        let character = std::char::from_u32(unicode)?;
+        let glyph = self.rasterize(character)?;
+        let index = self.glyphs.len();
+        self.char_map.insert(unicode, index);
+        self.glyphs.push(glyph);
+
+        if !self.place_glyph(index) {
+            // Rebuild the atlas with the new glyph, this invalidates whole atlas.
+            self.pack((self.atlas_size * 2).max(self.compute_atlas_size(ATLAS_BORDER)));
+        }
+
+        Some(index)
+    }
+
+    /// Returns region of the atlas (in pixels) that was changed since the last call, if any.
+    /// Renderers should re-upload this region (or the whole atlas) to the GPU.
+    pub fn take_dirty_region(&mut self) -> Option<Rect<usize>> {
+        self.dirty_region.take()
+    }
+
+    fn mark_dirty(&mut self, region: Rect<usize>) {
+        match self.dirty_region.as_mut() {
+            Some(dirty_region) => *dirty_region = dirty_region.union(region),
+            None => self.dirty_region = Some(region),
+        }
+    }
+
+    /// Creates new bitmap font from a glyph table in text format of BMFont (`.fnt`) and its atlas.
+    /// The atlas is given as a single channel image (usually alpha channel of a texture), where
+    /// each pixel is the coverage of a glyph. Such fonts are rendered exactly as drawn, which is
+    /// the usual way to get crisp stylized text in pixel-art games. Only the first page of the
+    /// description is supported, glyphs that are not in the table cannot be rendered.
+    pub fn from_bmfont(
+        description: &str,
+        atlas: &[u8],
+        atlas_width: usize,
+        atlas_height: usize,
+    ) -> Result<Self, &'static str> {
+        if atlas.len() != atlas_width * atlas_height {
+            return Err("Atlas size does not match its dimensions");
+        }
+
+        // Atlas of a font is always square.
+        let atlas_size = atlas_width.max(atlas_height);
+        let mut square_atlas = vec![0; atlas_size * atlas_size];
+        for (row, pixels) in atlas.chunks(atlas_width).enumerate() {
+            let begin = row * atlas_size;
+            square_atlas[begin..(begin + atlas_width)].copy_from_slice(pixels);
+        }
+
+        let mut line_height = None;
+        let mut base = None;
+        let mut glyphs = Vec::new();
+        let mut char_map = FxHashMap::default();
+
+        for line in description.lines() {
+            let tag = line.split_whitespace().next();
+            let value = |key: &str| -> Option<i64> {
+                line.split_whitespace()
+                    .filter_map(|token| token.split_once('='))
+                    .find(|(name, _)| *name == key)
+                    .and_then(|(_, value)| value.parse().ok())
+            };
+
+            match tag {
+                Some("common") => {
+                    line_height = value("lineHeight");
+                    base = value("base");
+                }
+                Some("char") => {
+                    if value("page").unwrap_or_default() != 0 {
+                        continue;
+                    }
+
+                    let required = |key: &str| value(key).ok_or("Invalid char definition");
+                    let id = required("id")? as u32;
+                    let x = required("x")? as usize;
+                    let y = required("y")? as usize;
+                    let width = required("width")? as usize;
+                    let height = required("height")? as usize;
+                    let x_offset = required("xoffset")? as f32;
+                    let y_offset = required("yoffset")? as f32;
+                    let x_advance = required("xadvance")? as f32;
+
+                    if x + width > atlas_width || y + height > atlas_height {
+                        return Err("Glyph is out of atlas bounds");
+                    }
+
+                    let mut pixels = Vec::with_capacity(width * height);
+                    for row in y..(y + height) {
+                        let begin = row * atlas_width + x;
+                        pixels.extend_from_slice(&atlas[begin..(begin + width)]);
+                    }
+
+                    let k = 1.0 / atlas_size as f32;
+                    let (tx, ty, tw, th) = (
+                        x as f32 * k,
+                        y as f32 * k,
+                        width as f32 * k,
+                        height as f32 * k,
+                    );
+
+                    char_map.insert(id, glyphs.len());
+                    glyphs.push(FontGlyph {
+                        // Converted when the whole table is read, because it depends on base.
+                        top: y_offset,
+                        left: x_offset,
+                        advance: x_advance,
+                        tex_coords: [
+                            Vector2::new(tx, ty),
+                            Vector2::new(tx + tw, ty),
+                            Vector2::new(tx + tw, ty + th),
+                            Vector2::new(tx, ty + th),
+                        ],
+                        bitmap_width: width,
+                        bitmap_height: height,
+                        pixels,
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        let (line_height, base) = match (line_height, base) {
+            (Some(line_height), Some(base)) => (line_height as f32, base as f32),
+            _ => return Err("Missing common font metrics"),
+        };
+
+        // BMFont measures glyph offset from the top of the line, convert it to the offset of
+        // glyph bottom from the baseline.
+        for glyph in glyphs.iter_mut() {
+            glyph.top = base - glyph.top - glyph.bitmap_height as f32;
+        }
+
+        let mut packer = RectPacker::new(atlas_size, atlas_size);
+        // Occupy the whole atlas, there is no way to add new glyphs to a bitmap font.
+        packer.find_free(atlas_size, atlas_size);
+
+        Ok(Font {
+            height: line_height,
+            glyphs,
+            ascender: base,
+            descender: base - line_height,
+            char_map,
+            atlas: square_atlas,
+            atlas_size,
+            source: None,
+            packer,
+            dirty_region: Some(Rect::new(0, 0, atlas_size, atlas_size)),
+            render_mode: FontRenderMode::Bitmap,
+            texture: None,
+        })
+    }
+
     pub async fn from_file<P: AsRef<Path>>(
         path: P,
         height: f32,
         char_set: &[Range<u32>],
+    ) -> Result<Self, &'static str> {
+        Self::from_file_with_mode(path, height, char_set, FontRenderMode::Bitmap).await
+    }
+
+    /// Same as [`Self::from_file`], but allows to choose how glyphs will be rendered.
+    pub async fn from_file_with_mode<P: AsRef<Path>>(
+        path: P,
+        height: f32,
+        char_set: &[Range<u32>],
+        render_mode: FontRenderMode,
     ) -> Result<Self, &'static str> {
         if let Ok(file_content) = io::load_file(path).await {
-            Self::from_memory(file_content, height, char_set)
+            Self::from_memory_with_mode(file_content, height, char_set, render_mode)
         } else {
             Err("Unable to read file")
         }
@@ -250,47 +533,109 @@ impl Font {
         for glyph in self.glyphs.iter() {
             area += (glyph.bitmap_width + border) as f32 * (glyph.bitmap_height + border) as f32;
         }
-        (1.3 * area.sqrt()) as usize
-    }
-
-    fn pack(&mut self) {
-        let border = 2;
-        self.atlas_size = self.compute_atlas_size(border);
-        self.atlas = vec![0; (self.atlas_size * self.atlas_size) as usize];
-        let k = 1.0 / self.atlas_size as f32;
-        let mut rect_packer = RectPacker::new(self.atlas_size, self.atlas_size);
-        for glyph in self.glyphs.iter_mut() {
-            if let Some(bounds) =
-                rect_packer.find_free(glyph.bitmap_width + border, glyph.bitmap_height + border)
-            {
-                let bw = (bounds.w() - border) as usize;
-                let bh = (bounds.h() - border) as usize;
-                let bx = (bounds.x() + border / 2) as usize;
-                let by = (bounds.y() + border / 2) as usize;
-
-                let tw = bw as f32 * k;
-                let th = bh as f32 * k;
-                let tx = bx as f32 * k;
-                let ty = by as f32 * k;
-
-                glyph.tex_coords[0] = Vector2::new(tx, ty);
-                glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
-                glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
-                glyph.tex_coords[3] = Vector2::new(tx, ty + th);
-
-                let row_end = by + bh;
-                let col_end = bx + bw;
-
-                // Copy glyph pixels to atlas pixels
-                for (src_row, row) in (by..row_end).enumerate() {
-                    for (src_col, col) in (bx..col_end).enumerate() {
-                        self.atlas[row * self.atlas_size + col] =
-                            glyph.pixels[src_row * bw + src_col];
-                    }
+        ((1.3 * area.sqrt()) as usize).max(MIN_ATLAS_SIZE)
+    }
+
+    /// Tries to find a place for a glyph in the atlas and copies its pixels there.
+    fn place_glyph(&mut self, index: usize) -> bool {
+        let glyph = &mut self.glyphs[index];
+
+        if let Some(bounds) = self.packer.find_free(
+            glyph.bitmap_width + ATLAS_BORDER,
+            glyph.bitmap_height + ATLAS_BORDER,
+        ) {
+            let k = 1.0 / self.atlas_size as f32;
+
+            let bw = bounds.w() - ATLAS_BORDER;
+            let bh = bounds.h() - ATLAS_BORDER;
+            let bx = bounds.x() + ATLAS_BORDER / 2;
+            let by = bounds.y() + ATLAS_BORDER / 2;
+
+            let tw = bw as f32 * k;
+            let th = bh as f32 * k;
+            let tx = bx as f32 * k;
+            let ty = by as f32 * k;
+
+            glyph.tex_coords[0] = Vector2::new(tx, ty);
+            glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
+            glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
+            glyph.tex_coords[3] = Vector2::new(tx, ty + th);
+
+            let row_end = by + bh;
+            let col_end = bx + bw;
+
+            // Copy glyph pixels to atlas pixels
+            for (src_row, row) in (by..row_end).enumerate() {
+                for (src_col, col) in (bx..col_end).enumerate() {
+                    self.atlas[row * self.atlas_size + col] = glyph.pixels[src_row * bw + src_col];
+                }
+            }
+
+            self.mark_dirty(bounds);
+
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Rebuilds the atlas of given size from scratch, the size is doubled until every glyph fits.
+    fn pack(&mut self, mut atlas_size: usize) {
+        'try_pack: loop {
+            self.atlas_size = atlas_size;
+            self.atlas = vec![0; self.atlas_size * self.atlas_size];
+            self.packer = RectPacker::new(self.atlas_size, self.atlas_size);
+
+            for index in 0..self.glyphs.len() {
+                if !self.place_glyph(index) {
+                    atlas_size *= 2;
+                    continue 'try_pack;
                 }
-            } else {
-                println!("Insufficient atlas size!");
             }
+
+            break;
         }
+
+        self.dirty_region = Some(Rect::new(0, 0, self.atlas_size, self.atlas_size));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ttf::{make_distance_field, Font};
+
+    #[test]
+    fn distance_field() {
+        // Single filled pixel.
+        let field = make_distance_field(&[255], 1, 1, 2);
+        assert_eq!(field.len(), 25);
+        // Center is inside, corners are far outside.
+        assert!(field[12] > 127);
+        assert_eq!(field[0], 0);
+        // Direct neighbours are outside, but closer than corners.
+        assert!(field[7] < 128 && field[7] > field[0]);
+    }
+
+    #[test]
+    fn bitmap_font() {
+        let description = "info face=\"Pixel\" size=8\n\
+            common lineHeight=8 base=6 scaleW=4 scaleH=2 pages=1\n\
+            chars count=1\n\
+            char id=65 x=1 y=0 width=2 height=2 xoffset=0 yoffset=4 xadvance=3 page=0\n";
+        let atlas = [0, 255, 255, 0, 0, 255, 255, 0];
+
+        let mut font = Font::from_bmfont(description, &atlas, 4, 2).unwrap();
+        assert_eq!(font.atlas_size(), 4);
+        assert_eq!(font.ascender(), 6.0);
+        assert_eq!(font.descender(), -2.0);
+
+        let glyph = font.glyph(65).unwrap();
+        assert_eq!(glyph.advance, 3.0);
+        assert_eq!(glyph.top, 0.0);
+        assert_eq!(glyph.pixels, vec![255; 4]);
+
+        // Bitmap fonts cannot rasterize missing glyphs.
+        assert_eq!(font.rasterize_glyph(66), None);
+        assert!(Font::from_bmfont(description, &atlas, 2, 2).is_err());
     }
 }
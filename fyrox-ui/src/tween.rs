@@ -0,0 +1,283 @@
+//! Tweens smoothly change a property of a widget from its current value to a target value over
+//! time. See [`UserInterface::animate`](crate::UserInterface::animate) for more info.
+
+use crate::{
+    brush::Brush,
+    core::{algebra::Vector2, color::Color, math::lerpf, pool::Handle},
+    message::{MessageDirection, UiMessage},
+    widget::{Widget, WidgetMessage},
+    UiNode, UserInterface,
+};
+use std::fmt::{Debug, Formatter};
+
+/// Easing function that maps linear progress of a tween to progress of a value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl Easing {
+    /// Maps `t` in `[0; 1]` range to eased progress, `0` maps to `0` and `1` maps to `1`.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) * 0.5
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+                }
+            }
+        }
+    }
+}
+
+/// A property of a widget that could be animated, together with its target value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TweenProperty {
+    /// See [`Widget::desired_local_position`].
+    DesiredPosition(Vector2<f32>),
+    /// See [`Widget::width`]. If the width is not set explicitly, animation starts from the
+    /// actual width of the widget.
+    Width(f32),
+    /// See [`Widget::height`]. If the height is not set explicitly, animation starts from the
+    /// actual height of the widget.
+    Height(f32),
+    /// Color of solid background brush of the widget. If the background is not a solid brush,
+    /// animation starts from white color.
+    Color(Color),
+    /// See [`Widget::opacity`]. If the opacity is not set, animation starts from `1.0`.
+    Opacity(f32),
+}
+
+impl TweenProperty {
+    // Reads current value of the same property from the widget.
+    fn current(&self, widget: &Widget) -> Self {
+        match self {
+            TweenProperty::DesiredPosition(_) => {
+                TweenProperty::DesiredPosition(widget.desired_local_position())
+            }
+            TweenProperty::Width(_) => TweenProperty::Width(if widget.width().is_nan() {
+                widget.actual_size().x
+            } else {
+                widget.width()
+            }),
+            TweenProperty::Height(_) => TweenProperty::Height(if widget.height().is_nan() {
+                widget.actual_size().y
+            } else {
+                widget.height()
+            }),
+            TweenProperty::Color(_) => TweenProperty::Color(match widget.background() {
+                Brush::Solid(color) => color,
+                _ => Color::WHITE,
+            }),
+            TweenProperty::Opacity(_) => TweenProperty::Opacity(widget.opacity().unwrap_or(1.0)),
+        }
+    }
+
+    fn is_same_property(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    // Makes a message that sets interpolated value of the property.
+    fn message(&self, start: &Self, t: f32, destination: Handle<UiNode>) -> Option<UiMessage> {
+        let direction = MessageDirection::ToWidget;
+        Some(match (start, self) {
+            (TweenProperty::DesiredPosition(a), TweenProperty::DesiredPosition(b)) => {
+                WidgetMessage::desired_position(destination, direction, a.lerp(b, t))
+            }
+            (TweenProperty::Width(a), TweenProperty::Width(b)) => {
+                WidgetMessage::width(destination, direction, lerpf(*a, *b, t))
+            }
+            (TweenProperty::Height(a), TweenProperty::Height(b)) => {
+                WidgetMessage::height(destination, direction, lerpf(*a, *b, t))
+            }
+            (TweenProperty::Color(a), TweenProperty::Color(b)) => {
+                WidgetMessage::background(destination, direction, Brush::Solid(a.lerp(*b, t)))
+            }
+            (TweenProperty::Opacity(a), TweenProperty::Opacity(b)) => {
+                WidgetMessage::opacity(destination, direction, Some(lerpf(*a, *b, t)))
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// An active animation of a property of a widget, see
+/// [`UserInterface::animate`](crate::UserInterface::animate).
+pub struct Tween {
+    pub(in crate) node: Handle<UiNode>,
+    start: TweenProperty,
+    target: TweenProperty,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+    pub(in crate) on_complete: Option<Box<dyn FnOnce(&mut UserInterface)>>,
+}
+
+impl Debug for Tween {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tween")
+            .field("node", &self.node)
+            .field("start", &self.start)
+            .field("target", &self.target)
+            .field("duration", &self.duration)
+            .field("elapsed", &self.elapsed)
+            .field("easing", &self.easing)
+            .finish()
+    }
+}
+
+impl Tween {
+    pub(in crate) fn new(
+        node: Handle<UiNode>,
+        widget: &Widget,
+        target: TweenProperty,
+        duration: f32,
+        easing: Easing,
+    ) -> Self {
+        Self {
+            node,
+            start: target.current(widget),
+            target,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+            easing,
+            on_complete: None,
+        }
+    }
+
+    /// Sets a callback that will be called once the tween has reached its target value. The
+    /// callback is not called if the tween was replaced by another tween of the same property or
+    /// if the widget was deleted.
+    pub fn set_on_complete(&mut self, callback: Box<dyn FnOnce(&mut UserInterface)>) -> &mut Self {
+        self.on_complete = Some(callback);
+        self
+    }
+
+    pub fn node(&self) -> Handle<UiNode> {
+        self.node
+    }
+
+    pub fn target(&self) -> &TweenProperty {
+        &self.target
+    }
+
+    pub(in crate) fn is_animating(&self, node: Handle<UiNode>, property: &TweenProperty) -> bool {
+        self.node == node && self.target.is_same_property(property)
+    }
+
+    pub(in crate) fn is_complete(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Advances the tween and returns a message that sets new value of the property.
+    pub(in crate) fn advance(&mut self, dt: f32) -> Option<UiMessage> {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        let t = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+        self.target
+            .message(&self.start, self.easing.ease(t), self.node)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        core::algebra::Vector2,
+        tween::{Easing, TweenProperty},
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn easing_end_points() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadIn,
+            Easing::QuadOut,
+            Easing::QuadInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+        ] {
+            assert_eq!(easing.ease(0.0), 0.0);
+            assert_eq!(easing.ease(1.0), 1.0);
+            assert!((easing.ease(0.5) - 0.5).abs() <= 0.5);
+        }
+    }
+
+    #[test]
+    fn animate_width() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let border =
+            BorderBuilder::new(WidgetBuilder::new().with_width(100.0)).build(&mut ui.build_ctx());
+
+        let completed = Rc::new(Cell::new(false));
+        ui.animate(border, TweenProperty::Width(200.0), 1.0, Easing::Linear)
+            .set_on_complete(Box::new({
+                let completed = completed.clone();
+                move |_| completed.set(true)
+            }));
+
+        ui.update(screen_size, 0.5);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(border).width(), 150.0);
+        assert!(!completed.get());
+
+        ui.update(screen_size, 0.75);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(border).width(), 200.0);
+        assert!(completed.get());
+        assert!(ui.tweens.is_empty());
+    }
+
+    #[test]
+    fn replace_animation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let border = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+
+        ui.animate(border, TweenProperty::Opacity(0.0), 1.0, Easing::Linear);
+        ui.update(screen_size, 0.5);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(border).opacity(), Some(0.5));
+
+        // New animation of the same property starts from the current value.
+        ui.animate(border, TweenProperty::Opacity(1.0), 1.0, Easing::Linear);
+        assert_eq!(ui.tweens.len(), 1);
+        ui.update(screen_size, 0.5);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(border).opacity(), Some(0.75));
+    }
+}
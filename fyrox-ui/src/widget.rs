@@ -2,10 +2,11 @@ use crate::{
     brush::Brush,
     core::{algebra::Vector2, math::Rect, pool::Handle},
     define_constructor,
-    message::{CursorIcon, KeyCode, MessageDirection, UiMessage},
+    message::{CursorIcon, KeyCode, MessageDirection, UiMessage, WheelDelta},
     HorizontalAlignment, LayoutEvent, MouseButton, MouseState, Thickness, UiNode, UserInterface,
     VerticalAlignment, BRUSH_FOREGROUND, BRUSH_PRIMARY,
 };
+use fxhash::FxHashSet;
 use std::{
     any::Any,
     cell::{Cell, RefCell},
@@ -53,8 +54,78 @@ pub enum WidgetMessage {
     MouseWheel {
         /// Position of cursor.
         pos: Vector2<f32>,
-        /// Amount of lines per mouse wheel turn.
+        /// Vertical scroll amount in lines, positive values mean scrolling up. Could be
+        /// fractional when scrolling is done with a touchpad.
         amount: f32,
+        /// Scroll amount as it was reported by OS. Could be used to get horizontal amount or to
+        /// tell mouse wheel from touchpad.
+        delta: WheelDelta,
+    },
+
+    /// Initiated when user holds a mouse button (or a finger on a touch screen) over widget's
+    /// geometry without moving it for some time. See [`crate::GestureSettings`] for thresholds.
+    ///
+    /// Direction: **From UI**.
+    LongPress {
+        /// Position of cursor.
+        pos: Vector2<f32>,
+        /// A button that was held.
+        button: MouseButton,
+    },
+
+    /// Initiated when user clicks (or taps) twice on a widget's geometry in a short period of
+    /// time. See [`crate::GestureSettings`] for thresholds.
+    ///
+    /// Direction: **From UI**.
+    DoubleClick {
+        /// Position of cursor.
+        pos: Vector2<f32>,
+        /// A button that was clicked.
+        button: MouseButton,
+    },
+
+    /// Initiated when a finger touches a widget's geometry. Every finger has its own id, which
+    /// could be used to track multiple simultaneous touches (for example for pinch gestures).
+    /// The first touch is also reported as left mouse button press for compatibility.
+    ///
+    /// Direction: **From UI**.
+    TouchStarted {
+        /// Id of the touch.
+        id: u64,
+        /// Position of the finger.
+        pos: Vector2<f32>,
+    },
+
+    /// Initiated when a finger moves along the screen. The message is sent to the widget that
+    /// received [`WidgetMessage::TouchStarted`] with the same id, even if the finger left its
+    /// geometry.
+    ///
+    /// Direction: **From UI**.
+    TouchMoved {
+        /// Id of the touch.
+        id: u64,
+        /// Position of the finger.
+        pos: Vector2<f32>,
+    },
+
+    /// Initiated when a finger is lifted from the screen. The message is sent to the widget
+    /// that received [`WidgetMessage::TouchStarted`] with the same id.
+    ///
+    /// Direction: **From UI**.
+    TouchEnded {
+        /// Id of the touch.
+        id: u64,
+        /// Position of the finger.
+        pos: Vector2<f32>,
+    },
+
+    /// Initiated when a touch was interrupted by OS. The message is sent to the widget that
+    /// received [`WidgetMessage::TouchStarted`] with the same id.
+    ///
+    /// Direction: **From UI**.
+    TouchCancelled {
+        /// Id of the touch.
+        id: u64,
     },
 
     /// Initiated when cursor leaves geometry of a widget.
@@ -72,6 +143,13 @@ pub enum WidgetMessage {
     /// Direction: **From/To UI**.
     Text(char),
 
+    /// Initiated when widget is in focus and composition (pre-edit) text of an input method editor
+    /// has changed. Empty string means that the composition has ended. Committed text is sent as
+    /// a sequence of [`WidgetMessage::Text`] messages.
+    ///
+    /// Direction: **From UI**.
+    ImeComposition(String),
+
     /// Initiated when widget is in focus and user presses a button on a keyboard.
     ///
     /// Direction: **From UI**.
@@ -109,7 +187,8 @@ pub enum WidgetMessage {
     LostFocus,
 
     /// A request to make widget topmost. Widget can be made topmost only in the same hierarchy
-    /// level only!
+    /// level only! Z-index has priority, so the widget is drawn on top of its siblings with the
+    /// same or lower z-index only (see [`WidgetMessage::ZIndex`]).
     ///
     /// Direction: **From/To UI**.
     TopMost,
@@ -208,18 +287,47 @@ pub enum WidgetMessage {
     /// of this indirect attachment.
     Column(usize),
 
+    /// A request to set amount of rows of a grid that widget should occupy, starting from its row.
+    /// Zero is treated as one.
+    ///
+    /// Direction: **From/To UI**
+    RowSpan(usize),
+
+    /// A request to set amount of columns of a grid that widget should occupy, starting from its
+    /// column. Zero is treated as one.
+    ///
+    /// Direction: **From/To UI**
+    ColumnSpan(usize),
+
     /// A request to set new margin of widget. Margin could be used to add some free space around widget to make UI look less
     /// dense.
     ///
     /// Direction: **From/To UI**
     Margin(Thickness),
 
+    /// A request to set new padding of widget. Padding is a free space between edges of a container and its
+    /// children, unlike margin it is inside of the widget.
+    ///
+    /// Direction: **From/To UI**
+    Padding(Thickness),
+
     /// A request to set new state hit test visibility. If set to false, widget will become "non-clickable". It is useful for
     /// decorations which should be transparent for mouse events.
     ///
     /// Direction: **From/To UI**
     HitTestVisibility(bool),
 
+    /// A request to change whether a widget could receive focus by directional navigation (see
+    /// [`crate::UserInterface::navigate_focus`]).
+    ///
+    /// Direction: **From/To UI**
+    Focusable(bool),
+
+    /// A request to change whether children of a widget are clipped by its bounds.
+    ///
+    /// Direction: **From/To UI**
+    ClipChildren(bool),
+
     /// A request to set new visibility of a widget. Widget can be either visible or not. Invisible widgets does not take space
     /// in layout pass and collapsed to a point.
     ///
@@ -228,7 +336,8 @@ pub enum WidgetMessage {
 
     /// A request to set new z index of a widget. Z index is used to change drawing order of widgets. Please note that it works
     /// only in same hierarchy level, which means that it is impossible to set z index to 9999 (or similar huge value) to force
-    /// widget to be drawn on top of everything.
+    /// widget to be drawn on top of everything. Children with the same z index keep their order, [`WidgetMessage::TopMost`]
+    /// moves a widget on top of its siblings with the same z index, but never above siblings with higher z index.
     ///
     /// Direction: **From/To UI**
     ZIndex(usize),
@@ -279,10 +388,15 @@ impl WidgetMessage {
     define_constructor!(WidgetMessage:Name => fn name(String), layout: false);
     define_constructor!(WidgetMessage:Row => fn row(usize), layout: false);
     define_constructor!(WidgetMessage:Column => fn column(usize), layout: false);
+    define_constructor!(WidgetMessage:RowSpan => fn row_span(usize), layout: false);
+    define_constructor!(WidgetMessage:ColumnSpan => fn column_span(usize), layout: false);
     define_constructor!(WidgetMessage:Cursor => fn cursor(Option<CursorIcon>), layout: false);
     define_constructor!(WidgetMessage:ZIndex => fn z_index(usize), layout: false);
     define_constructor!(WidgetMessage:HitTestVisibility => fn hit_test_visibility(bool), layout: false);
+    define_constructor!(WidgetMessage:Focusable => fn focusable(bool), layout: false);
+    define_constructor!(WidgetMessage:ClipChildren => fn clip_children(bool), layout: false);
     define_constructor!(WidgetMessage:Margin => fn margin(Thickness), layout: false);
+    define_constructor!(WidgetMessage:Padding => fn padding(Thickness), layout: false);
     define_constructor!(WidgetMessage:MinSize => fn min_size(Vector2<f32>), layout: false);
     define_constructor!(WidgetMessage:MaxSize => fn max_size(Vector2<f32>), layout: false);
     define_constructor!(WidgetMessage:HorizontalAlignment => fn horizontal_alignment(HorizontalAlignment), layout: false);
@@ -295,10 +409,17 @@ impl WidgetMessage {
     define_constructor!(WidgetMessage:MouseDown => fn mouse_down(pos: Vector2<f32>, button: MouseButton), layout: false);
     define_constructor!(WidgetMessage:MouseUp => fn mouse_up(pos: Vector2<f32>, button: MouseButton), layout: false);
     define_constructor!(WidgetMessage:MouseMove => fn mouse_move(pos: Vector2<f32>, state: MouseState), layout: false);
-    define_constructor!(WidgetMessage:MouseWheel => fn mouse_wheel(pos: Vector2<f32>, amount: f32), layout: false);
+    define_constructor!(WidgetMessage:MouseWheel => fn mouse_wheel(pos: Vector2<f32>, amount: f32, delta: WheelDelta), layout: false);
+    define_constructor!(WidgetMessage:LongPress => fn long_press(pos: Vector2<f32>, button: MouseButton), layout: false);
+    define_constructor!(WidgetMessage:DoubleClick => fn double_click(pos: Vector2<f32>, button: MouseButton), layout: false);
+    define_constructor!(WidgetMessage:TouchStarted => fn touch_started(id: u64, pos: Vector2<f32>), layout: false);
+    define_constructor!(WidgetMessage:TouchMoved => fn touch_moved(id: u64, pos: Vector2<f32>), layout: false);
+    define_constructor!(WidgetMessage:TouchEnded => fn touch_ended(id: u64, pos: Vector2<f32>), layout: false);
+    define_constructor!(WidgetMessage:TouchCancelled => fn touch_cancelled(id: u64), layout: false);
     define_constructor!(WidgetMessage:MouseLeave => fn mouse_leave(), layout: false);
     define_constructor!(WidgetMessage:MouseEnter => fn mouse_enter(), layout: false);
     define_constructor!(WidgetMessage:Text => fn text(char), layout: false);
+    define_constructor!(WidgetMessage:ImeComposition => fn ime_composition(String), layout: false);
     define_constructor!(WidgetMessage:KeyDown => fn key_down(KeyCode), layout: false);
     define_constructor!(WidgetMessage:KeyUp => fn key_up(KeyCode), layout: false);
     define_constructor!(WidgetMessage:DragStarted => fn drag_started(Handle<UiNode>), layout: false);
@@ -309,6 +430,7 @@ impl WidgetMessage {
 #[derive(Debug, Clone)]
 pub struct Widget {
     pub(in crate) handle: Handle<UiNode>,
+    pub(in crate) id: u64,
     name: String,
     /// Desired position relative to parent node
     desired_local_position: Vector2<f32>,
@@ -328,12 +450,18 @@ pub struct Widget {
     row: usize,
     /// Index of column to which this node belongs
     column: usize,
+    /// Amount of rows this node occupies
+    row_span: usize,
+    /// Amount of columns this node occupies
+    column_span: usize,
     /// Vertical alignment
     vertical_alignment: VerticalAlignment,
     /// Horizontal alignment
     horizontal_alignment: HorizontalAlignment,
     /// Margin (four sides)
     margin: Thickness,
+    /// Padding (four sides), used by containers to offset children from own edges
+    padding: Thickness,
     /// Current visibility state
     visibility: bool,
     global_visibility: bool,
@@ -341,14 +469,23 @@ pub struct Widget {
     parent: Handle<UiNode>,
     /// Indices of commands in command buffer emitted by the node.
     pub(in crate) command_indices: RefCell<Vec<usize>>,
+    /// Indices of commands emitted by the node on previous frame.
+    pub(in crate) prev_command_indices: RefCell<Vec<usize>>,
+    /// Index of the frame the node was drawn last time with its screen and clip bounds at that
+    /// moment. Commands of the node could be reused only if nothing of this was changed.
+    pub(in crate) last_draw: Cell<(u64, Rect<f32>, Rect<f32>)>,
+    visual_valid: Cell<bool>,
     pub(in crate) is_mouse_directly_over: bool,
     hit_test_visibility: bool,
+    focusable: bool,
+    clip_children: bool,
     z_index: usize,
     allow_drag: bool,
     allow_drop: bool,
     pub user_data: Option<Rc<dyn Any>>,
     draw_on_top: bool,
     enabled: bool,
+    global_enabled: bool,
     cursor: Option<CursorIcon>,
     opacity: Option<f32>,
     tooltip: Handle<UiNode>,
@@ -372,14 +509,26 @@ pub struct Widget {
     pub(in crate) actual_size: Cell<Vector2<f32>>,
     pub(in crate) prev_global_visibility: bool,
     pub(in crate) clip_bounds: Cell<Rect<f32>>,
+    /// Whether the widget is completely outside of clip bounds of its ancestors.
+    pub(in crate) is_fully_clipped: Cell<bool>,
 }
 
 impl Widget {
+    /// Returns own handle of the widget. The handle is assigned when the widget is added to the
+    /// user interface by [`UserInterface::add_node`], before that it is [`Handle::NONE`].
     #[inline]
     pub fn handle(&self) -> Handle<UiNode> {
         self.handle
     }
 
+    /// Returns unique id of the widget. The id is assigned when the widget is added to the user
+    /// interface and, unlike the handle, it is never reused by other widgets of the same user
+    /// interface. Zero means that the widget was not added to a user interface yet.
+    #[inline]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     #[inline]
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -468,6 +617,18 @@ impl Widget {
         self.hit_test_visibility
     }
 
+    /// Returns `true` if the widget could receive focus by directional navigation.
+    #[inline]
+    pub fn is_focusable(&self) -> bool {
+        self.focusable
+    }
+
+    /// Returns `true` if children of the widget are clipped by its bounds.
+    #[inline]
+    pub fn clip_children(&self) -> bool {
+        self.clip_children
+    }
+
     #[inline]
     pub fn set_max_size(&mut self, value: Vector2<f32>) -> &mut Self {
         self.max_size = value;
@@ -569,6 +730,12 @@ impl Widget {
         }
     }
 
+    #[inline]
+    pub(in crate) fn add_children(&mut self, children: &[Handle<UiNode>]) {
+        self.invalidate_layout();
+        self.children.extend_from_slice(children);
+    }
+
     #[inline(always)]
     pub fn children(&self) -> &[Handle<UiNode>] {
         &self.children
@@ -588,6 +755,12 @@ impl Widget {
         }
     }
 
+    #[inline]
+    pub(in crate) fn remove_children(&mut self, children: &FxHashSet<Handle<UiNode>>) {
+        self.children.retain(|h| !children.contains(h));
+        self.invalidate_layout();
+    }
+
     #[inline]
     pub fn parent(&self) -> Handle<UiNode> {
         self.parent
@@ -614,6 +787,30 @@ impl Widget {
         self.row
     }
 
+    #[inline]
+    pub fn set_row_span(&mut self, row_span: usize) -> &mut Self {
+        self.row_span = row_span.max(1);
+        self
+    }
+
+    /// Returns amount of rows of a grid the widget occupies, it is always at least one.
+    #[inline]
+    pub fn row_span(&self) -> usize {
+        self.row_span
+    }
+
+    #[inline]
+    pub fn set_column_span(&mut self, column_span: usize) -> &mut Self {
+        self.column_span = column_span.max(1);
+        self
+    }
+
+    /// Returns amount of columns of a grid the widget occupies, it is always at least one.
+    #[inline]
+    pub fn column_span(&self) -> usize {
+        self.column_span
+    }
+
     #[inline]
     pub fn desired_size(&self) -> Vector2<f32> {
         self.desired_size.get()
@@ -725,15 +922,37 @@ impl Widget {
                             self.invalidate_layout();
                         }
                     }
+                    &WidgetMessage::RowSpan(row_span) => {
+                        if self.row_span != row_span.max(1) {
+                            self.set_row_span(row_span);
+                            self.invalidate_layout();
+                        }
+                    }
+                    &WidgetMessage::ColumnSpan(column_span) => {
+                        if self.column_span != column_span.max(1) {
+                            self.set_column_span(column_span);
+                            self.invalidate_layout();
+                        }
+                    }
                     &WidgetMessage::Margin(margin) => {
                         if self.margin != margin {
                             self.margin = margin;
                             self.invalidate_layout();
                         }
                     }
+                    &WidgetMessage::Padding(padding) => {
+                        if self.padding != padding {
+                            self.padding = padding;
+                            self.invalidate_layout();
+                        }
+                    }
                     WidgetMessage::HitTestVisibility(hit_test_visibility) => {
                         self.hit_test_visibility = *hit_test_visibility
                     }
+                    &WidgetMessage::Focusable(focusable) => self.focusable = focusable,
+                    &WidgetMessage::ClipChildren(clip_children) => {
+                        self.clip_children = clip_children
+                    }
                     &WidgetMessage::Visibility(visibility) => {
                         self.set_visibility(visibility);
                     }
@@ -797,6 +1016,19 @@ impl Widget {
         self.margin
     }
 
+    #[inline]
+    pub fn set_padding(&mut self, padding: Thickness) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns padding of the widget. Containers (grid, stack panel, canvas) shrink space for
+    /// their children by the padding, children margins are applied on top of it.
+    #[inline]
+    pub fn padding(&self) -> Thickness {
+        self.padding
+    }
+
     #[inline]
     pub fn measure_override(
         &self,
@@ -899,6 +1131,19 @@ impl Widget {
         self.enabled
     }
 
+    /// Returns `true` if the widget and all its ancestors are enabled. The value is calculated
+    /// in [`UserInterface::update`], so it may be outdated right after [`WidgetMessage::Enabled`]
+    /// was processed.
+    #[inline]
+    pub fn is_globally_enabled(&self) -> bool {
+        self.global_enabled
+    }
+
+    #[inline]
+    pub(in crate) fn set_global_enabled(&mut self, value: bool) {
+        self.global_enabled = value;
+    }
+
     #[inline]
     pub fn set_cursor(&mut self, cursor: Option<CursorIcon>) {
         self.cursor = cursor;
@@ -919,6 +1164,26 @@ impl Widget {
         self.clip_bounds.get()
     }
 
+    /// Forces the widget to be drawn again on next frame. Draw commands of a widget are reused
+    /// between frames while it does not receive messages with
+    /// [`crate::message::MessageDirection::ToWidget`] direction and its bounds stay the same, so
+    /// custom widgets that change their look in any other way (for example in response to input
+    /// or in [`crate::Control::update`]) must call this method.
+    #[inline]
+    pub fn invalidate_visual(&self) {
+        self.visual_valid.set(false);
+    }
+
+    #[inline]
+    pub(in crate) fn is_visual_valid(&self) -> bool {
+        self.visual_valid.get()
+    }
+
+    #[inline]
+    pub(in crate) fn validate_visual(&self) {
+        self.visual_valid.set(true);
+    }
+
     #[inline]
     pub fn set_opacity(&mut self, opacity: Option<f32>) -> &mut Self {
         self.opacity = opacity;
@@ -997,9 +1262,16 @@ pub struct WidgetBuilder {
     pub foreground: Option<Brush>,
     pub row: usize,
     pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
     pub margin: Thickness,
+    pub padding: Thickness,
     pub children: Vec<Handle<UiNode>>,
     pub is_hit_test_visible: bool,
+    /// `None` means that builders of particular widgets decide, interactive widgets (buttons,
+    /// text boxes) are focusable, other widgets are not.
+    pub focusable: Option<bool>,
+    pub clip_children: bool,
     pub visibility: bool,
     pub z_index: usize,
     pub allow_drag: bool,
@@ -1036,10 +1308,15 @@ impl WidgetBuilder {
             foreground: None,
             row: 0,
             column: 0,
+            row_span: 1,
+            column_span: 1,
             margin: Thickness::zero(),
+            padding: Thickness::zero(),
             desired_position: Vector2::default(),
             children: Vec::new(),
             is_hit_test_visible: true,
+            focusable: None,
+            clip_children: true,
             visibility: true,
             z_index: 0,
             allow_drag: false,
@@ -1122,11 +1399,30 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets amount of rows of a grid the widget should occupy, starting from its row. Span that
+    /// goes past the last row of a grid is clamped.
+    pub fn with_row_span(mut self, row_span: usize) -> Self {
+        self.row_span = row_span.max(1);
+        self
+    }
+
+    /// Sets amount of columns of a grid the widget should occupy, starting from its column. Span
+    /// that goes past the last column of a grid is clamped.
+    pub fn with_column_span(mut self, column_span: usize) -> Self {
+        self.column_span = column_span.max(1);
+        self
+    }
+
     pub fn with_margin(mut self, margin: Thickness) -> Self {
         self.margin = margin;
         self
     }
 
+    pub fn with_padding(mut self, padding: Thickness) -> Self {
+        self.padding = padding;
+        self
+    }
+
     pub fn with_desired_position(mut self, desired_position: Vector2<f32>) -> Self {
         self.desired_position = desired_position;
         self
@@ -1168,6 +1464,23 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets whether the widget could receive focus by directional navigation (for example using
+    /// D-pad of a gamepad) or by Tab key. See [`crate::UserInterface::navigate_focus`] and
+    /// [`crate::UserInterface::focus_next`].
+    pub fn with_focusable(mut self, focusable: bool) -> Self {
+        self.focusable = Some(focusable);
+        self
+    }
+
+    /// Sets whether children of the widget should be clipped by its bounds. Disabling clipping
+    /// allows children to intentionally overflow the widget, for example a notification badge
+    /// at a corner of a button. Children are still clipped by the ancestors of the widget.
+    /// Default is `true`.
+    pub fn with_clip_children(mut self, clip_children: bool) -> Self {
+        self.clip_children = clip_children;
+        self
+    }
+
     pub fn with_visibility(mut self, visibility: bool) -> Self {
         self.visibility = visibility;
         self
@@ -1193,6 +1506,9 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets opacity of the widget and its whole subtree. Opacities of nested widgets are
+    /// multiplied, so a widget with opacity `0.5` inside a widget with opacity `0.5` is drawn
+    /// with opacity `0.25`.
     pub fn with_opacity(mut self, opacity: Option<f32>) -> Self {
         self.opacity = opacity;
         self
@@ -1227,6 +1543,7 @@ impl WidgetBuilder {
     pub fn build(self) -> Widget {
         Widget {
             handle: Default::default(),
+            id: 0,
             name: self.name,
             desired_local_position: self.desired_position,
             width: self.width,
@@ -1243,19 +1560,27 @@ impl WidgetBuilder {
             foreground: self.foreground.unwrap_or_else(|| BRUSH_FOREGROUND.clone()),
             row: self.row,
             column: self.column,
+            row_span: self.row_span.max(1),
+            column_span: self.column_span.max(1),
             vertical_alignment: self.vertical_alignment,
             horizontal_alignment: self.horizontal_alignment,
             margin: self.margin,
+            padding: self.padding,
             visibility: self.visibility,
             global_visibility: true,
             prev_global_visibility: false,
             children: self.children,
             parent: Handle::NONE,
             command_indices: Default::default(),
+            prev_command_indices: Default::default(),
+            last_draw: Default::default(),
+            visual_valid: Cell::new(false),
             is_mouse_directly_over: false,
             measure_valid: Cell::new(false),
             arrange_valid: Cell::new(false),
             hit_test_visibility: self.is_hit_test_visible,
+            focusable: self.focusable.unwrap_or(false),
+            clip_children: self.clip_children,
             prev_measure: Default::default(),
             prev_arrange: Default::default(),
             z_index: self.z_index,
@@ -1264,8 +1589,10 @@ impl WidgetBuilder {
             user_data: self.user_data.clone(),
             draw_on_top: self.draw_on_top,
             enabled: self.enabled,
+            global_enabled: self.enabled,
             cursor: self.cursor,
             clip_bounds: Cell::new(Default::default()),
+            is_fully_clipped: Cell::new(false),
             opacity: self.opacity,
             tooltip: self.tooltip,
             tooltip_time: self.tooltip_time,
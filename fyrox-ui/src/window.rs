@@ -321,7 +321,17 @@ impl Control for Window {
                     WidgetMessage::MouseMove { pos, .. } => {
                         if self.is_dragging {
                             self.drag_delta = *pos - self.mouse_click_pos;
-                            let new_pos = self.initial_position + self.drag_delta;
+                            let mut new_pos = self.initial_position + self.drag_delta;
+
+                            // Keep the header on screen, otherwise the window could be dragged
+                            // away to the place where it cannot be grabbed anymore.
+                            if self.parent() == ui.root() {
+                                let header_size = ui.node(self.header).actual_size();
+                                let max = (ui.screen_size() - header_size)
+                                    .per_component_max(&Vector2::default());
+                                new_pos = new_pos.per_component_clamp(&Vector2::default(), &max);
+                            }
+
                             ui.send_message(WindowMessage::move_to(
                                 self.handle(),
                                 MessageDirection::ToWidget,
@@ -869,3 +879,43 @@ impl<'a> WindowBuilder {
         handle
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        message::{MessageDirection, MouseButton},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{Window, WindowBuilder},
+        UserInterface,
+    };
+
+    #[test]
+    fn header_stays_on_screen() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(200.0).with_height(100.0))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        let header = ui.node(window).cast::<Window>().unwrap().header;
+        ui.send_message(WidgetMessage::mouse_down(
+            header,
+            MessageDirection::FromWidget,
+            Vector2::new(10.0, 10.0),
+            MouseButton::Left,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.send_message(WidgetMessage::mouse_move(
+            header,
+            MessageDirection::FromWidget,
+            Vector2::new(-500.0, 5000.0),
+            Default::default(),
+        ));
+        while ui.poll_message().is_some() {}
+
+        let header_height = ui.node(header).actual_size().y;
+        let position = ui.node(window).desired_local_position();
+        assert_eq!(position, Vector2::new(0.0, screen_size.y - header_height));
+    }
+}
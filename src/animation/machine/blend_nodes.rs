@@ -1,6 +1,8 @@
 use crate::{
     animation::{
-        machine::{EvaluatePose, Parameter, ParameterContainer, PoseNode, PoseWeight},
+        machine::{
+            EvalDebugInfo, EvaluatePose, Parameter, ParameterContainer, PoseNode, PoseWeight,
+        },
         AnimationContainer, AnimationPose,
     },
     core::{
@@ -101,16 +103,7 @@ impl EvaluatePose for BlendAnimations {
     ) -> Ref<AnimationPose> {
         self.output_pose.borrow_mut().reset();
         for blend_pose in self.pose_sources.iter() {
-            let weight = match blend_pose.weight {
-                PoseWeight::Constant(value) => value,
-                PoseWeight::Parameter(ref param_id) => {
-                    if let Some(Parameter::Weight(weight)) = params.get(param_id) {
-                        *weight
-                    } else {
-                        0.0
-                    }
-                }
-            };
+            let weight = blend_pose.weight.resolve(params);
 
             let pose_source =
                 nodes[blend_pose.pose_source].eval_pose(nodes, params, animations, dt);
@@ -120,6 +113,106 @@ impl EvaluatePose for BlendAnimations {
         }
         self.output_pose.borrow()
     }
+
+    fn collect_debug_info(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        contribution: f32,
+        info: &mut EvalDebugInfo,
+    ) {
+        for blend_pose in self.pose_sources.iter() {
+            let weight = blend_pose.weight.resolve(params);
+            info.add_node(nodes, params, blend_pose.pose_source, contribution * weight);
+        }
+    }
+}
+
+/// Animation node that adds an additive pose on top of a base pose. Additive pose is defined
+/// relative to a reference pose, so only the difference between them is applied. It could be
+/// used to add aim or lean offsets on top of locomotion. Weight could be parametrized, weight
+/// of `0.0` yields exactly the base pose.
+#[derive(Default)]
+pub struct AdditiveBlend {
+    base: Handle<PoseNode>,
+    additive: Handle<PoseNode>,
+    reference: Handle<PoseNode>,
+    weight: PoseWeight,
+    output_pose: RefCell<AnimationPose>,
+}
+
+impl AdditiveBlend {
+    /// Creates new additive blend node.
+    pub fn new(
+        base: Handle<PoseNode>,
+        additive: Handle<PoseNode>,
+        reference: Handle<PoseNode>,
+        weight: PoseWeight,
+    ) -> Self {
+        Self {
+            base,
+            additive,
+            reference,
+            weight,
+            output_pose: Default::default(),
+        }
+    }
+}
+
+impl Visit for AdditiveBlend {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.base.visit("Base", visitor)?;
+        self.additive.visit("Additive", visitor)?;
+        self.reference.visit("Reference", visitor)?;
+        self.weight.visit("Weight", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl EvaluatePose for AdditiveBlend {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer,
+        dt: f32,
+    ) -> Ref<AnimationPose> {
+        let weight = self.weight.resolve(params);
+
+        nodes[self.base]
+            .eval_pose(nodes, params, animations, dt)
+            .clone_into(&mut self.output_pose.borrow_mut());
+
+        // Same additive and reference poses give zero difference.
+        if self.additive != self.reference {
+            let additive = nodes[self.additive].eval_pose(nodes, params, animations, dt);
+            let reference = nodes[self.reference].eval_pose(nodes, params, animations, dt);
+            self.output_pose
+                .borrow_mut()
+                .add_additive(&additive, &reference, weight);
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn collect_debug_info(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        contribution: f32,
+        info: &mut EvalDebugInfo,
+    ) {
+        let weight = self.weight.resolve(params);
+        info.add_node(nodes, params, self.base, contribution);
+        // Reference pose is subtracted from additive pose with the same weight.
+        if self.additive != self.reference {
+            info.add_node(nodes, params, self.additive, contribution * weight);
+            info.add_node(nodes, params, self.reference, contribution * weight);
+        }
+    }
 }
 
 #[derive(Default)]
@@ -158,6 +251,15 @@ impl BlendAnimationsByIndex {
             blend_time: Cell::new(0.0),
         }
     }
+
+    pub(in crate) fn blend_state(&self) -> (Option<u32>, f32) {
+        (self.prev_index.get(), self.blend_time.get())
+    }
+
+    pub(in crate) fn set_blend_state(&self, prev_index: Option<u32>, blend_time: f32) {
+        self.prev_index.set(prev_index);
+        self.blend_time.set(blend_time);
+    }
 }
 
 impl Visit for BlendAnimationsByIndex {
@@ -228,4 +330,35 @@ impl EvaluatePose for BlendAnimationsByIndex {
 
         self.output_pose.borrow()
     }
+
+    fn collect_debug_info(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        contribution: f32,
+        info: &mut EvalDebugInfo,
+    ) {
+        if let Some(&Parameter::Index(current_index)) = params.get(&self.index_parameter) {
+            let current_input = &self.inputs[current_index as usize];
+
+            match self.prev_index.get() {
+                Some(prev_index) if prev_index != current_index => {
+                    let interpolator = self.blend_time.get() / current_input.blend_time;
+                    info.add_node(
+                        nodes,
+                        params,
+                        self.inputs[prev_index as usize].pose_source,
+                        contribution * (1.0 - interpolator),
+                    );
+                    info.add_node(
+                        nodes,
+                        params,
+                        current_input.pose_source,
+                        contribution * interpolator,
+                    );
+                }
+                _ => info.add_node(nodes, params, current_input.pose_source, contribution),
+            }
+        }
+    }
 }
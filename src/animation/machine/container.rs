@@ -0,0 +1,284 @@
+//! Container for multiple animation blending state machines of a single character.
+//!
+//! A character often needs several machines at once - for example one for locomotion, one for
+//! facial animation and one for weapon handling. [`MachineContainer`] owns such machines as
+//! layers, evaluates them together and composes their poses into a single final pose. Each layer
+//! has a mask - a set of nodes the layer controls, so locomotion machine could control lower body
+//! and weapon machine - upper body.
+//!
+//! ```no_run
+//! use fyrox::{
+//!     animation::{
+//!         machine::{container::{MachineContainer, MachineLayer}, Machine, Parameter},
+//!         AnimationContainer,
+//!     },
+//!     core::pool::Handle,
+//!     scene::graph::Graph,
+//! };
+//!
+//! fn update(graph: &mut Graph, animations: &AnimationContainer, dt: f32) {
+//!     // Assume that these are correct handles of nodes of upper body.
+//!     let spine = Handle::NONE;
+//!     let arm = Handle::NONE;
+//!
+//!     let mut container = MachineContainer::new();
+//!     let locomotion = container.add(MachineLayer::new(Machine::new()));
+//!     let weapon = container.add(MachineLayer::new(Machine::new()).with_mask(vec![spine, arm]));
+//!
+//!     container
+//!         .machine_mut(weapon)
+//!         .set_parameter("Aim", Parameter::Rule(true));
+//!
+//!     container.evaluate_pose(animations, dt).apply(graph);
+//! }
+//! ```
+
+use crate::{
+    animation::{machine::Machine, AnimationContainer, AnimationPose},
+    core::{
+        pool::{Handle, Pool, PoolIterator, PoolIteratorMut},
+        visitor::prelude::*,
+    },
+    scene::{graph::Graph, node::Node},
+};
+use fxhash::FxHashSet;
+use std::ops::{Index, IndexMut};
+
+/// A machine of a [`MachineContainer`] with a set of nodes it controls.
+#[derive(Default)]
+pub struct MachineLayer {
+    machine: Machine,
+    // Empty mask means that the layer controls every node.
+    mask: FxHashSet<Handle<Node>>,
+}
+
+impl MachineLayer {
+    /// Creates new layer that controls every node of its machine's pose.
+    pub fn new(machine: Machine) -> Self {
+        Self {
+            machine,
+            mask: Default::default(),
+        }
+    }
+
+    /// Restricts the layer to given nodes, local poses of other nodes will be ignored. Empty
+    /// mask means that the layer controls every node.
+    pub fn with_mask(mut self, mask: Vec<Handle<Node>>) -> Self {
+        self.set_mask(mask);
+        self
+    }
+
+    pub fn set_mask(&mut self, mask: Vec<Handle<Node>>) {
+        self.mask = mask.into_iter().collect();
+    }
+
+    /// Checks whether the layer controls given node.
+    pub fn is_affecting(&self, node: Handle<Node>) -> bool {
+        self.mask.is_empty() || self.mask.contains(&node)
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    pub fn machine_mut(&mut self) -> &mut Machine {
+        &mut self.machine
+    }
+}
+
+impl Visit for MachineLayer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.machine.visit("Machine", visitor)?;
+
+        let mut mask = self.mask.iter().cloned().collect::<Vec<_>>();
+        mask.visit("Mask", visitor)?;
+        if visitor.is_reading() {
+            self.set_mask(mask);
+        }
+
+        visitor.leave_region()
+    }
+}
+
+/// See module docs.
+#[derive(Default)]
+pub struct MachineContainer {
+    layers: Pool<MachineLayer>,
+    final_pose: AnimationPose,
+}
+
+impl MachineContainer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn add(&mut self, layer: MachineLayer) -> Handle<MachineLayer> {
+        self.layers.spawn(layer)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, handle: Handle<MachineLayer>) -> MachineLayer {
+        self.layers.free(handle)
+    }
+
+    #[inline]
+    pub fn get(&self, handle: Handle<MachineLayer>) -> &MachineLayer {
+        self.layers.borrow(handle)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, handle: Handle<MachineLayer>) -> &mut MachineLayer {
+        self.layers.borrow_mut(handle)
+    }
+
+    /// Returns a reference to a layer, or `None` if the handle is invalid.
+    #[inline]
+    pub fn try_get(&self, handle: Handle<MachineLayer>) -> Option<&MachineLayer> {
+        self.layers.try_borrow(handle)
+    }
+
+    /// Shortcut for `container.get(handle).machine()`.
+    #[inline]
+    pub fn machine(&self, handle: Handle<MachineLayer>) -> &Machine {
+        &self.layers[handle].machine
+    }
+
+    /// Shortcut for `container.get_mut(handle).machine_mut()`, useful to set parameters of a
+    /// machine.
+    #[inline]
+    pub fn machine_mut(&mut self, handle: Handle<MachineLayer>) -> &mut Machine {
+        &mut self.layers[handle].machine
+    }
+
+    #[inline]
+    pub fn iter(&self) -> PoolIterator<MachineLayer> {
+        self.layers.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> PoolIteratorMut<MachineLayer> {
+        self.layers.iter_mut()
+    }
+
+    /// Evaluates every machine and composes their poses into the final pose. Layers are composed
+    /// in order of their indices in the container: local pose of a node from a layer replaces
+    /// local pose of the same node from previous layers.
+    pub fn evaluate_pose(&mut self, animations: &AnimationContainer, dt: f32) -> &AnimationPose {
+        self.final_pose.reset();
+
+        for layer in self.layers.iter_mut() {
+            let pose = layer.machine.evaluate_pose(animations, dt);
+            for (&node, local_pose) in pose.local_poses.iter() {
+                if layer.mask.is_empty() || layer.mask.contains(&node) {
+                    self.final_pose.add_local_pose(local_pose.clone());
+                }
+            }
+        }
+
+        &self.final_pose
+    }
+
+    /// Returns the pose composed during last [`Self::evaluate_pose`] call.
+    pub fn pose(&self) -> &AnimationPose {
+        &self.final_pose
+    }
+
+    /// Writes the pose composed during last [`Self::evaluate_pose`] call to the graph.
+    pub fn apply(&self, graph: &mut Graph) {
+        self.final_pose.apply(graph)
+    }
+}
+
+impl Visit for MachineContainer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        visitor.enter_region(name)?;
+
+        self.layers.visit("Layers", visitor)?;
+
+        visitor.leave_region()
+    }
+}
+
+impl Index<Handle<MachineLayer>> for MachineContainer {
+    type Output = MachineLayer;
+
+    fn index(&self, index: Handle<MachineLayer>) -> &Self::Output {
+        &self.layers[index]
+    }
+}
+
+impl IndexMut<Handle<MachineLayer>> for MachineContainer {
+    fn index_mut(&mut self, index: Handle<MachineLayer>) -> &mut Self::Output {
+        &mut self.layers[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{
+            machine::{
+                container::{MachineContainer, MachineLayer},
+                Machine, PoseNode, State,
+            },
+            Animation, AnimationContainer, LocalPose,
+        },
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::Handle,
+        },
+        scene::node::Node,
+    };
+
+    fn make_machine(
+        animations: &mut AnimationContainer,
+        nodes: &[Handle<Node>],
+        x: f32,
+    ) -> Machine {
+        let mut animation = Animation::default();
+        for &node in nodes {
+            animation.pose.add_local_pose(LocalPose::new(
+                node,
+                Vector3::new(x, 0.0, 0.0),
+                UnitQuaternion::identity(),
+                Vector3::new(1.0, 1.0, 1.0),
+            ));
+        }
+        let animation = animations.add(animation);
+
+        let mut machine = Machine::new();
+        let play = machine.add_node(PoseNode::make_play_animation(animation));
+        let state = machine.add_state(State::new("State", play));
+        machine.set_entry_state(state);
+        machine
+    }
+
+    #[test]
+    fn masked_layers() {
+        let legs = Handle::<Node>::new(1, 1);
+        let arms = Handle::<Node>::new(2, 1);
+
+        let mut animations = AnimationContainer::new();
+        let mut container = MachineContainer::new();
+        container.add(MachineLayer::new(make_machine(
+            &mut animations,
+            &[legs, arms],
+            1.0,
+        )));
+        let upper = container.add(
+            MachineLayer::new(make_machine(&mut animations, &[legs, arms], 2.0))
+                .with_mask(vec![arms]),
+        );
+
+        let pose = container.evaluate_pose(&animations, 0.0);
+        assert_eq!(pose.local_poses[&legs].position().x, 1.0);
+        assert_eq!(pose.local_poses[&arms].position().x, 2.0);
+
+        assert!(container[upper].is_affecting(arms));
+        assert!(!container[upper].is_affecting(legs));
+        assert!(container.machine(upper).active_state().is_some());
+    }
+}
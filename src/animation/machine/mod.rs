@@ -84,27 +84,30 @@
 //! You can use multiple machines to animation single model - for example one machine can be for
 //! locomotion and other is for combat. This means that locomotion machine will take control over
 //! lower body and combat machine will control upper body.
+//! [`container::MachineContainer`] could be used to evaluate such machines together and compose
+//! their poses.
 
 use crate::{
     animation::{
         machine::blend_nodes::{
-            BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput,
+            AdditiveBlend, BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput,
         },
         Animation, AnimationContainer, AnimationPose,
     },
     core::{
         pool::{Handle, Pool, PoolIterator},
-        visitor::{Visit, VisitResult, Visitor},
+        visitor::prelude::*,
     },
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashMap;
 use std::{
-    cell::{Ref, RefCell},
+    cell::{Cell, Ref, RefCell},
     collections::VecDeque,
 };
 
 pub mod blend_nodes;
+pub mod container;
 
 /// Specific machine event.
 pub enum Event {
@@ -123,6 +126,8 @@ pub enum Event {
 pub struct PlayAnimation {
     pub animation: Handle<Animation>,
     output_pose: RefCell<AnimationPose>,
+    // Prevents flooding the log with the same message every frame.
+    missing_animation_reported: Cell<bool>,
 }
 
 impl PlayAnimation {
@@ -131,6 +136,7 @@ impl PlayAnimation {
         Self {
             animation,
             output_pose: Default::default(),
+            missing_animation_reported: Default::default(),
         }
     }
 }
@@ -222,6 +228,19 @@ impl Default for PoseWeight {
 }
 
 impl PoseWeight {
+    fn resolve(&self, params: &ParameterContainer) -> f32 {
+        match self {
+            PoseWeight::Constant(value) => *value,
+            PoseWeight::Parameter(param_id) => {
+                if let Some(Parameter::Weight(weight)) = params.get(param_id) {
+                    *weight
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
     fn from_id(id: i32) -> Result<Self, String> {
         match id {
             0 => Ok(Self::Parameter(Default::default())),
@@ -267,6 +286,9 @@ pub enum PoseNode {
 
     /// See docs for `BlendAnimationsByIndex`.
     BlendAnimationsByIndex(BlendAnimationsByIndex),
+
+    /// See docs for `AdditiveBlend`.
+    AdditiveBlend(AdditiveBlend),
 }
 
 impl Default for PoseNode {
@@ -294,11 +316,23 @@ impl PoseNode {
         Self::BlendAnimationsByIndex(BlendAnimationsByIndex::new(index_parameter, inputs))
     }
 
+    /// Creates new node that adds difference between additive and reference poses on top of
+    /// base pose.
+    pub fn make_additive_blend(
+        base: Handle<PoseNode>,
+        additive: Handle<PoseNode>,
+        reference: Handle<PoseNode>,
+        weight: PoseWeight,
+    ) -> Self {
+        Self::AdditiveBlend(AdditiveBlend::new(base, additive, reference, weight))
+    }
+
     fn from_id(id: i32) -> Result<Self, String> {
         match id {
             0 => Ok(Self::PlayAnimation(Default::default())),
             1 => Ok(Self::BlendAnimations(Default::default())),
             2 => Ok(Self::BlendAnimationsByIndex(Default::default())),
+            3 => Ok(Self::AdditiveBlend(Default::default())),
             _ => Err(format!("Invalid pose node id {}", id)),
         }
     }
@@ -308,6 +342,7 @@ impl PoseNode {
             Self::PlayAnimation(_) => 0,
             Self::BlendAnimations(_) => 1,
             Self::BlendAnimationsByIndex(_) => 2,
+            Self::AdditiveBlend(_) => 3,
         }
     }
 }
@@ -318,6 +353,7 @@ macro_rules! static_dispatch {
             PoseNode::PlayAnimation(v) => v.$func($($args),*),
             PoseNode::BlendAnimations(v) => v.$func($($args),*),
             PoseNode::BlendAnimationsByIndex(v) => v.$func($($args),*),
+            PoseNode::AdditiveBlend(v) => v.$func($($args),*),
         }
     };
 }
@@ -352,6 +388,16 @@ trait EvaluatePose {
         animations: &AnimationContainer,
         dt: f32,
     ) -> Ref<AnimationPose>;
+
+    /// Passes contribution of the node to its inputs. Leaf nodes have no inputs.
+    fn collect_debug_info(
+        &self,
+        _nodes: &Pool<PoseNode>,
+        _params: &ParameterContainer,
+        _contribution: f32,
+        _info: &mut EvalDebugInfo,
+    ) {
+    }
 }
 
 impl EvaluatePose for PlayAnimation {
@@ -362,10 +408,25 @@ impl EvaluatePose for PlayAnimation {
         animations: &AnimationContainer,
         _dt: f32,
     ) -> Ref<AnimationPose> {
-        animations
-            .get(self.animation)
-            .get_pose()
-            .clone_into(&mut self.output_pose.borrow_mut());
+        match animations.try_get(self.animation) {
+            Some(animation) => {
+                animation
+                    .get_pose()
+                    .clone_into(&mut self.output_pose.borrow_mut());
+                self.missing_animation_reported.set(false);
+            }
+            None => {
+                // Empty pose does not affect anything, so a bad handle won't break the whole
+                // machine.
+                self.output_pose.borrow_mut().reset();
+                if !self.missing_animation_reported.replace(true) {
+                    Log::warn(format!(
+                        "PlayAnimation node refers to missing animation {}, empty pose is used.",
+                        self.animation
+                    ));
+                }
+            }
+        }
         self.output_pose.borrow()
     }
 }
@@ -380,6 +441,16 @@ impl EvaluatePose for PoseNode {
     ) -> Ref<AnimationPose> {
         static_dispatch!(self, eval_pose, nodes, params, animations, dt)
     }
+
+    fn collect_debug_info(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        contribution: f32,
+        info: &mut EvalDebugInfo,
+    ) {
+        static_dispatch!(self, collect_debug_info, nodes, params, contribution, info)
+    }
 }
 
 impl State {
@@ -455,6 +526,8 @@ impl Visit for Transition {
 }
 
 impl Transition {
+    /// Creates new transition. Zero `time` means instant transition (snap cut), negative
+    /// values are treated as zero.
     pub fn new(
         name: &str,
         src: Handle<State>,
@@ -462,6 +535,16 @@ impl Transition {
         time: f32,
         rule: &str,
     ) -> Transition {
+        let time = if time < 0.0 || time.is_nan() {
+            Log::warn(format!(
+                "Transition {} has invalid time {}, zero will be used instead.",
+                name, time
+            ));
+            0.0
+        } else {
+            time
+        };
+
         Self {
             name: name.to_owned(),
             transition_time: time,
@@ -499,6 +582,13 @@ impl Transition {
     }
 
     fn update(&mut self, dt: f32) {
+        // Instant transition, also prevents division by zero below.
+        if self.transition_time <= f32::EPSILON {
+            self.elapsed_time = self.transition_time;
+            self.blend_factor = 1.0;
+            return;
+        }
+
         self.elapsed_time += dt;
         if self.elapsed_time > self.transition_time {
             self.elapsed_time = self.transition_time;
@@ -523,6 +613,7 @@ pub struct Machine {
     parameters: ParameterContainer,
     events: LimitedEventQueue,
     debug: bool,
+    eval_debug_info: EvalDebugInfo,
 }
 
 struct LimitedEventQueue {
@@ -558,6 +649,86 @@ impl LimitedEventQueue {
     }
 }
 
+/// Weights of pose nodes resolved during last evaluation of a machine, it is recorded only if
+/// debug mode of the machine is enabled (see [`Machine::debug`]). Contribution of a node is the
+/// weight with which its pose ended up in the final pose, it takes into account weights of every
+/// blend node between the node and the root of active state, as well as the blend factor of
+/// active transition. Nodes that do not contribute to the final pose are not listed.
+#[derive(Default, Debug, Clone)]
+pub struct EvalDebugInfo {
+    contributions: FxHashMap<Handle<PoseNode>, f32>,
+}
+
+impl EvalDebugInfo {
+    /// Returns contribution of a node to the final pose.
+    pub fn contribution(&self, node: Handle<PoseNode>) -> Option<f32> {
+        self.contributions.get(&node).cloned()
+    }
+
+    /// Returns contributions of all nodes that were used to evaluate the final pose.
+    pub fn contributions(&self) -> &FxHashMap<Handle<PoseNode>, f32> {
+        &self.contributions
+    }
+
+    fn clear(&mut self) {
+        self.contributions.clear();
+    }
+
+    fn add_node(
+        &mut self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        node: Handle<PoseNode>,
+        contribution: f32,
+    ) {
+        *self.contributions.entry(node).or_default() += contribution;
+
+        if let Some(node) = nodes.try_borrow(node) {
+            node.collect_debug_info(nodes, params, contribution, self);
+        }
+    }
+}
+
+/// Runtime state of a [`BlendAnimationsByIndex`] node.
+#[derive(Default, Clone, Visit)]
+pub struct IndexedBlendRuntimeState {
+    node: Handle<PoseNode>,
+    prev_index: Option<u32>,
+    blend_time: f32,
+}
+
+/// A snapshot of transient state of a machine: active state, progress of active transition,
+/// parameters and blending progress of nodes. Unlike [`Machine`] itself, it does not contain
+/// structure of the machine, so it is cheap to capture and it could be saved separately (for
+/// example in a save game) and restored later on the same machine. Keep in mind that playback
+/// positions of animations are stored in animations, not in the machine.
+#[derive(Default, Clone, Visit)]
+pub struct MachineRuntimeState {
+    active_state: Handle<State>,
+    active_transition: Handle<Transition>,
+    transition_elapsed_time: f32,
+    transition_blend_factor: f32,
+    parameters: ParameterContainer,
+    indexed_blends: Vec<IndexedBlendRuntimeState>,
+}
+
+impl MachineRuntimeState {
+    /// Returns active state at the moment of capture.
+    pub fn active_state(&self) -> Handle<State> {
+        self.active_state
+    }
+
+    /// Returns active transition at the moment of capture.
+    pub fn active_transition(&self) -> Handle<Transition> {
+        self.active_transition
+    }
+
+    /// Returns elapsed time of active transition at the moment of capture.
+    pub fn transition_elapsed_time(&self) -> f32 {
+        self.transition_elapsed_time
+    }
+}
+
 impl Machine {
     pub fn new() -> Self {
         Self {
@@ -571,6 +742,7 @@ impl Machine {
             parameters: Default::default(),
             events: LimitedEventQueue::new(2048),
             debug: false,
+            eval_debug_info: Default::default(),
         }
     }
 
@@ -596,8 +768,39 @@ impl Machine {
         self.entry_state = entry_state;
     }
 
+    /// Enables or disables debug mode. In debug mode the machine writes state changes to the log
+    /// and records weights of pose nodes on each evaluation, see [`Self::last_eval_debug`].
     pub fn debug(&mut self, state: bool) {
         self.debug = state;
+        if !state {
+            self.eval_debug_info.clear();
+        }
+    }
+
+    /// Returns weights of pose nodes resolved during last evaluation. Info is recorded only in
+    /// debug mode, otherwise it is empty.
+    pub fn last_eval_debug(&self) -> &EvalDebugInfo {
+        &self.eval_debug_info
+    }
+
+    fn collect_debug_info(&mut self) {
+        self.eval_debug_info.clear();
+
+        let mut roots = Vec::new();
+        if let Some(transition) = self.transitions.try_borrow(self.active_transition) {
+            roots.push((
+                self.states[transition.source].root,
+                1.0 - transition.blend_factor,
+            ));
+            roots.push((self.states[transition.dest].root, transition.blend_factor));
+        } else if let Some(state) = self.states.try_borrow(self.active_state) {
+            roots.push((state.root, 1.0));
+        }
+
+        for (root, weight) in roots {
+            self.eval_debug_info
+                .add_node(&self.nodes, &self.parameters, root, weight);
+        }
     }
 
     pub fn add_state(&mut self, state: State) -> Handle<State> {
@@ -632,6 +835,79 @@ impl Machine {
         self.active_state = self.entry_state;
     }
 
+    /// Starts transition from current state to given state, bypassing rules. Existing
+    /// transition between the states is used if there is one, otherwise a transition without
+    /// rule with given `time` is added to the machine (and will be reused later). If the machine
+    /// is in the middle of another transition, the transition is stopped and the new one starts
+    /// from the state that has more influence on current pose. [`Event::ActiveStateChanged`]
+    /// will be emitted once the transition is done.
+    ///
+    /// Returns handle of the started transition or [`Handle::NONE`] if there is nothing to do
+    /// (machine is already in the state) or the state does not exist.
+    pub fn transition_to(&mut self, dest: Handle<State>, time: f32) -> Handle<Transition> {
+        if !self.states.is_valid_handle(dest) {
+            Log::warn(format!(
+                "Unable to transition to state {}, it does not exist!",
+                dest
+            ));
+            return Handle::NONE;
+        }
+
+        let source =
+            if let Some(transition) = self.transitions.try_borrow_mut(self.active_transition) {
+                let source = if transition.blend_factor < 0.5 {
+                    transition.source
+                } else {
+                    transition.dest
+                };
+                transition.reset();
+                self.active_transition = Handle::NONE;
+                source
+            } else {
+                self.active_state
+            };
+
+        if source == dest || source.is_none() {
+            if self.active_state != dest {
+                self.active_state = dest;
+                self.events.push(Event::ActiveStateChanged(dest));
+            }
+            return Handle::NONE;
+        }
+
+        let existing = self
+            .transitions
+            .pair_iter()
+            .find(|(_, t)| t.source == source && t.dest == dest)
+            .map(|(handle, _)| handle);
+        let transition = match existing {
+            Some(transition) => transition,
+            None => {
+                let name = format!("{}->{}", self.states[source].name, self.states[dest].name);
+                self.transitions
+                    .spawn(Transition::new(&name, source, dest, time, ""))
+            }
+        };
+
+        self.events.push(Event::StateLeave(source));
+        self.events.push(Event::StateEnter(dest));
+        if self.debug {
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Forced transition: {} -> {}",
+                    self.states[source].name, self.states[dest].name
+                ),
+            );
+        }
+
+        self.transitions[transition].reset();
+        self.active_state = Handle::NONE;
+        self.active_transition = transition;
+
+        transition
+    }
+
     pub fn nodes(&self) -> PoolIterator<PoseNode> {
         self.nodes.iter()
     }
@@ -644,10 +920,118 @@ impl Machine {
         self.active_transition
     }
 
+    fn active_state_animation<'a>(
+        &self,
+        animations: &'a AnimationContainer,
+    ) -> Option<&'a Animation> {
+        let state = self.states.try_borrow(self.active_state)?;
+        match self.nodes.try_borrow(state.root)? {
+            PoseNode::PlayAnimation(play_animation) => animations.try_get(play_animation.animation),
+            _ => None,
+        }
+    }
+
+    /// Returns playback progress of the active state in `[0; 1]` range. Works only for states
+    /// whose root node is [`PoseNode::PlayAnimation`], `None` is returned for states with blend
+    /// nodes as root or if there is no active state (for example during a transition).
+    pub fn active_state_normalized_time(&self, animations: &AnimationContainer) -> Option<f32> {
+        let animation = self.active_state_animation(animations)?;
+        let length = animation.length();
+        if length > 0.0 {
+            Some((animation.get_time_position() / length).clamp(0.0, 1.0))
+        } else {
+            Some(1.0)
+        }
+    }
+
+    /// Returns amount of time (in seconds) left until the animation of the active state reaches
+    /// its end, speed of the animation is taken into account. Has the same limitations as
+    /// [`Self::active_state_normalized_time`], also returns `None` if the animation is not moving.
+    pub fn active_state_time_remaining(&self, animations: &AnimationContainer) -> Option<f32> {
+        let animation = self.active_state_animation(animations)?;
+        let speed = animation.get_speed();
+        let time_position = animation.get_time_position();
+        if speed > 0.0 {
+            Some((animation.length() - time_position).max(0.0) / speed)
+        } else if speed < 0.0 {
+            Some(time_position.max(0.0) / -speed)
+        } else {
+            None
+        }
+    }
+
     pub fn transitions(&self) -> &Pool<Transition> {
         &self.transitions
     }
 
+    /// Captures runtime state of the machine, see [`MachineRuntimeState`] docs for more info.
+    pub fn capture_runtime_state(&self) -> MachineRuntimeState {
+        let (transition_elapsed_time, transition_blend_factor) = self
+            .transitions
+            .try_borrow(self.active_transition)
+            .map_or((0.0, 0.0), |transition| {
+                (transition.elapsed_time, transition.blend_factor)
+            });
+
+        MachineRuntimeState {
+            active_state: self.active_state,
+            active_transition: self.active_transition,
+            transition_elapsed_time,
+            transition_blend_factor,
+            parameters: self.parameters.clone(),
+            indexed_blends: self
+                .nodes
+                .pair_iter()
+                .filter_map(|(handle, node)| match node {
+                    PoseNode::BlendAnimationsByIndex(blend) => {
+                        let (prev_index, blend_time) = blend.blend_state();
+                        Some(IndexedBlendRuntimeState {
+                            node: handle,
+                            prev_index,
+                            blend_time,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Restores runtime state of the machine previously captured by
+    /// [`Self::capture_runtime_state`]. The state must be captured from the same machine (or
+    /// from a machine with the same structure), entries that refer to missing states,
+    /// transitions or nodes are ignored.
+    pub fn restore_runtime_state(&mut self, state: &MachineRuntimeState) {
+        for transition in self.transitions.iter_mut() {
+            transition.reset();
+        }
+
+        self.active_transition = Handle::NONE;
+        if let Some(transition) = self.transitions.try_borrow_mut(state.active_transition) {
+            transition.elapsed_time = state.transition_elapsed_time;
+            transition.blend_factor = state.transition_blend_factor;
+            self.active_transition = state.active_transition;
+        }
+
+        self.active_state = if self.states.is_valid_handle(state.active_state) {
+            state.active_state
+        } else if self.active_transition.is_some() {
+            Handle::NONE
+        } else {
+            self.entry_state
+        };
+
+        self.parameters = state.parameters.clone();
+
+        for indexed_blend in state.indexed_blends.iter() {
+            if let Some(PoseNode::BlendAnimationsByIndex(blend)) =
+                self.nodes.try_borrow(indexed_blend.node)
+            {
+                blend.set_blend_state(indexed_blend.prev_index, indexed_blend.blend_time);
+            }
+        }
+    }
+
     pub fn evaluate_pose(&mut self, animations: &AnimationContainer, dt: f32) -> &AnimationPose {
         self.final_pose.reset();
 
@@ -698,6 +1082,10 @@ impl Machine {
                 }
             }
 
+            if self.debug {
+                self.collect_debug_info();
+            }
+
             // Double check for active transition because we can have empty machine.
             if self.active_transition.is_some() {
                 let transition = &mut self.transitions[self.active_transition];
@@ -757,3 +1145,225 @@ impl Visit for Machine {
         visitor.leave_region()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{
+            machine::{
+                blend_nodes::BlendPose, Event, Machine, Parameter, PoseNode, State, Transition,
+            },
+            Animation, AnimationContainer, KeyFrame, Track,
+        },
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::Handle,
+        },
+    };
+
+    #[test]
+    fn active_state_time() {
+        let mut animations = AnimationContainer::new();
+        let mut track = Track::new();
+        for &time in [0.0, 2.0].iter() {
+            track.add_key_frame(KeyFrame::new(
+                time,
+                Vector3::default(),
+                Vector3::new(1.0, 1.0, 1.0),
+                UnitQuaternion::default(),
+            ));
+        }
+        let mut animation = Animation::default();
+        animation.add_track(track);
+        animation.set_time_position(0.5);
+        let animation = animations.add(animation);
+        let empty = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let play = machine.add_node(PoseNode::make_play_animation(animation));
+        let play = machine.add_state(State::new("Play", play));
+        let play_empty = machine.add_node(PoseNode::make_play_animation(empty));
+        let play_empty = machine.add_state(State::new("PlayEmpty", play_empty));
+
+        machine.set_entry_state(play);
+        assert_eq!(
+            machine.active_state_normalized_time(&animations),
+            Some(0.25)
+        );
+        assert_eq!(machine.active_state_time_remaining(&animations), Some(1.5));
+        // Backward playback ends at the beginning.
+        animations.get_mut(animation).set_speed(-2.0);
+        assert_eq!(machine.active_state_time_remaining(&animations), Some(0.25));
+        animations.get_mut(animation).set_speed(0.0);
+        assert_eq!(machine.active_state_time_remaining(&animations), None);
+
+        // Zero-length animation is always finished.
+        machine.set_entry_state(play_empty);
+        assert_eq!(machine.active_state_normalized_time(&animations), Some(1.0));
+        assert_eq!(machine.active_state_time_remaining(&animations), Some(0.0));
+
+        // Missing animation.
+        animations.remove(empty);
+        assert_eq!(machine.active_state_normalized_time(&animations), None);
+        assert_eq!(machine.active_state_time_remaining(&animations), None);
+
+        // No active state.
+        machine.set_entry_state(Handle::NONE);
+        assert_eq!(machine.active_state_normalized_time(&animations), None);
+        assert_eq!(machine.active_state_time_remaining(&animations), None);
+    }
+
+    #[test]
+    fn runtime_state() {
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let idle = machine.add_node(PoseNode::make_play_animation(animation));
+        let walk = machine.add_node(PoseNode::make_play_animation(animation));
+        let idle = machine.add_state(State::new("Idle", idle));
+        let walk = machine.add_state(State::new("Walk", walk));
+        let transition =
+            machine.add_transition(Transition::new("Idle->Walk", idle, walk, 1.0, "Walk"));
+        machine.set_entry_state(idle);
+        machine.set_parameter("Walk", Parameter::Rule(true));
+
+        // Start the transition and save in the middle of it.
+        machine.evaluate_pose(&animations, 0.25);
+        let state = machine.capture_runtime_state();
+        assert_eq!(state.active_transition(), transition);
+        assert_eq!(state.transition_elapsed_time(), 0.25);
+
+        // Finish the transition and change the parameters.
+        machine.evaluate_pose(&animations, 1.0);
+        machine.set_parameter("Walk", Parameter::Rule(false));
+        assert_eq!(machine.active_state(), walk);
+
+        machine.restore_runtime_state(&state);
+        assert!(machine.active_state().is_none());
+        assert_eq!(machine.active_transition(), transition);
+        assert_eq!(machine.get_transition(transition).elapsed_time, 0.25);
+        assert!(matches!(
+            machine.parameters.get("Walk"),
+            Some(Parameter::Rule(true))
+        ));
+
+        // Transition continues from restored point.
+        machine.evaluate_pose(&animations, 0.5);
+        assert_eq!(machine.get_transition(transition).elapsed_time, 0.75);
+    }
+
+    #[test]
+    fn instant_transition() {
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let idle = machine.add_node(PoseNode::make_play_animation(animation));
+        let walk = machine.add_node(PoseNode::make_play_animation(animation));
+        let idle = machine.add_state(State::new("Idle", idle));
+        let walk = machine.add_state(State::new("Walk", walk));
+        let transition =
+            machine.add_transition(Transition::new("Idle->Walk", idle, walk, 0.0, "Walk"));
+        machine.set_entry_state(idle);
+        machine.set_parameter("Walk", Parameter::Rule(true));
+
+        machine.evaluate_pose(&animations, 0.1);
+        assert_eq!(machine.active_state(), walk);
+        assert!(machine.active_transition().is_none());
+        assert_eq!(machine.get_transition(transition).blend_factor, 0.0);
+
+        let mut instant = Transition::new("Instant", idle, walk, 0.0, "Walk");
+        instant.update(0.1);
+        assert_eq!(instant.blend_factor, 1.0);
+        assert!(instant.is_done());
+
+        let negative = Transition::new("Negative", idle, walk, -1.0, "Walk");
+        assert_eq!(negative.transition_time(), 0.0);
+    }
+
+    #[test]
+    fn forced_transition() {
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let idle = machine.add_node(PoseNode::make_play_animation(animation));
+        let run = machine.add_node(PoseNode::make_play_animation(animation));
+        let idle = machine.add_state(State::new("Idle", idle));
+        let run = machine.add_state(State::new("Run", run));
+        machine.set_entry_state(idle);
+
+        assert!(machine.transition_to(idle, 1.0).is_none());
+
+        let transition = machine.transition_to(run, 1.0);
+        assert_eq!(machine.active_transition(), transition);
+        assert_eq!(machine.get_transition(transition).transition_time(), 1.0);
+        assert!(machine.active_state().is_none());
+
+        // Go back in the middle - the source state still has more influence, so the machine
+        // just returns to it.
+        machine.evaluate_pose(&animations, 0.25);
+        let back = machine.transition_to(idle, 0.5);
+        assert_ne!(back, transition);
+        assert_eq!(machine.get_transition(transition).elapsed_time, 0.0);
+        assert!(machine.active_transition().is_none());
+        assert_eq!(machine.active_state(), idle);
+
+        // Existing transition is reused.
+        assert_eq!(machine.transition_to(run, 2.0), transition);
+        while machine.pop_event().is_some() {}
+        machine.evaluate_pose(&animations, 0.5);
+        machine.evaluate_pose(&animations, 0.6);
+        assert_eq!(machine.active_state(), run);
+        assert!(matches!(
+            machine.pop_event(),
+            Some(Event::ActiveStateChanged(state)) if state == run
+        ));
+    }
+
+    #[test]
+    fn missing_animation() {
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let idle = machine.add_node(PoseNode::make_play_animation(animation));
+        let idle = machine.add_state(State::new("Idle", idle));
+        machine.set_entry_state(idle);
+
+        animations.remove(animation);
+        assert!(animations.try_get(animation).is_none());
+
+        // Must not panic.
+        machine.evaluate_pose(&animations, 0.1);
+        assert!(machine.active_state_normalized_time(&animations).is_none());
+    }
+
+    #[test]
+    fn eval_debug_info() {
+        let mut animations = AnimationContainer::new();
+        let animation = animations.add(Animation::default());
+
+        let mut machine = Machine::new();
+        let walk = machine.add_node(PoseNode::make_play_animation(animation));
+        let run = machine.add_node(PoseNode::make_play_animation(animation));
+        let blend = machine.add_node(PoseNode::make_blend_animations(vec![
+            BlendPose::with_constant_weight(0.25, walk),
+            BlendPose::with_param_weight("Run", run),
+        ]));
+        machine.add_state(State::new("Locomotion", blend));
+        machine.set_parameter("Run", Parameter::Weight(0.75));
+
+        // Nothing is recorded without debug mode.
+        machine.evaluate_pose(&animations, 0.1);
+        assert!(machine.last_eval_debug().contributions().is_empty());
+
+        machine.debug(true);
+        machine.evaluate_pose(&animations, 0.1);
+        let info = machine.last_eval_debug();
+        assert_eq!(info.contribution(blend), Some(1.0));
+        assert_eq!(info.contribution(walk), Some(0.25));
+        assert_eq!(info.contribution(run), Some(0.75));
+    }
+}
@@ -46,6 +46,45 @@ impl KeyFrame {
     }
 }
 
+impl KeyFrame {
+    fn interpolate(&self, other: &KeyFrame, t: f32) -> KeyFrame {
+        KeyFrame {
+            position: self.position.lerp(&other.position, t),
+            scale: self.scale.lerp(&other.scale, t),
+            rotation: self.rotation.nlerp(&other.rotation, t),
+            time: self.time + (other.time - self.time) * t,
+        }
+    }
+
+    /// Checks if key frame is close enough to other key frame. Tolerance is used for distances
+    /// between positions and scales, and for the angle (in radians) between rotations.
+    fn is_close_to(&self, other: &KeyFrame, tolerance: f32) -> bool {
+        self.position.metric_distance(&other.position) <= tolerance
+            && self.scale.metric_distance(&other.scale) <= tolerance
+            && self.rotation.angle_to(&other.rotation) <= tolerance
+    }
+}
+
+/// Result of animation compression, see [`Animation::compress`] for more info.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompressionReport {
+    /// Total amount of key frames before compression.
+    pub key_frames_before: usize,
+    /// Total amount of key frames after compression.
+    pub key_frames_after: usize,
+}
+
+impl CompressionReport {
+    /// Returns ratio of key frames count before compression to key frames count after it.
+    pub fn ratio(&self) -> f32 {
+        if self.key_frames_after == 0 {
+            1.0
+        } else {
+            self.key_frames_before as f32 / self.key_frames_after as f32
+        }
+    }
+}
+
 impl Default for KeyFrame {
     fn default() -> Self {
         Self {
@@ -256,6 +295,41 @@ impl Track {
     pub fn set_flags(&mut self, flags: PoseEvaluationFlags) {
         self.flags = flags;
     }
+
+    /// Removes key frames that could be restored by interpolation of neighbour key frames within
+    /// given tolerance, first and last key frames are always kept. Returns compression report.
+    pub fn compress(&mut self, tolerance: f32) -> CompressionReport {
+        let key_frames_before = self.frames.len();
+
+        if self.frames.len() > 2 {
+            let mut compressed = vec![self.frames[0]];
+            let mut left = 0;
+            for right in 2..self.frames.len() {
+                // Check whether every key frame between left and right could be interpolated.
+                let left_frame = &self.frames[left];
+                let right_frame = &self.frames[right];
+                let removable = self.frames[(left + 1)..right].iter().all(|frame| {
+                    let t = (frame.time - left_frame.time) / (right_frame.time - left_frame.time);
+                    left_frame
+                        .interpolate(right_frame, t)
+                        .is_close_to(frame, tolerance)
+                });
+
+                if !removable {
+                    left = right - 1;
+                    compressed.push(self.frames[left]);
+                }
+            }
+            compressed.extend(self.frames.last().cloned());
+
+            self.frames = compressed;
+        }
+
+        CompressionReport {
+            key_frames_before,
+            key_frames_after: self.frames.len(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -355,6 +429,22 @@ impl Default for LocalPose {
 }
 
 impl LocalPose {
+    /// Creates new local pose of the given node. Could be used to build poses by hand, for example
+    /// from a pose received over the network.
+    pub fn new(
+        node: Handle<Node>,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        scale: Vector3<f32>,
+    ) -> Self {
+        Self {
+            node,
+            position,
+            scale,
+            rotation,
+        }
+    }
+
     fn weighted_clone(&self, weight: f32) -> Self {
         Self {
             node: self.node,
@@ -370,6 +460,11 @@ impl LocalPose {
         self.scale += other.scale.scale(weight);
     }
 
+    /// Returns handle of a node the pose belongs to.
+    pub fn node(&self) -> Handle<Node> {
+        self.node
+    }
+
     pub fn position(&self) -> Vector3<f32> {
         self.position
     }
@@ -408,7 +503,39 @@ impl AnimationPose {
         }
     }
 
-    fn add_local_pose(&mut self, local_pose: LocalPose) {
+    /// Adds difference between additive and reference poses on top of this pose, scaled by the
+    /// weight. Only nodes that present in all three poses are affected. Weight of `0.0` leaves
+    /// the pose untouched. This is the usual way of layering aim or lean offsets on top of
+    /// locomotion.
+    pub fn add_additive(
+        &mut self,
+        additive: &AnimationPose,
+        reference: &AnimationPose,
+        weight: f32,
+    ) {
+        if weight == 0.0 {
+            return;
+        }
+
+        for (handle, local_pose) in self.local_poses.iter_mut() {
+            if let (Some(additive), Some(reference)) = (
+                additive.local_poses.get(handle),
+                reference.local_poses.get(handle),
+            ) {
+                let position_delta = additive.position - reference.position;
+                let rotation_delta = reference.rotation.inverse() * additive.rotation;
+                let scale_delta = additive.scale.component_div(&reference.scale);
+                let scale_factor = Vector3::new(1.0, 1.0, 1.0).lerp(&scale_delta, weight);
+
+                local_pose.position += position_delta.scale(weight);
+                local_pose.rotation *= UnitQuaternion::identity().nlerp(&rotation_delta, weight);
+                local_pose.scale.component_mul_assign(&scale_factor);
+            }
+        }
+    }
+
+    /// Adds a local pose of a node to the pose, replacing previous local pose of the same node.
+    pub fn add_local_pose(&mut self, local_pose: LocalPose) {
         self.local_poses.insert(local_pose.node, local_pose);
     }
 
@@ -416,18 +543,34 @@ impl AnimationPose {
         self.local_poses.clear();
     }
 
+    /// Writes local transform of every node in the pose to the respective node of the graph. The
+    /// pose does not have to come from an animation, it could be built by hand or received over the
+    /// network. Local poses of missing nodes are skipped with a warning.
     pub fn apply(&self, graph: &mut Graph) {
-        for (node, local_pose) in self.local_poses.iter() {
-            if node.is_none() {
-                Log::writeln(MessageKind::Error, "Invalid node handle found for animation pose, most likely it means that animation retargeting failed!".to_owned());
-            } else {
-                graph[*node]
-                    .local_transform_mut()
-                    .set_position(local_pose.position)
-                    .set_rotation(local_pose.rotation)
-                    .set_scale(local_pose.scale);
-            }
-        }
+        self.apply_with(graph, |node, _, local_pose| {
+            node.local_transform_mut()
+                .set_position(local_pose.position)
+                .set_rotation(local_pose.rotation)
+                .set_scale(local_pose.scale);
+        });
+    }
+
+    /// Blends the pose onto current local transforms of the nodes of the graph. Current local
+    /// transform of a node is used as the "from" pose, so `weight` of `0.0` leaves the graph
+    /// untouched and `1.0` is the same as [`Self::apply`]. Could be used to layer procedural
+    /// effects (recoil, hit reactions, etc.) on top of the output of an animation machine.
+    pub fn apply_with_weight(&self, graph: &mut Graph, weight: f32) {
+        let weight = clampf(weight, 0.0, 1.0);
+        self.apply_with(graph, |node, _, local_pose| {
+            let transform = node.local_transform_mut();
+            let position = transform.position().lerp(&local_pose.position, weight);
+            let rotation = transform.rotation().nlerp(&local_pose.rotation, weight);
+            let scale = transform.scale().lerp(&local_pose.scale, weight);
+            transform
+                .set_position(position)
+                .set_rotation(rotation)
+                .set_scale(scale);
+        });
     }
 
     /// Calls given callback function for each node and allows you to apply pose with your own
@@ -436,11 +579,15 @@ impl AnimationPose {
     where
         C: FnMut(&mut Node, Handle<Node>, &LocalPose),
     {
-        for (node, local_pose) in self.local_poses.iter() {
-            if node.is_none() {
-                Log::writeln(MessageKind::Error, "Invalid node handle found for animation pose, most likely it means that animation retargeting failed!".to_owned());
+        for (&handle, local_pose) in self.local_poses.iter() {
+            if let Some(node) = graph.try_get_mut(handle) {
+                callback(node, handle, local_pose);
             } else {
-                callback(&mut graph[*node], *node, local_pose);
+                Log::warn(format!(
+                    "Unable to apply local pose of node {}, the node does not exist! \
+                    Most likely it means that animation retargeting failed!",
+                    handle
+                ));
             }
         }
     }
@@ -623,6 +770,18 @@ impl Animation {
         }
     }
 
+    /// Removes redundant key frames of every track, see [`Track::compress`] for more info. Higher
+    /// tolerance gives smaller animations with less fidelity. Returns compression report.
+    pub fn compress(&mut self, tolerance: f32) -> CompressionReport {
+        let mut report = CompressionReport::default();
+        for track in self.tracks.iter_mut() {
+            let track_report = track.compress(tolerance);
+            report.key_frames_before += track_report.key_frames_before;
+            report.key_frames_after += track_report.key_frames_after;
+        }
+        report
+    }
+
     pub fn track_of(&self, handle: Handle<Node>) -> Option<&Track> {
         for track in self.tracks.iter() {
             if track.node == handle {
@@ -845,6 +1004,25 @@ impl AnimationContainer {
         self.pool.borrow_mut(handle)
     }
 
+    /// Returns a reference to an animation, or `None` if the handle is invalid (for example if
+    /// the animation was removed).
+    #[inline]
+    pub fn try_get(&self, handle: Handle<Animation>) -> Option<&Animation> {
+        self.pool.try_borrow(handle)
+    }
+
+    /// Returns a mutable reference to an animation, or `None` if the handle is invalid.
+    #[inline]
+    pub fn try_get_mut(&mut self, handle: Handle<Animation>) -> Option<&mut Animation> {
+        self.pool.try_borrow_mut(handle)
+    }
+
+    /// Checks whether the handle points to an existing animation.
+    #[inline]
+    pub fn is_valid_handle(&self, handle: Handle<Animation>) -> bool {
+        self.pool.is_valid_handle(handle)
+    }
+
     #[inline]
     pub fn retain<P>(&mut self, pred: P)
     where
@@ -916,3 +1094,71 @@ impl IndexMut<Handle<Animation>> for AnimationContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{AnimationPose, KeyFrame, LocalPose, Track},
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::Handle,
+        },
+    };
+
+    #[test]
+    fn additive_pose() {
+        let node = Handle::new(1, 1);
+        let pose = |position: Vector3<f32>, angle: f32| {
+            let mut pose = AnimationPose::default();
+            pose.add_local_pose(LocalPose::new(
+                node,
+                position,
+                UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle),
+                Vector3::new(1.0, 1.0, 1.0),
+            ));
+            pose
+        };
+
+        let base = pose(Vector3::new(1.0, 2.0, 3.0), 0.5);
+        let reference = pose(Vector3::new(0.0, 1.0, 0.0), 0.25);
+        let additive = pose(Vector3::new(0.0, 3.0, 0.0), 0.75);
+
+        // Zero weight gives exactly base pose.
+        let mut result = pose(Vector3::new(1.0, 2.0, 3.0), 0.5);
+        result.add_additive(&additive, &reference, 0.0);
+        let (result_pose, base_pose) = (&result.local_poses[&node], &base.local_poses[&node]);
+        assert_eq!(result_pose.position, base_pose.position);
+        assert_eq!(result_pose.rotation, base_pose.rotation);
+        assert_eq!(result_pose.scale, base_pose.scale);
+
+        result.add_additive(&additive, &reference, 1.0);
+        let result_pose = &result.local_poses[&node];
+        assert!((result_pose.position - Vector3::new(1.0, 4.0, 3.0)).norm() < 0.001);
+        assert!((result_pose.rotation.angle() - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn track_compression() {
+        let mut track = Track::new();
+        // Linear motion with one spike in the middle.
+        for (i, y) in [0.0, 0.0, 0.0, 5.0, 0.0, 0.0].iter().enumerate() {
+            let x = i as f32;
+            track.add_key_frame(KeyFrame::new(
+                x,
+                Vector3::new(x, *y, 0.0),
+                Vector3::new(1.0, 1.0, 1.0),
+                UnitQuaternion::default(),
+            ));
+        }
+
+        let report = track.compress(0.01);
+        assert_eq!(report.key_frames_before, 6);
+        assert_eq!(report.key_frames_after, 5);
+
+        // Sampling of reduced track gives the same result.
+        let pose = track.get_local_pose(1.0).unwrap();
+        assert!((pose.position() - Vector3::new(1.0, 0.0, 0.0)).norm() < 0.01);
+        let pose = track.get_local_pose(3.0).unwrap();
+        assert!((pose.position() - Vector3::new(3.0, 5.0, 0.0)).norm() < 0.01);
+    }
+}
@@ -9,11 +9,16 @@ use crate::{
             state::PipelineState,
         },
     },
-    resource::texture::{Texture, TextureState},
+    resource::texture::{Texture, TextureKind, TextureState},
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashMap;
-use std::{cell::RefCell, collections::hash_map::Entry, ops::Deref, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::hash_map::Entry,
+    ops::{Deref, Range},
+    rc::Rc,
+};
 
 #[derive(Default)]
 pub struct TextureCache {
@@ -63,6 +68,50 @@ impl TextureCache {
         }
     }
 
+    /// Uploads given rows of a rectangle texture into GPU memory, the rest of GPU texture stays the
+    /// same. Texture will be uploaded entirely if there is no GPU texture for it yet.
+    pub fn upload_rows(
+        &mut self,
+        state: &mut PipelineState,
+        texture: &Texture,
+        rows: Range<usize>,
+    ) -> Result<(), FrameworkError> {
+        let key = texture.key();
+
+        if !self.map.contains_key(&key) {
+            return self.upload(state, texture);
+        }
+
+        let texture = texture.state();
+
+        if let TextureState::Ok(texture) = texture.deref() {
+            let entry = self.map.get_mut(&key).unwrap();
+            let row_size = match texture.kind() {
+                TextureKind::Rectangle { height, .. } if height > 0 => {
+                    texture.first_mip_level_data().len() / height as usize
+                }
+                _ => {
+                    return Err(FrameworkError::Custom(
+                        "Only rows of rectangle textures could be uploaded!".to_string(),
+                    ))
+                }
+            };
+
+            entry.borrow_mut().bind_mut(state, 0).set_rows(
+                rows.start,
+                &texture.first_mip_level_data()[(rows.start * row_size)..(rows.end * row_size)],
+            )?;
+            // Data on GPU matches data of the resource now, there is no need to upload it again.
+            entry.value_hash = texture.data_hash();
+
+            Ok(())
+        } else {
+            Err(FrameworkError::Custom(
+                "Texture is not loaded yet!".to_string(),
+            ))
+        }
+    }
+
     pub fn get(
         &mut self,
         state: &mut PipelineState,
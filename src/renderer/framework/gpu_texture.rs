@@ -303,6 +303,52 @@ fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> us
     }
 }
 
+/// Returns type, format and internal format of pixels of given kind.
+fn gl_formats(pixel_kind: PixelKind) -> (u32, u32, u32) {
+    match pixel_kind {
+        PixelKind::F32 => (glow::FLOAT, glow::RED, glow::R32F),
+        PixelKind::F16 => (glow::FLOAT, glow::RED, glow::R16F),
+        PixelKind::D32F => (glow::FLOAT, glow::DEPTH_COMPONENT, glow::DEPTH_COMPONENT32F),
+        PixelKind::D16 => (
+            glow::UNSIGNED_SHORT,
+            glow::DEPTH_COMPONENT,
+            glow::DEPTH_COMPONENT16,
+        ),
+        PixelKind::D24S8 => (
+            glow::UNSIGNED_INT_24_8,
+            glow::DEPTH_STENCIL,
+            glow::DEPTH24_STENCIL8,
+        ),
+        PixelKind::RGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::RGBA8),
+        PixelKind::SRGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::SRGB8_ALPHA8),
+        PixelKind::RGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::RGB8),
+        PixelKind::SRGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::SRGB8),
+        PixelKind::RG8 => (glow::UNSIGNED_BYTE, glow::RG, glow::RG8),
+        PixelKind::R8 => (glow::UNSIGNED_BYTE, glow::RED, glow::R8),
+        PixelKind::R8UI => (glow::UNSIGNED_BYTE, glow::RED_INTEGER, glow::R8UI),
+        PixelKind::BGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::RGBA8),
+        PixelKind::BGR8 => (glow::UNSIGNED_BYTE, glow::BGR, glow::RGB8),
+        PixelKind::RG16 => (glow::UNSIGNED_SHORT, glow::RG, glow::RG16),
+        PixelKind::R16 => (glow::UNSIGNED_SHORT, glow::RED, glow::R16),
+        PixelKind::RGB16 => (glow::UNSIGNED_SHORT, glow::RGB, glow::RGB16),
+        PixelKind::RGBA16 => (glow::UNSIGNED_SHORT, glow::RGBA, glow::RGBA16),
+        PixelKind::RGB10A2 => (
+            glow::UNSIGNED_INT_2_10_10_10_REV,
+            glow::RGBA,
+            glow::RGB10_A2,
+        ),
+        PixelKind::DXT1RGB => (0, 0, GL_COMPRESSED_RGB_S3TC_DXT1_EXT),
+        PixelKind::DXT1RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
+        PixelKind::DXT3RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
+        PixelKind::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
+        PixelKind::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1),
+        PixelKind::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2),
+        PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F),
+        PixelKind::RGBA16F => (glow::FLOAT, glow::RGBA, glow::RGBA16F),
+        PixelKind::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F),
+    }
+}
+
 fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
     match pixel_kind {
         PixelKind::RGBA32F => 16 * length,
@@ -535,6 +581,61 @@ impl<'a> TextureBinding<'a> {
         self
     }
 
+    /// Replaces rows of the first mip level of a rectangle texture starting from `first_row`,
+    /// the rest of the texture stays the same. Data must contain whole rows. Compressed
+    /// textures are not supported.
+    pub fn set_rows(self, first_row: usize, data: &[u8]) -> Result<Self, FrameworkError> {
+        let width = match self.texture.kind {
+            GpuTextureKind::Rectangle { width, .. } => width,
+            _ => {
+                return Err(FrameworkError::Custom(
+                    "Only rows of rectangle textures could be replaced!".to_string(),
+                ))
+            }
+        };
+
+        let pixel_kind = self.texture.pixel_kind;
+        if pixel_kind.is_compressed() {
+            return Err(FrameworkError::Custom(
+                "Rows of compressed textures could not be replaced!".to_string(),
+            ));
+        }
+
+        let row_size = image_2d_size_bytes(pixel_kind, width, 1);
+        if row_size == 0 || data.len() % row_size != 0 {
+            return Err(FrameworkError::Custom(
+                "Texture data must contain whole rows!".to_string(),
+            ));
+        }
+
+        unsafe {
+            self.state
+                .set_texture(0, glow::TEXTURE_2D, Some(self.texture.texture));
+
+            let (type_, format, _) = gl_formats(pixel_kind);
+
+            if let Some(alignment) = pixel_kind.unpack_alignment() {
+                self.state
+                    .gl
+                    .pixel_store_i32(glow::UNPACK_ALIGNMENT, alignment);
+            }
+
+            self.state.gl.tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                0,
+                first_row as i32,
+                width as i32,
+                (data.len() / row_size) as i32,
+                format,
+                type_,
+                glow::PixelUnpackData::Slice(data),
+            );
+        }
+
+        Ok(self)
+    }
+
     pub fn set_data(
         self,
         kind: GpuTextureKind,
@@ -612,48 +713,7 @@ impl<'a> TextureBinding<'a> {
             self.state
                 .set_texture(0, target, Some(self.texture.texture));
 
-            let (type_, format, internal_format) = match pixel_kind {
-                PixelKind::F32 => (glow::FLOAT, glow::RED, glow::R32F),
-                PixelKind::F16 => (glow::FLOAT, glow::RED, glow::R16F),
-                PixelKind::D32F => (glow::FLOAT, glow::DEPTH_COMPONENT, glow::DEPTH_COMPONENT32F),
-                PixelKind::D16 => (
-                    glow::UNSIGNED_SHORT,
-                    glow::DEPTH_COMPONENT,
-                    glow::DEPTH_COMPONENT16,
-                ),
-                PixelKind::D24S8 => (
-                    glow::UNSIGNED_INT_24_8,
-                    glow::DEPTH_STENCIL,
-                    glow::DEPTH24_STENCIL8,
-                ),
-                PixelKind::RGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::RGBA8),
-                PixelKind::SRGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::SRGB8_ALPHA8),
-                PixelKind::RGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::RGB8),
-                PixelKind::SRGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::SRGB8),
-                PixelKind::RG8 => (glow::UNSIGNED_BYTE, glow::RG, glow::RG8),
-                PixelKind::R8 => (glow::UNSIGNED_BYTE, glow::RED, glow::R8),
-                PixelKind::R8UI => (glow::UNSIGNED_BYTE, glow::RED_INTEGER, glow::R8UI),
-                PixelKind::BGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::RGBA8),
-                PixelKind::BGR8 => (glow::UNSIGNED_BYTE, glow::BGR, glow::RGB8),
-                PixelKind::RG16 => (glow::UNSIGNED_SHORT, glow::RG, glow::RG16),
-                PixelKind::R16 => (glow::UNSIGNED_SHORT, glow::RED, glow::R16),
-                PixelKind::RGB16 => (glow::UNSIGNED_SHORT, glow::RGB, glow::RGB16),
-                PixelKind::RGBA16 => (glow::UNSIGNED_SHORT, glow::RGBA, glow::RGBA16),
-                PixelKind::RGB10A2 => (
-                    glow::UNSIGNED_INT_2_10_10_10_REV,
-                    glow::RGBA,
-                    glow::RGB10_A2,
-                ),
-                PixelKind::DXT1RGB => (0, 0, GL_COMPRESSED_RGB_S3TC_DXT1_EXT),
-                PixelKind::DXT1RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT1_EXT),
-                PixelKind::DXT3RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT3_EXT),
-                PixelKind::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT),
-                PixelKind::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1),
-                PixelKind::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2),
-                PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F),
-                PixelKind::RGBA16F => (glow::FLOAT, glow::RGBA, glow::RGBA16F),
-                PixelKind::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F),
-            };
+            let (type_, format, internal_format) = gl_formats(pixel_kind);
 
             let is_compressed = pixel_kind.is_compressed();
 
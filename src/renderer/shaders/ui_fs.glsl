@@ -3,6 +3,7 @@
 uniform sampler2D diffuseTexture;
 
 uniform bool isFont;
+uniform bool isSdfFont;
 uniform vec4 solidColor;
 uniform float opacity;
 
@@ -76,7 +77,18 @@ void main()
 
     if (isFont)
     {
-        fragColor.a *= diffuseColor.r;
+        if (isSdfFont)
+        {
+            // Distance field stores 0.5 on glyph edges, use screen-space derivative of
+            // the distance to get sharp edges at any scale.
+            float distance = diffuseColor.r;
+            float width = max(fwidth(distance), 0.0001);
+            fragColor.a *= smoothstep(0.5 - width, 0.5 + width, distance);
+        }
+        else
+        {
+            fragColor.a *= diffuseColor.r;
+        }
     }
     else
     {
@@ -11,6 +11,7 @@ use crate::{
     gui::{
         brush::Brush,
         draw::{CommandTexture, DrawingContext, SharedTexture},
+        ttf::{Font, FontRenderMode},
     },
     renderer::{
         framework::{
@@ -30,14 +31,72 @@ use crate::{
         RenderPassStatistics, TextureCache,
     },
     resource::texture::{Texture, TextureData, TextureKind, TexturePixelKind, TextureState},
+    utils::log::{Log, MessageKind},
 };
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
+/// Makes sure that the texture of the font contains actual atlas. Atlas could be changed by
+/// glyphs rasterized on demand. If the atlas keeps its size, only changed rows are uploaded,
+/// otherwise the whole texture is re-created and old one will be removed from the cache later.
+fn upload_font_atlas(font: &mut Font, state: &mut PipelineState, texture_cache: &mut TextureCache) {
+    let dirty_region = font.take_dirty_region();
+    let size = font.atlas_size();
+
+    if let (Some(dirty_region), Some(texture)) = (dirty_region, font.texture.clone()) {
+        if let Ok(texture) = texture.0.downcast::<Mutex<TextureState>>() {
+            let texture = Texture(Resource::from(texture));
+            let rows = dirty_region.y()..(dirty_region.y() + dirty_region.h());
+            let bytes = (rows.start * size)..(rows.end * size);
+
+            let updated = match *texture.state() {
+                TextureState::Ok(ref mut data) => match data.kind() {
+                    TextureKind::Rectangle { width, height }
+                        if width as usize == size && height as usize == size =>
+                    {
+                        data.modify().data_mut()[bytes.clone()]
+                            .copy_from_slice(&font.atlas_pixels()[bytes]);
+                        true
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+
+            if updated {
+                if let Err(e) = texture_cache.upload_rows(state, &texture, rows) {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!("Unable to upload rows of font atlas. Reason: {:?}", e),
+                    );
+                }
+                return;
+            }
+        }
+    }
+
+    if dirty_region.is_some() || font.texture.is_none() {
+        if let Some(details) = TextureData::from_bytes(
+            TextureKind::Rectangle {
+                width: size as u32,
+                height: size as u32,
+            },
+            TexturePixelKind::R8,
+            font.atlas_pixels().to_vec(),
+            false,
+        ) {
+            font.texture = Some(SharedTexture(Arc::new(Mutex::new(TextureState::Ok(
+                details,
+            )))));
+        }
+    }
+}
+
 struct UiShader {
     program: GpuProgram,
     wvp_matrix: UniformLocation,
     diffuse_texture: UniformLocation,
     is_font: UniformLocation,
+    is_sdf_font: UniformLocation,
     solid_color: UniformLocation,
     brush_type: UniformLocation,
     gradient_point_count: UniformLocation,
@@ -62,6 +121,7 @@ impl UiShader {
             diffuse_texture: program
                 .uniform_location(state, &ImmutableString::new("diffuseTexture"))?,
             is_font: program.uniform_location(state, &ImmutableString::new("isFont"))?,
+            is_sdf_font: program.uniform_location(state, &ImmutableString::new("isSdfFont"))?,
             solid_color: program.uniform_location(state, &ImmutableString::new("solidColor"))?,
             brush_type: program.uniform_location(state, &ImmutableString::new("brushType"))?,
             gradient_point_count: program
@@ -184,6 +244,7 @@ impl UiRenderer {
         for cmd in drawing_context.get_commands() {
             let mut diffuse_texture = white_dummy.clone();
             let mut is_font_texture = false;
+            let mut is_sdf_font_texture = false;
 
             let mut clip_bounds = cmd.clip_bounds;
             clip_bounds.position.x = clip_bounds.position.x.floor();
@@ -249,22 +310,7 @@ impl UiRenderer {
             match &cmd.texture {
                 CommandTexture::Font(font_arc) => {
                     let mut font = font_arc.0.lock().unwrap();
-                    if font.texture.is_none() {
-                        let size = font.atlas_size() as u32;
-                        if let Some(details) = TextureData::from_bytes(
-                            TextureKind::Rectangle {
-                                width: size,
-                                height: size,
-                            },
-                            TexturePixelKind::R8,
-                            font.atlas_pixels().to_vec(),
-                            false,
-                        ) {
-                            font.texture = Some(SharedTexture(Arc::new(Mutex::new(
-                                TextureState::Ok(details),
-                            ))));
-                        }
-                    }
+                    upload_font_atlas(&mut font, state, texture_cache);
                     let tex = font
                         .texture
                         .clone()
@@ -276,6 +322,7 @@ impl UiRenderer {
                         diffuse_texture = texture;
                     }
                     is_font_texture = true;
+                    is_sdf_font_texture = matches!(font.render_mode(), FontRenderMode::Sdf { .. });
                 }
                 CommandTexture::Texture(texture) => {
                     if let Ok(texture) = texture.clone().0.downcast::<Mutex<TextureState>>() {
@@ -328,6 +375,7 @@ impl UiRenderer {
                         .set_vector2(&shader.bounds_min, &cmd.bounds.position)
                         .set_vector2(&shader.bounds_max, &bounds_max)
                         .set_bool(&shader.is_font, is_font_texture)
+                        .set_bool(&shader.is_sdf_font, is_sdf_font_texture)
                         .set_i32(
                             &shader.brush_type,
                             match cmd.brush {
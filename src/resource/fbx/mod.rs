@@ -736,6 +736,22 @@ pub async fn load_to_scene<P: AsRef<Path>>(
     .await?;
     let conversion_time = now.elapsed().as_millis();
 
+    if let Some(tolerance) = model_import_options.animation_compression_tolerance {
+        for animation in scene.animations.iter_mut() {
+            let report = animation.compress(tolerance);
+            Log::writeln(
+                MessageKind::Information,
+                format!(
+                    "Animation of {:?} compressed from {} to {} key frames ({:.2}x)",
+                    path.as_ref(),
+                    report.key_frames_before,
+                    report.key_frames_after,
+                    report.ratio()
+                ),
+            );
+        }
+    }
+
     Log::writeln(MessageKind::Information,
                  format!("FBX {:?} loaded in {} ms\n\t- Parsing - {} ms\n\t- DOM Prepare - {} ms\n\t- Conversion - {} ms",
                          path.as_ref(), start_time.elapsed().as_millis(), parsing_time, dom_prepare_time, conversion_time));
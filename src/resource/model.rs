@@ -318,6 +318,11 @@ pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+
+    /// If set, animations of the model will be compressed on import with given tolerance. See
+    /// [`Animation::compress`] docs for more info.
+    #[serde(default)]
+    pub animation_compression_tolerance: Option<f32>,
 }
 
 impl ImportOptions for ModelImportOptions {}
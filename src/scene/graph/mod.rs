@@ -37,7 +37,10 @@ use crate::{
     resource::model::{Model, NodeMapping},
     scene::{
         dim2,
-        graph::physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+        graph::{
+            physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+            resolve::{ResolveIssueKind, ResolveReport},
+        },
         node::Node,
         sound::context::SoundContext,
         transform::TransformBuilder,
@@ -55,6 +58,7 @@ use std::{
 };
 
 pub mod physics;
+pub mod resolve;
 
 /// Graph performance statistics. Allows you to find out "hot" parts of the scene graph, which
 /// parts takes the most time to update.
@@ -586,11 +590,11 @@ impl Graph {
         dest_copy_handle
     }
 
-    fn restore_original_handles(&mut self) {
+    fn restore_original_handles(&mut self, report: &mut ResolveReport) {
         // Iterate over each node in the graph and resolve original handles. Original handle is a handle
         // to a node in resource from which a node was instantiated from. Also sync templated properties
         // if needed and copy surfaces from originals.
-        for node in self.pool.iter_mut() {
+        for (handle, node) in self.pool.pair_iter_mut() {
             if let Some(model) = node.resource() {
                 let model = model.state();
                 match *model {
@@ -633,7 +637,14 @@ impl Graph {
                             Log::warn(format!(
                                 "Unable to find original handle for node {}",
                                 node.name(),
-                            ))
+                            ));
+
+                            report.add(
+                                handle,
+                                node.name(),
+                                data.path.clone(),
+                                ResolveIssueKind::UnresolvedOriginalHandle,
+                            );
                         }
                     }
                     ResourceState::Pending { .. } => {
@@ -650,7 +661,7 @@ impl Graph {
         );
     }
 
-    fn remap_handles(&mut self, instances: &[(Handle<Node>, Model)]) {
+    fn remap_handles(&mut self, instances: &[(Handle<Node>, Model)], report: &mut ResolveReport) {
         for (instance_root, resource) in instances {
             // Prepare old -> new handle mapping first by walking over the graph
             // starting from instance root.
@@ -661,10 +672,27 @@ impl Graph {
                 if let Some(node_resource) = node.resource().as_ref() {
                     // We're interested only in instance nodes.
                     if node_resource == resource {
-                        let previous_mapping =
+                        // There must be no such node, otherwise mapping is ambiguous and we'll
+                        // keep the first one.
+                        if old_new_mapping.contains_key(&node.original_handle_in_resource) {
+                            let resource_path = resource.state().path().to_path_buf();
+
+                            Log::warn(format!(
+                                "Node {} of an instance of resource {} refers to an original node \
+                                that is already used by other node of the instance!",
+                                node.name(),
+                                resource_path.display()
+                            ));
+
+                            report.add(
+                                node_handle,
+                                node.name(),
+                                resource_path,
+                                ResolveIssueKind::RemapConflict,
+                            );
+                        } else {
                             old_new_mapping.insert(node.original_handle_in_resource, node_handle);
-                        // There must be no such node.
-                        assert!(previous_mapping.is_none());
+                        }
                     }
                 }
 
@@ -679,7 +707,11 @@ impl Graph {
         }
     }
 
-    fn restore_integrity(&mut self) -> Vec<(Handle<Node>, Model)> {
+    fn restore_integrity(
+        &mut self,
+        report: &mut ResolveReport,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Vec<(Handle<Node>, Model)> {
         Log::writeln(MessageKind::Information, "Checking integrity...".to_owned());
 
         // Check integrity - if a node was added in resource, it must be also added in the graph.
@@ -701,7 +733,9 @@ impl Graph {
         let instance_count = instances.len();
         let mut restored_count = 0;
 
-        for (instance_root, resource) in instances.iter().cloned() {
+        for (processed, (instance_root, resource)) in instances.iter().cloned().enumerate() {
+            progress(processed, instance_count);
+
             let model = resource.state();
             if let ResourceState::Ok(ref data) = *model {
                 let resource_graph = &data.get_scene().graph;
@@ -720,6 +754,13 @@ impl Graph {
                         ),
                     );
 
+                    report.add(
+                        instance_root,
+                        instance.name(),
+                        data.path.clone(),
+                        ResolveIssueKind::MissingInstanceRoot,
+                    );
+
                     continue;
                 }
 
@@ -753,6 +794,13 @@ impl Graph {
 
                         restored_count += old_to_new_mapping.len();
 
+                        report.add(
+                            copy,
+                            resource_node.name(),
+                            data.path.clone(),
+                            ResolveIssueKind::MissingInstance,
+                        );
+
                         // Link it with existing node.
                         if resource_node.parent().is_some() {
                             let parent = self.find(instance_root, &mut |n| {
@@ -784,16 +832,24 @@ impl Graph {
             ),
         );
 
+        progress(instance_count, instance_count);
+
         instances
     }
 
-    pub(in crate) fn resolve(&mut self) {
+    /// Restores connections between instances and resources they were instantiated from.
+    /// Returns a report with every problem that was found. `progress` is called with the
+    /// amount of processed and total resource instances while restoring integrity of the graph,
+    /// which is the longest part of resolve for scenes with lots of instances.
+    pub(in crate) fn resolve(&mut self, progress: &mut dyn FnMut(usize, usize)) -> ResolveReport {
         Log::writeln(MessageKind::Information, "Resolving graph...".to_owned());
 
+        let mut report = ResolveReport::default();
+
         self.update_hierarchical_data();
-        self.restore_original_handles();
-        let instances = self.restore_integrity();
-        self.remap_handles(&instances);
+        self.restore_original_handles(&mut report);
+        let instances = self.restore_integrity(&mut report, progress);
+        self.remap_handles(&instances, &mut report);
 
         // Update cube maps for sky boxes.
         for node in self.linear_iter_mut() {
@@ -808,6 +864,8 @@ impl Graph {
             MessageKind::Information,
             "Graph resolved successfully!".to_owned(),
         );
+
+        report
     }
 
     /// Calculates local and global transform, global visibility for each node in graph.
@@ -1175,6 +1233,81 @@ impl Graph {
         }
     }
 
+    /// Create a graph depth traversal iterator which will emit *handles* to nodes, but won't
+    /// descend into children of nodes for which `prune` returns `true`. Pruned nodes are still
+    /// emitted. For example, to treat resource instances as opaque units:
+    ///
+    /// ```no_run
+    /// # use fyrox::scene::graph::Graph;
+    /// fn print_top_level_nodes(graph: &Graph) {
+    ///     for handle in graph.traverse_handle_iter_pruned(graph.get_root(), |node| {
+    ///         node.is_resource_instance_root()
+    ///     }) {
+    ///         println!("{}", graph[handle].name());
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// This method allocates temporal array so it is not cheap! Should not be
+    /// used on each frame.
+    pub fn traverse_handle_iter_pruned<F>(
+        &self,
+        from: Handle<Node>,
+        mut prune: F,
+    ) -> GraphFilteredHandleTraverseIterator<impl FnMut(&Node) -> bool>
+    where
+        F: FnMut(&Node) -> bool,
+    {
+        self.traverse_handle_filtered(from, move |node| !prune(node))
+    }
+
+    /// Create a graph depth traversal iterator which will emit references to nodes, but won't
+    /// descend into children of nodes for which `descend` returns `false`. Such nodes are still
+    /// emitted, only their subtrees are skipped. Could be used to stop at prefab roots, to skip
+    /// hidden branches, etc.
+    ///
+    /// # Notes
+    ///
+    /// This method allocates temporal array so it is not cheap! Should not be
+    /// used on each frame.
+    pub fn traverse_filtered<F>(
+        &self,
+        from: Handle<Node>,
+        descend: F,
+    ) -> GraphFilteredTraverseIterator<F>
+    where
+        F: FnMut(&Node) -> bool,
+    {
+        GraphFilteredTraverseIterator {
+            graph: self,
+            stack: vec![from],
+            descend,
+        }
+    }
+
+    /// Same as [`Self::traverse_filtered`], but emits *handles* to nodes.
+    ///
+    /// # Notes
+    ///
+    /// This method allocates temporal array so it is not cheap! Should not be
+    /// used on each frame.
+    pub fn traverse_handle_filtered<F>(
+        &self,
+        from: Handle<Node>,
+        descend: F,
+    ) -> GraphFilteredHandleTraverseIterator<F>
+    where
+        F: FnMut(&Node) -> bool,
+    {
+        GraphFilteredHandleTraverseIterator {
+            graph: self,
+            stack: vec![from],
+            descend,
+        }
+    }
+
     /// Creates deep copy of graph. Allows filtering while copying, returns copy and
     /// old-to-new node mapping.
     pub fn clone<F>(&self, filter: &mut F) -> (Self, FxHashMap<Handle<Node>, Handle<Node>>)
@@ -1326,6 +1459,68 @@ impl<'a> Iterator for GraphHandleTraverseIterator<'a> {
     }
 }
 
+/// Iterator that traverses tree in depth and returns shared references to nodes, skipping
+/// descendants of nodes that do not pass a predicate.
+pub struct GraphFilteredTraverseIterator<'a, F> {
+    graph: &'a Graph,
+    stack: Vec<Handle<Node>>,
+    descend: F,
+}
+
+impl<'a, F> Iterator for GraphFilteredTraverseIterator<'a, F>
+where
+    F: FnMut(&Node) -> bool,
+{
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(handle) = self.stack.pop() {
+            let node = &self.graph[handle];
+
+            if (self.descend)(node) {
+                for child_handle in node.children() {
+                    self.stack.push(*child_handle);
+                }
+            }
+
+            return Some(node);
+        }
+
+        None
+    }
+}
+
+/// Iterator that traverses tree in depth and returns handles to nodes, skipping descendants
+/// of nodes that do not pass a predicate.
+pub struct GraphFilteredHandleTraverseIterator<'a, F> {
+    graph: &'a Graph,
+    stack: Vec<Handle<Node>>,
+    descend: F,
+}
+
+impl<'a, F> Iterator for GraphFilteredHandleTraverseIterator<'a, F>
+where
+    F: FnMut(&Node) -> bool,
+{
+    type Item = Handle<Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(handle) = self.stack.pop() {
+            let node = &self.graph[handle];
+
+            if (self.descend)(node) {
+                for child_handle in node.children() {
+                    self.stack.push(*child_handle);
+                }
+            }
+
+            return Some(handle);
+        }
+
+        None
+    }
+}
+
 impl Visit for Graph {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         visitor.enter_region(name)?;
@@ -1368,4 +1563,37 @@ mod test {
         graph.add_node(Node::Base(Base::default()));
         assert_eq!(graph.pool.alive_count(), 4);
     }
+
+    #[test]
+    fn pruned_traversal_test() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(Node::Base(Base::default()));
+        let b = graph.add_node(Node::Base(Base::default()));
+        let c = graph.add_node(Node::Base(Base::default()));
+        graph.link_nodes(b, a);
+        graph.link_nodes(c, b);
+
+        let handles = graph
+            .traverse_handle_iter_pruned(graph.get_root(), |node| node.children().contains(&c))
+            .collect::<Vec<_>>();
+        assert_eq!(handles, vec![graph.get_root(), a, b]);
+    }
+
+    #[test]
+    fn filtered_traversal_test() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(Node::Base(Base::default()));
+        let b = graph.add_node(Node::Base(Base::default()));
+        graph.link_nodes(b, a);
+
+        let count = graph
+            .traverse_filtered(graph.get_root(), |node| node.children().is_empty())
+            .count();
+        assert_eq!(count, 1);
+
+        let handles = graph
+            .traverse_handle_filtered(graph.get_root(), |node| !node.children().contains(&b))
+            .collect::<Vec<_>>();
+        assert_eq!(handles, vec![graph.get_root(), a]);
+    }
 }
@@ -0,0 +1,71 @@
+//! Structured diagnostics of graph resolution. See [`ResolveReport`] docs for more info.
+
+use crate::{core::pool::Handle, scene::node::Node};
+use std::path::PathBuf;
+
+/// Describes what went wrong with a node during graph resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveIssueKind {
+    /// Original node of an instance cannot be found in the resource, the node won't inherit
+    /// properties from the resource.
+    UnresolvedOriginalHandle,
+    /// Instance root does not have original node in the resource, integrity of the instance
+    /// cannot be checked.
+    MissingInstanceRoot,
+    /// A node was added to the resource after the instance was created, the node was instantiated
+    /// and attached to the instance.
+    MissingInstance,
+    /// Multiple nodes of an instance refer to the same original node, so handles of the instance
+    /// cannot be remapped unambiguously. Only the first node was used for remapping.
+    RemapConflict,
+}
+
+/// Single problem found during graph resolution.
+#[derive(Debug, Clone)]
+pub struct ResolveIssue {
+    /// Handle of a node in the graph that has the problem. Could be [`Handle::NONE`] if there is
+    /// no such node (for example if it was not instantiated).
+    pub node: Handle<Node>,
+    /// Name of the node.
+    pub node_name: String,
+    /// Path of the resource the node was instantiated from.
+    pub resource_path: PathBuf,
+    /// Reason of the problem.
+    pub kind: ResolveIssueKind,
+}
+
+/// A report of graph resolution, lists every problem found while restoring connections between
+/// instances and resources. Every issue is also written to the log. Editors could use the report
+/// to show the health of a scene built from broken prefabs.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveReport {
+    /// List of all problems in the order they were found.
+    pub issues: Vec<ResolveIssue>,
+}
+
+impl ResolveReport {
+    /// Returns `true` if there were no problems.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Moves issues of the other report to the end of this report.
+    pub fn append(&mut self, other: ResolveReport) {
+        self.issues.extend(other.issues)
+    }
+
+    pub(in crate) fn add(
+        &mut self,
+        node: Handle<Node>,
+        node_name: &str,
+        resource_path: PathBuf,
+        kind: ResolveIssueKind,
+    ) {
+        self.issues.push(ResolveIssue {
+            node,
+            node_name: node_name.to_owned(),
+            resource_path,
+            kind,
+        })
+    }
+}
@@ -42,7 +42,7 @@ use crate::{
     resource::texture::Texture,
     scene::{
         debug::SceneDrawingContext,
-        graph::Graph,
+        graph::{resolve::ResolveReport, Graph},
         mesh::buffer::{
             VertexAttributeDataType, VertexAttributeDescriptor, VertexAttributeUsage,
             VertexWriteTrait,
@@ -249,6 +249,10 @@ pub struct Scene {
     /// to false for menu's scene and when you need to open a menu - set it to true and
     /// set `enabled` flag to false for level's scene.
     pub enabled: bool,
+
+    /// Report of the last resolve, contains every problem that was found while restoring
+    /// connections between instances and resources. Not serialized.
+    resolve_report: ResolveReport,
 }
 
 impl Default for Scene {
@@ -263,6 +267,7 @@ impl Default for Scene {
             performance_statistics: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
             enabled: true,
+            resolve_report: Default::default(),
         }
     }
 }
@@ -336,6 +341,20 @@ impl SceneLoader {
 
     /// Finishes scene loading.
     pub async fn finish(self, resource_manager: ResourceManager) -> Scene {
+        self.finish_with_progress(resource_manager, |_, _| {}).await
+    }
+
+    /// Finishes scene loading and calls `progress` with the amount of processed and total
+    /// resource instances while the scene is being resolved. Could be used to update a loading
+    /// screen for scenes with hundreds of prefab instances.
+    pub async fn finish_with_progress<F>(
+        self,
+        resource_manager: ResourceManager,
+        mut progress: F,
+    ) -> Scene
+    where
+        F: FnMut(usize, usize),
+    {
         let mut scene = self.scene;
 
         // Collect all model resources and wait for them. This step is crucial, because
@@ -395,7 +414,7 @@ impl SceneLoader {
         join_all(animation_resources).await;
 
         // And do resolve to extract correct graphical data and so on.
-        scene.resolve();
+        scene.resolve_with_progress(&mut progress);
 
         scene
     }
@@ -421,6 +440,7 @@ impl Scene {
             performance_statistics: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
             enabled: true,
+            resolve_report: Default::default(),
         }
     }
 
@@ -446,10 +466,22 @@ impl Scene {
         self.graph.remove_node(handle)
     }
 
+    /// Returns report of the last resolve of the scene. The scene is resolved when it is loaded
+    /// and when any resource it depends on is reloaded.
+    pub fn resolve_report(&self) -> &ResolveReport {
+        &self.resolve_report
+    }
+
     pub(in crate) fn resolve(&mut self) {
+        self.resolve_with_progress(&mut |_, _| {})
+    }
+
+    /// Resolves the scene and reports progress of the graph resolve, see
+    /// [`SceneLoader::finish_with_progress`] for more info.
+    pub(in crate) fn resolve_with_progress(&mut self, progress: &mut dyn FnMut(usize, usize)) {
         Log::writeln(MessageKind::Information, "Starting resolve...".to_owned());
 
-        self.graph.resolve();
+        self.resolve_report = self.graph.resolve(progress);
         self.animations.resolve(&self.graph);
         self.graph.update_hierarchical_data();
 
@@ -623,6 +655,7 @@ impl Scene {
                 performance_statistics: Default::default(),
                 ambient_lighting_color: self.ambient_lighting_color,
                 enabled: self.enabled,
+                resolve_report: self.resolve_report.clone(),
             },
             old_new_map,
         )
@@ -22,7 +22,8 @@ lazy_static! {
     static ref LOG: Mutex<Log> = Mutex::new(Log {
         #[cfg(not(target_arch = "wasm32"))]
         file: std::fs::File::create("fyrox.log").unwrap(),
-        verbosity: MessageKind::Information
+        verbosity: MessageKind::Information,
+        sink: None,
     });
 }
 
@@ -48,16 +49,25 @@ impl MessageKind {
     }
 }
 
+/// A function that receives every message that passed verbosity filter, see [`Log::set_sink`].
+pub type LogSink = Box<dyn FnMut(MessageKind, &str) + Send>;
+
 /// See module docs.
 pub struct Log {
     #[cfg(not(target_arch = "wasm32"))]
     file: std::fs::File,
     verbosity: MessageKind,
+    sink: Option<LogSink>,
 }
 
 impl Log {
     fn write_internal(&mut self, kind: MessageKind, mut msg: String) {
         if kind as u32 >= self.verbosity as u32 {
+            if let Some(sink) = self.sink.as_mut() {
+                sink(kind, &msg);
+                return;
+            }
+
             msg.insert_str(0, kind.as_str());
 
             #[cfg(target_arch = "wasm32")]
@@ -103,11 +113,34 @@ impl Log {
         Self::writeln(MessageKind::Error, msg)
     }
 
-    /// Sets verbosity level.
+    /// Sets verbosity level - minimal kind of messages that will be written, every message with
+    /// lower kind will be ignored. For example [`MessageKind::Warning`] suppresses informational
+    /// messages.
     pub fn set_verbosity(kind: MessageKind) {
         LOG.lock().unwrap().verbosity = kind;
     }
 
+    /// Returns current verbosity level.
+    pub fn verbosity() -> MessageKind {
+        LOG.lock().unwrap().verbosity
+    }
+
+    /// Redirects every message that passed verbosity filter to the given sink instead of console
+    /// and file. Messages are passed without the kind prefix. The sink can be called from any
+    /// thread, but only from one at a time.
+    ///
+    /// # Notes
+    ///
+    /// The sink must not write to the log itself, it will cause a deadlock.
+    pub fn set_sink(sink: LogSink) {
+        LOG.lock().unwrap().sink = Some(sink);
+    }
+
+    /// Removes the sink (if any), so the messages will be written to console and file again.
+    pub fn reset_sink() {
+        LOG.lock().unwrap().sink = None;
+    }
+
     /// Allows you to verify that the result of operation is Ok, or print the error in the log.
     ///
     /// # Use cases
@@ -12,10 +12,12 @@ pub mod uvgen;
 
 use crate::core::algebra::Vector2;
 use crate::{
-    event::{ElementState, ModifiersState, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event::{
+        ElementState, ModifiersState, MouseScrollDelta, TouchPhase, VirtualKeyCode, WindowEvent,
+    },
     gui::{
-        draw,
-        message::{ButtonState, KeyCode, KeyboardModifiers, OsEvent},
+        self, draw,
+        message::{ButtonState, KeyCode, KeyboardModifiers, OsEvent, WheelDelta},
     },
     resource::texture::Texture,
 };
@@ -264,10 +266,12 @@ pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
             position: Vector2::new(position.x as f32, position.y as f32),
         }),
         WindowEvent::MouseWheel { delta, .. } => match delta {
-            MouseScrollDelta::LineDelta(x, y) => Some(OsEvent::MouseWheel(*x, *y)),
-            MouseScrollDelta::PixelDelta(pos) => {
-                Some(OsEvent::MouseWheel(pos.x as f32, pos.y as f32))
+            MouseScrollDelta::LineDelta(x, y) => {
+                Some(OsEvent::MouseWheel(WheelDelta::Lines(Vector2::new(*x, *y))))
             }
+            MouseScrollDelta::PixelDelta(pos) => Some(OsEvent::MouseWheel(WheelDelta::Pixels(
+                Vector2::new(pos.x as f32, pos.y as f32),
+            ))),
         },
         WindowEvent::MouseInput { state, button, .. } => Some(OsEvent::MouseInput {
             button: translate_button(*button),
@@ -276,6 +280,16 @@ pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
         &WindowEvent::ModifiersChanged(modifiers) => Some(OsEvent::KeyboardModifiers(
             translate_keyboard_modifiers(modifiers),
         )),
+        WindowEvent::Touch(touch) => Some(OsEvent::Touch {
+            id: touch.id,
+            phase: match touch.phase {
+                TouchPhase::Started => gui::message::TouchPhase::Started,
+                TouchPhase::Moved => gui::message::TouchPhase::Moved,
+                TouchPhase::Ended => gui::message::TouchPhase::Ended,
+                TouchPhase::Cancelled => gui::message::TouchPhase::Cancelled,
+            },
+            position: Vector2::new(touch.location.x as f32, touch.location.y as f32),
+        }),
         _ => None,
     }
 }